@@ -0,0 +1,1027 @@
+//! Conversion between ADF documents and CommonMark with the GitHub table and
+//! strikethrough extensions.
+//!
+//! Markdown has no syntax for some ADF nodes so a few conventions are used,
+//! all of which are read back by `Document::from_markdown`:
+//!
+//! * mentions are links to `mention:<account id>`, e.g. `[@Jane](mention:5b10)`
+//! * inline cards are autolinks, e.g. `<https://example.com>`
+//! * panels are block quotes starting with the panel type, e.g. `> [!WARNING]`
+//! * media is an image linking to `media:<id>` or the external url
+//! * underline, colour and sub/superscript use inline HTML
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::adf::*;
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag};
+use std::collections::BTreeMap;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+impl Document {
+    /// Parses Markdown into a document
+    pub fn from_markdown(markdown: &str) -> Document {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+
+        let mut builder = Builder::default();
+        for event in Parser::new_ext(markdown, options) {
+            builder.event(event);
+        }
+
+        Document::new(wrap_inline(builder.root))
+    }
+
+    /// Writes the document as Markdown. Nodes Markdown can not represent are
+    /// written as their text content.
+    pub fn to_markdown(&self) -> String {
+        write_blocks(&self.content)
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+const MENTION_SCHEME: &str = "mention:";
+const MEDIA_SCHEME: &str = "media:";
+const HARD_BREAK: &str = "\\\n";
+const CELL_BREAK: &str = "<br>";
+
+const PANEL_TYPES: &[(PanelType, &str)] = &[
+    (PanelType::Info, "INFO"),
+    (PanelType::Note, "NOTE"),
+    (PanelType::Warning, "WARNING"),
+    (PanelType::Success, "SUCCESS"),
+    (PanelType::Error, "ERROR"),
+    (PanelType::Custom, "CUSTOM"),
+];
+
+// ----------------------------------------------------------------------------
+// Markdown to ADF
+// ----------------------------------------------------------------------------
+struct Frame<'a> {
+    tag: Tag<'a>,
+    content: Vec<Node>,
+}
+
+#[derive(Default)]
+struct Builder<'a> {
+    stack: Vec<Frame<'a>>,
+    root: Vec<Node>,
+    marks: Vec<Mark>,
+    in_table_head: bool,
+}
+
+impl<'a> Builder<'a> {
+    fn event(&mut self, event: Event<'a>) {
+        match event {
+            Event::Start(Tag::Emphasis) => self.marks.push(Mark::Em),
+            Event::Start(Tag::Strong) => self.marks.push(Mark::Strong),
+            Event::Start(Tag::Strikethrough) => self.marks.push(Mark::Strike),
+            Event::End(Tag::Emphasis) => self.close_mark(|m| *m == Mark::Em),
+            Event::End(Tag::Strong) => self.close_mark(|m| *m == Mark::Strong),
+            Event::End(Tag::Strikethrough) => self.close_mark(|m| *m == Mark::Strike),
+            Event::Start(Tag::Link(kind, dest, title)) if !is_card_or_mention(kind, &dest) => {
+                self.marks.push(Mark::Link {
+                    attrs: LinkAttrs {
+                        href: dest.to_string(),
+                        title: non_empty(&title),
+                        other: BTreeMap::new(),
+                    },
+                })
+            }
+            Event::End(Tag::Link(kind, dest, _)) if !is_card_or_mention(kind, &dest) => {
+                self.close_mark(|m| matches!(m, Mark::Link { .. }))
+            }
+            Event::Start(Tag::TableHead) => {
+                self.in_table_head = true;
+                self.open(Tag::TableHead);
+            }
+            Event::Start(tag) => self.open(tag),
+            Event::End(_) => self.close(),
+            Event::Text(text) => {
+                let in_code = matches!(
+                    self.stack.last(),
+                    Some(Frame {
+                        tag: Tag::CodeBlock(_),
+                        ..
+                    })
+                );
+
+                if in_code {
+                    self.push(Node::text(text.to_string()));
+                } else {
+                    self.push_text(&text, self.marks.clone());
+                }
+            }
+            Event::Code(code) => {
+                let mut marks = self.marks.clone();
+                marks.push(Mark::Code);
+                self.push_text(&code, marks);
+            }
+            Event::Html(html) => self.html(&html),
+            Event::SoftBreak => self.push_text(" ", self.marks.clone()),
+            Event::HardBreak => self.push(Node::hard_break()),
+            Event::Rule => self.push(Node::rule()),
+            Event::FootnoteReference(label) => self.push_text(&label, self.marks.clone()),
+            Event::TaskListMarker(_) => {}
+        }
+    }
+
+    /// Inline HTML is only understood for the marks Markdown has no syntax
+    /// for, anything else is kept as text.
+    fn html(&mut self, html: &str) {
+        let tag = html.trim();
+
+        match tag {
+            "<u>" => self.marks.push(Mark::Underline),
+            "</u>" => self.close_mark(|m| *m == Mark::Underline),
+            "<sub>" => self.marks.push(subsup(SubsupType::Sub)),
+            "<sup>" => self.marks.push(subsup(SubsupType::Sup)),
+            "</sub>" | "</sup>" => self.close_mark(|m| matches!(m, Mark::Subsup { .. })),
+            "</span>" => self.close_mark(|m| matches!(m, Mark::TextColor { .. })),
+            "<br>" | "<br/>" | "<br />" => self.push(Node::hard_break()),
+            _ => match span_colour(tag) {
+                Some(color) => self.marks.push(Mark::TextColor {
+                    attrs: TextColorAttrs {
+                        color,
+                        other: BTreeMap::new(),
+                    },
+                }),
+                None => self.push_text(html, self.marks.clone()),
+            },
+        }
+    }
+
+    fn open(&mut self, tag: Tag<'a>) {
+        self.stack.push(Frame {
+            tag,
+            content: vec![],
+        });
+    }
+
+    fn close(&mut self) {
+        let frame = match self.stack.pop() {
+            Some(f) => f,
+            None => return,
+        };
+
+        let content = frame.content;
+
+        match frame.tag {
+            Tag::Paragraph => {
+                if !content.is_empty() && content.iter().all(|n| matches!(n, Node::Media(_))) {
+                    for media in content {
+                        self.push(Node::MediaSingle(MediaSingle {
+                            attrs: None,
+                            content: vec![media],
+                            other: BTreeMap::new(),
+                        }));
+                    }
+                } else {
+                    self.push(Node::paragraph(merge_text(media_as_links(content))));
+                }
+            }
+            Tag::Heading(level, _, _) => self.push(Node::Heading(Heading {
+                attrs: HeadingAttrs {
+                    level: level as u8,
+                    other: BTreeMap::new(),
+                },
+                content: merge_text(content),
+                other: BTreeMap::new(),
+            })),
+            Tag::BlockQuote => {
+                let mut content = wrap_inline(content);
+
+                match take_panel_type(&mut content) {
+                    Some(panel_type) => self.push(Node::Panel(Panel {
+                        attrs: PanelAttrs {
+                            panel_type,
+                            other: BTreeMap::new(),
+                        },
+                        content,
+                        other: BTreeMap::new(),
+                    })),
+                    None => self.push(Node::Blockquote(Container::new(content))),
+                }
+            }
+            Tag::CodeBlock(kind) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().map(|l| l.to_string())
+                    }
+                    CodeBlockKind::Indented => None,
+                };
+
+                let mut code = plain_text(&content);
+                if code.ends_with('\n') {
+                    code.pop();
+                }
+
+                self.push(Node::CodeBlock(CodeBlock {
+                    attrs: language.map(|language| CodeBlockAttrs {
+                        language: Some(language),
+                        other: BTreeMap::new(),
+                    }),
+                    content: if code.is_empty() {
+                        vec![]
+                    } else {
+                        vec![Node::text(code)]
+                    },
+                    other: BTreeMap::new(),
+                }))
+            }
+            Tag::List(Some(start)) => self.push(Node::OrderedList(OrderedList {
+                attrs: if start == 1 {
+                    None
+                } else {
+                    Some(OrderedListAttrs {
+                        order: Some(start),
+                        other: BTreeMap::new(),
+                    })
+                },
+                content,
+                other: BTreeMap::new(),
+            })),
+            Tag::List(None) => self.push(Node::BulletList(Container::new(content))),
+            Tag::Item => self.push(Node::ListItem(Container::new(wrap_inline(content)))),
+            Tag::Table(_) => self.push(Node::Table(Table {
+                attrs: None,
+                content,
+                other: BTreeMap::new(),
+            })),
+            Tag::TableHead => {
+                self.in_table_head = false;
+                self.push(Node::TableRow(Container::new(content)));
+            }
+            Tag::TableRow => self.push(Node::TableRow(Container::new(content))),
+            Tag::TableCell => {
+                let cell = TableCell {
+                    attrs: None,
+                    content: vec![Node::paragraph(merge_text(content))],
+                    other: BTreeMap::new(),
+                };
+
+                if self.in_table_head {
+                    self.push(Node::TableHeader(cell));
+                } else {
+                    self.push(Node::TableCell(cell));
+                }
+            }
+            Tag::Image(_, dest, title) => {
+                let alt = plain_text(&content);
+                let (media_type, id, url) = match dest.strip_prefix(MEDIA_SCHEME) {
+                    Some(id) => (MediaType::File, Some(id.to_string()), None),
+                    None => (MediaType::External, None, Some(dest.to_string())),
+                };
+
+                let mut other = BTreeMap::new();
+                if !title.is_empty() {
+                    other.insert("title".to_string(), title.to_string().into());
+                }
+
+                self.push(Node::Media(Media {
+                    attrs: MediaAttrs {
+                        media_type,
+                        collection: id.as_ref().map(|_| String::new()),
+                        id,
+                        url,
+                        alt: non_empty(&alt),
+                        width: None,
+                        height: None,
+                        other,
+                    },
+                    other: BTreeMap::new(),
+                }))
+            }
+            Tag::Link(_, dest, _) => {
+                let text = plain_text(&content);
+
+                match dest.strip_prefix(MENTION_SCHEME) {
+                    Some(id) => self.push(Node::Mention(Mention {
+                        attrs: MentionAttrs {
+                            id: id.to_string(),
+                            text: non_empty(&text),
+                            ..Default::default()
+                        },
+                        other: BTreeMap::new(),
+                    })),
+                    None => self.push(Node::InlineCard(InlineCard {
+                        attrs: InlineCardAttrs {
+                            url: Some(dest.to_string()),
+                            ..Default::default()
+                        },
+                        other: BTreeMap::new(),
+                    })),
+                }
+            }
+            Tag::FootnoteDefinition(_) => {
+                for node in wrap_inline(content) {
+                    self.push(node);
+                }
+            }
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough => {}
+        }
+    }
+
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(frame) => frame.content.push(node),
+            None => self.root.push(node),
+        }
+    }
+
+    fn push_text(&mut self, text: &str, marks: Vec<Mark>) {
+        self.push(Node::Text(Text {
+            text: text.to_string(),
+            marks,
+            ..Default::default()
+        }));
+    }
+
+    fn close_mark<F>(&mut self, f: F)
+    where
+        F: Fn(&Mark) -> bool,
+    {
+        if let Some(i) = self.marks.iter().rposition(f) {
+            self.marks.remove(i);
+        }
+    }
+}
+
+fn is_card_or_mention(kind: LinkType, dest: &str) -> bool {
+    kind == LinkType::Autolink || dest.starts_with(MENTION_SCHEME)
+}
+
+fn subsup(kind: SubsupType) -> Mark {
+    Mark::Subsup {
+        attrs: SubsupAttrs {
+            kind,
+            other: BTreeMap::new(),
+        },
+    }
+}
+
+/// Reads the colour out of `<span style="color: #ff5630">`
+fn span_colour(tag: &str) -> Option<String> {
+    let style = tag.strip_prefix("<span style=\"")?.strip_suffix("\">")?;
+    let color = style
+        .trim()
+        .strip_prefix("color:")?
+        .trim()
+        .trim_end_matches(';');
+
+    Some(color.to_string())
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s.to_string())
+    }
+}
+
+/// The text of a list of inline nodes without any formatting
+fn plain_text(nodes: &[Node]) -> String {
+    nodes
+        .iter()
+        .map(|n| match n {
+            Node::Text(t) => t.text.clone(),
+            Node::HardBreak(_) => "\n".to_string(),
+            n => plain_text(n.content()),
+        })
+        .collect()
+}
+
+/// Joins neighbouring text nodes that have the same marks
+fn merge_text(nodes: Vec<Node>) -> Vec<Node> {
+    let mut merged: Vec<Node> = Vec::with_capacity(nodes.len());
+
+    for node in nodes {
+        if let (Some(Node::Text(last)), Node::Text(next)) = (merged.last_mut(), &node) {
+            if last.marks == next.marks {
+                last.text.push_str(&next.text);
+                continue;
+            }
+        }
+
+        merged.push(node);
+    }
+
+    merged
+}
+
+/// Wraps runs of inline nodes in paragraphs, tight list items and the end
+/// of a document have text without a surrounding paragraph
+fn wrap_inline(nodes: Vec<Node>) -> Vec<Node> {
+    let mut blocks = vec![];
+    let mut run = vec![];
+
+    for node in nodes {
+        if node.is_inline() {
+            run.push(node);
+        } else {
+            if !run.is_empty() {
+                blocks.push(Node::paragraph(merge_text(std::mem::take(&mut run))));
+            }
+            blocks.push(node);
+        }
+    }
+
+    if !run.is_empty() {
+        blocks.push(Node::paragraph(merge_text(run)));
+    }
+
+    blocks
+}
+
+/// Images mixed in with text can not be media, keep them as links instead
+fn media_as_links(nodes: Vec<Node>) -> Vec<Node> {
+    nodes
+        .into_iter()
+        .map(|n| match n {
+            Node::Media(m) => {
+                let href = match (m.attrs.url, m.attrs.id) {
+                    (Some(url), _) => url,
+                    (None, Some(id)) => format!("{}{}", MEDIA_SCHEME, id),
+                    (None, None) => String::new(),
+                };
+
+                Node::Text(Text {
+                    text: m.attrs.alt.unwrap_or_else(|| href.clone()),
+                    marks: vec![Mark::Link {
+                        attrs: LinkAttrs {
+                            href,
+                            ..Default::default()
+                        },
+                    }],
+                    ..Default::default()
+                })
+            }
+            n => n,
+        })
+        .collect()
+}
+
+/// Removes a leading "[!WARNING]" from a block quote returning the panel type
+fn take_panel_type(content: &mut Vec<Node>) -> Option<PanelType> {
+    let paragraph = match content.first_mut() {
+        Some(Node::Paragraph(p)) => p,
+        _ => return None,
+    };
+
+    let text = match paragraph.content.first_mut() {
+        Some(Node::Text(t)) if t.marks.is_empty() => t,
+        _ => return None,
+    };
+
+    let end = text.text.find(']')?;
+    let name = text.text.strip_prefix("[!")?.get(..end - 2)?;
+    let panel_type = PANEL_TYPES
+        .iter()
+        .find(|(_, n)| n.eq_ignore_ascii_case(name))
+        .map(|(t, _)| *t)?;
+
+    text.text = text.text[end + 1..].trim_start().to_string();
+
+    if text.text.is_empty() {
+        paragraph.content.remove(0);
+    }
+
+    if paragraph.content.is_empty() {
+        content.remove(0);
+    }
+
+    Some(panel_type)
+}
+
+// ----------------------------------------------------------------------------
+// ADF to Markdown
+// ----------------------------------------------------------------------------
+fn write_blocks(nodes: &[Node]) -> String {
+    let mut out = String::new();
+
+    for node in nodes {
+        let block = write_block(node);
+        if block.is_empty() {
+            continue;
+        }
+
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        out.push_str(&block);
+    }
+
+    out
+}
+
+fn write_block(node: &Node) -> String {
+    match node {
+        Node::Paragraph(p) => write_inline(&p.content, HARD_BREAK),
+        Node::Heading(h) => format!(
+            "{} {}",
+            "#".repeat(h.attrs.level.clamp(1, 6) as usize),
+            write_inline(&h.content, HARD_BREAK)
+        ),
+        Node::BulletList(l) => write_list(&l.content, |_| "- ".to_string()),
+        Node::OrderedList(l) => {
+            let start = l.attrs.as_ref().and_then(|a| a.order).unwrap_or(1);
+            write_list(&l.content, |i| format!("{}. ", start + i as u64))
+        }
+        Node::ListItem(i) => write_item(&i.content),
+        Node::CodeBlock(c) => {
+            let code = plain_text(&c.content);
+            let fence = "`".repeat(longest_run(&code, '`').max(2) + 1);
+            let language = c
+                .attrs
+                .as_ref()
+                .and_then(|a| a.language.clone())
+                .unwrap_or_default();
+
+            if code.is_empty() {
+                format!("{}{}\n{}", fence, language, fence)
+            } else {
+                format!("{}{}\n{}\n{}", fence, language, code, fence)
+            }
+        }
+        Node::Blockquote(q) => quote(&write_blocks(&q.content)),
+        Node::Panel(p) => {
+            let name = PANEL_TYPES
+                .iter()
+                .find(|(t, _)| *t == p.attrs.panel_type)
+                .map_or("INFO", |(_, n)| n);
+            let body = write_blocks(&p.content);
+
+            if body.is_empty() {
+                quote(&format!("[!{}]", name))
+            } else {
+                quote(&format!("[!{}]\n{}", name, body))
+            }
+        }
+        Node::Rule(_) => "---".to_string(),
+        Node::Table(t) => write_table(&t.content),
+        Node::MediaSingle(m) => write_blocks(&m.content),
+        Node::MediaGroup(m) => write_blocks(&m.content),
+        Node::Media(m) => write_media(m),
+        Node::Unknown(v) => match serde_json::from_value::<Vec<Node>>(v["content"].clone()) {
+            Ok(content) if content.iter().all(Node::is_inline) => {
+                write_inline(&content, HARD_BREAK)
+            }
+            Ok(content) => write_blocks(&content),
+            Err(_) => write_inline(std::slice::from_ref(node), HARD_BREAK),
+        },
+        n if n.is_inline() => write_inline(std::slice::from_ref(n), HARD_BREAK),
+        n => write_blocks(n.content()),
+    }
+}
+
+fn write_list<F>(items: &[Node], marker: F) -> String
+where
+    F: Fn(usize) -> String,
+{
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let marker = marker(i);
+            let body = write_block(item);
+            let indent = " ".repeat(marker.len());
+
+            if body.is_empty() {
+                return marker.trim_end().to_string();
+            }
+
+            body.lines()
+                .enumerate()
+                .map(|(n, line)| match (n, line.is_empty()) {
+                    (0, _) => format!("{}{}", marker, line),
+                    (_, true) => String::new(),
+                    (_, false) => format!("{}{}", indent, line),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The blocks of a list item, nested lists follow their paragraph directly
+/// so the list stays tight
+fn write_item(nodes: &[Node]) -> String {
+    let mut out = String::new();
+
+    for node in nodes {
+        let block = write_block(node);
+        if block.is_empty() {
+            continue;
+        }
+
+        if !out.is_empty() {
+            match node {
+                Node::BulletList(_) | Node::OrderedList(_) => out.push('\n'),
+                _ => out.push_str("\n\n"),
+            }
+        }
+        out.push_str(&block);
+    }
+
+    out
+}
+
+fn quote(body: &str) -> String {
+    body.lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn write_table(rows: &[Node]) -> String {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.content().iter().map(write_cell).collect())
+        .collect();
+
+    let width = cells.iter().map(Vec::len).max().unwrap_or(0);
+    if width == 0 {
+        return String::new();
+    }
+
+    let has_header = rows.first().is_some_and(|row| {
+        row.content()
+            .iter()
+            .all(|c| matches!(c, Node::TableHeader(_)))
+    });
+
+    let (header, body) = if has_header {
+        (cells[0].clone(), &cells[1..])
+    } else {
+        (vec![], &cells[..])
+    };
+
+    let row = |cells: &[String]| {
+        let padded: Vec<&str> = (0..width)
+            .map(|i| cells.get(i).map_or("", |c| c.as_str()))
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+
+    let mut lines = vec![row(&header), format!("|{}", " --- |".repeat(width))];
+    lines.extend(body.iter().map(|cells| row(cells)));
+
+    lines.join("\n")
+}
+
+fn write_cell(cell: &Node) -> String {
+    cell.content()
+        .iter()
+        .map(|block| match block {
+            Node::Paragraph(p) => write_inline(&p.content, CELL_BREAK),
+            block => write_block(block).replace('\n', " "),
+        })
+        .collect::<Vec<_>>()
+        .join(CELL_BREAK)
+}
+
+fn write_media(m: &Media) -> String {
+    let dest = match (&m.attrs.url, &m.attrs.id) {
+        (Some(url), _) => url.clone(),
+        (None, Some(id)) => format!("{}{}", MEDIA_SCHEME, id),
+        (None, None) => String::new(),
+    };
+
+    format!(
+        "![{}]({})",
+        escape(m.attrs.alt.as_deref().unwrap_or_default(), false),
+        destination(&dest)
+    )
+}
+
+/// Writes inline nodes opening and closing marks only where they change so
+/// neighbouring nodes share delimiters, e.g. `**a *b***`
+fn write_inline(nodes: &[Node], hard_break: &str) -> String {
+    let mut out = String::new();
+    let mut open: Vec<&Mark> = vec![];
+    let mut pending = String::new();
+
+    for node in nodes {
+        let (wanted, text): (Vec<&Mark>, Option<&Text>) = match node {
+            Node::Text(t) => {
+                let mut marks: Vec<&Mark> = t.marks.iter().filter(|m| rank(m).is_some()).collect();
+                marks.sort_by_key(|m| rank(m));
+                (marks, Some(t))
+            }
+            _ => (vec![], None),
+        };
+
+        if let Some(t) = text {
+            if t.text.trim().is_empty() {
+                pending.push_str(&t.text);
+                continue;
+            }
+        }
+
+        let keep = open
+            .iter()
+            .zip(wanted.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        while open.len() > keep {
+            out.push_str(&close_delimiter(open.pop().unwrap()));
+        }
+
+        match text {
+            Some(t) => {
+                let core = t.text.trim();
+                let leading = &t.text[..t.text.len() - t.text.trim_start().len()];
+                let trailing = &t.text[t.text.trim_end().len()..];
+
+                out.push_str(&pending);
+                out.push_str(leading);
+                pending = trailing.to_string();
+
+                for mark in &wanted[keep..] {
+                    out.push_str(&open_delimiter(mark));
+                    open.push(mark);
+                }
+
+                if t.marks.contains(&Mark::Code) {
+                    out.push_str(&code_span(core));
+                } else {
+                    let line_start = out.is_empty() || out.ends_with('\n');
+                    out.push_str(&escape(core, line_start));
+                }
+            }
+            None => {
+                out.push_str(&pending);
+                pending.clear();
+                out.push_str(&write_inline_node(node, hard_break));
+            }
+        }
+    }
+
+    while let Some(mark) = open.pop() {
+        out.push_str(&close_delimiter(mark));
+    }
+    out.push_str(&pending);
+
+    out
+}
+
+fn write_inline_node(node: &Node, hard_break: &str) -> String {
+    match node {
+        Node::HardBreak(_) => hard_break.to_string(),
+        Node::Mention(m) => {
+            let text = m
+                .attrs
+                .text
+                .clone()
+                .unwrap_or_else(|| format!("@{}", m.attrs.id));
+            format!(
+                "[{}]({}{})",
+                escape(&text, false),
+                MENTION_SCHEME,
+                m.attrs.id
+            )
+        }
+        Node::Emoji(e) => escape(&e.attrs.short_name, false),
+        Node::InlineCard(c) => c
+            .attrs
+            .url
+            .as_ref()
+            .map(|url| format!("<{}>", url))
+            .unwrap_or_default(),
+        Node::Media(m) => write_media(m),
+        Node::Unknown(v) => escape(v["text"].as_str().unwrap_or_default(), false),
+        n => write_block(n),
+    }
+}
+
+/// Order marks are opened in, marks without Markdown syntax are skipped
+fn rank(mark: &Mark) -> Option<u8> {
+    match mark {
+        Mark::Link { .. } => Some(0),
+        Mark::Strong => Some(1),
+        Mark::Em => Some(2),
+        Mark::Strike => Some(3),
+        Mark::Underline => Some(4),
+        Mark::TextColor { .. } => Some(5),
+        Mark::Subsup { .. } => Some(6),
+        Mark::Code | Mark::Unknown(_) => None,
+    }
+}
+
+fn open_delimiter(mark: &Mark) -> String {
+    match mark {
+        Mark::Link { .. } => "[".to_string(),
+        Mark::Strong => "**".to_string(),
+        Mark::Em => "*".to_string(),
+        Mark::Strike => "~~".to_string(),
+        Mark::Underline => "<u>".to_string(),
+        Mark::TextColor { attrs } => format!("<span style=\"color: {}\">", attrs.color),
+        Mark::Subsup { attrs } if attrs.kind == SubsupType::Sub => "<sub>".to_string(),
+        Mark::Subsup { .. } => "<sup>".to_string(),
+        Mark::Code | Mark::Unknown(_) => String::new(),
+    }
+}
+
+fn close_delimiter(mark: &Mark) -> String {
+    match mark {
+        Mark::Link { attrs } => match &attrs.title {
+            Some(title) => format!(
+                "]({} \"{}\")",
+                destination(&attrs.href),
+                title.replace('"', "\\\"")
+            ),
+            None => format!("]({})", destination(&attrs.href)),
+        },
+        Mark::Strong => "**".to_string(),
+        Mark::Em => "*".to_string(),
+        Mark::Strike => "~~".to_string(),
+        Mark::Underline => "</u>".to_string(),
+        Mark::TextColor { .. } => "</span>".to_string(),
+        Mark::Subsup { attrs } if attrs.kind == SubsupType::Sub => "</sub>".to_string(),
+        Mark::Subsup { .. } => "</sup>".to_string(),
+        Mark::Code | Mark::Unknown(_) => String::new(),
+    }
+}
+
+/// Link destinations with spaces or brackets need to be wrapped
+fn destination(dest: &str) -> String {
+    if dest.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        format!("<{}>", dest)
+    } else {
+        dest.to_string()
+    }
+}
+
+fn code_span(code: &str) -> String {
+    let ticks = "`".repeat(longest_run(code, '`') + 1);
+
+    if code.starts_with('`') || code.ends_with('`') {
+        format!("{} {} {}", ticks, code, ticks)
+    } else {
+        format!("{}{}{}", ticks, code, ticks)
+    }
+}
+
+fn longest_run(s: &str, c: char) -> usize {
+    s.split(|x| x != c).map(str::len).max().unwrap_or(0)
+}
+
+/// Escapes characters Markdown would treat as syntax. At the start of a line
+/// characters that begin a block (headings, lists etc.) are escaped too.
+fn escape(text: &str, line_start: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for (i, c) in text.char_indices() {
+        let starts_block = line_start && i == 0 && matches!(c, '#' | '-' | '+' | '=');
+        let ends_number = line_start
+            && matches!(c, '.' | ')')
+            && i > 0
+            && text[..i].chars().all(|d| d.is_ascii_digit());
+
+        if starts_block
+            || ends_number
+            || matches!(
+                c,
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~' | '|' | '&'
+            )
+        {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const MARKDOWN: &str = r#"## Release notes
+
+Deployed by **the bot**, see [the runbook](https://example.com/runbook "Runbook") [@Jane Doe](mention:5b10ac8d)\
+<https://example.com/browse/TEST-1> <u>under</u> H<sub>2</sub>O and `code` ~~gone~~
+
+- first
+- second **bold *both***
+  - nested
+
+3. third
+4. fourth
+
+```rust
+fn main() {}
+```
+
+> *quoted*
+
+> [!WARNING]
+> careful
+
+---
+
+| Key | Summary |
+| --- | --- |
+| TEST-1 | A \| pipe<br>two lines |
+
+![diagram](media:6e7c7f2c)"#;
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let doc = Document::from_markdown(MARKDOWN);
+
+        assert_eq!(doc.to_markdown(), MARKDOWN);
+    }
+
+    #[test]
+    fn test_from_markdown_nodes() {
+        let doc = Document::from_markdown(MARKDOWN);
+
+        assert!(matches!(&doc.content[0], Node::Heading(h) if h.attrs.level == 2));
+
+        let inline = doc.content[1].content();
+        assert!(inline
+            .iter()
+            .any(|n| matches!(n, Node::Mention(m) if m.attrs.id == "5b10ac8d")));
+        assert!(inline.iter().any(|n| matches!(n, Node::InlineCard(_))));
+        assert!(inline.iter().any(|n| matches!(n, Node::HardBreak(_))));
+
+        match &doc.content[3] {
+            Node::OrderedList(l) => assert_eq!(l.attrs.as_ref().unwrap().order, Some(3)),
+            n => panic!("expected an ordered list, got {:?}", n),
+        }
+
+        assert!(doc
+            .content
+            .iter()
+            .any(|n| matches!(n, Node::Panel(p) if p.attrs.panel_type == PanelType::Warning)));
+
+        match doc.content.last().unwrap() {
+            Node::MediaSingle(m) => match &m.content[0] {
+                Node::Media(m) => {
+                    assert_eq!(m.attrs.id.as_deref(), Some("6e7c7f2c"));
+                    assert_eq!(m.attrs.alt.as_deref(), Some("diagram"));
+                }
+                n => panic!("expected media, got {:?}", n),
+            },
+            n => panic!("expected a media single, got {:?}", n),
+        }
+    }
+
+    #[test]
+    fn test_to_markdown_from_adf() {
+        let results = fs::read_to_string("tests/assets/adf/document.json")
+            .expect("Unable to read in JSON file");
+        let doc: Document = serde_json::from_str(&results).unwrap();
+        let markdown = doc.to_markdown();
+
+        assert!(markdown.starts_with("## Release notes\n\n"));
+        assert!(markdown.contains("[@Jane Doe](mention:5b10ac8d82e05b22cc7d4ef5)"));
+        assert!(markdown.contains("\n3. third\n"));
+        assert!(markdown.contains("```rust\nfn main() {}\n```"));
+        assert!(markdown.contains("> [!WARNING]\n> careful"));
+        assert!(markdown.contains("| Key |\n| --- |\n| TEST-1 |"));
+        assert!(markdown.ends_with("hidden"));
+    }
+
+    #[test]
+    fn test_escapes_syntax_in_text() {
+        let doc = Document::new(vec![Node::paragraph(vec![Node::text("# 1*2_3 [x]")])]);
+        let markdown = doc.to_markdown();
+
+        assert_eq!(markdown, r"\# 1\*2\_3 \[x\]");
+        assert_eq!(Document::from_markdown(&markdown), doc);
+    }
+
+    #[test]
+    fn test_marks_do_not_wrap_whitespace() {
+        let doc = Document::new(vec![Node::paragraph(vec![
+            Node::Text(Text {
+                text: "bold ".to_string(),
+                marks: vec![Mark::Strong],
+                ..Default::default()
+            }),
+            Node::text("plain"),
+        ])]);
+
+        assert_eq!(doc.to_markdown(), "**bold** plain");
+    }
+}
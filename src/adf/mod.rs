@@ -0,0 +1,18 @@
+//! The Atlassian Document Format, used by JIRA Cloud for rich text such as
+//! issue descriptions and comments, along with conversion to and from
+//! Markdown.
+
+// ============================================================================
+// Use
+// ============================================================================
+pub use crate::adf::node::*;
+
+// ============================================================================
+// Private Modules
+// ============================================================================
+mod markdown;
+
+// ============================================================================
+// Public Modules
+// ============================================================================
+pub mod node;
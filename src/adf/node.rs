@@ -0,0 +1,683 @@
+//! The Atlassian Document Format node tree. Every node and mark JIRA documents
+//! is typed, anything else is kept as raw JSON so a document survives a round
+//! trip through this library unchanged. Attributes and fields the typed nodes
+//! do not model, such as `localId`, are kept in their `attrs` and `other`
+//! maps for the same reason.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+// ============================================================================
+// Public Enums
+// ============================================================================
+/// A single node in a document, block nodes hold other nodes in `content`
+/// and inline nodes sit inside paragraphs, headings and code blocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Node {
+    Paragraph(Container),
+    Heading(Heading),
+    BulletList(Container),
+    OrderedList(OrderedList),
+    ListItem(Container),
+    CodeBlock(CodeBlock),
+    Blockquote(Container),
+    Panel(Panel),
+    Rule(Leaf),
+    Table(Table),
+    TableRow(Container),
+    TableHeader(TableCell),
+    TableCell(TableCell),
+    MediaSingle(MediaSingle),
+    MediaGroup(Container),
+    Media(Media),
+    Text(Text),
+    HardBreak(Leaf),
+    Mention(Mention),
+    Emoji(Emoji),
+    InlineCard(InlineCard),
+
+    /// Any node this library does not know about
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+/// Formatting applied to a text node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Mark {
+    Strong,
+    Em,
+    Code,
+    Strike,
+    Underline,
+    Link {
+        attrs: LinkAttrs,
+    },
+    TextColor {
+        attrs: TextColorAttrs,
+    },
+    Subsup {
+        attrs: SubsupAttrs,
+    },
+
+    /// Any mark this library does not know about
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PanelType {
+    Info,
+    Note,
+    Warning,
+    Success,
+    Error,
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubsupType {
+    Sub,
+    Sup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaType {
+    File,
+    Link,
+    External,
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// The root of an ADF document, as found in `description` and comment `body`
+/// fields of the v3 API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    #[serde(rename = "type", default)]
+    kind: DocumentType,
+
+    /// Version of the format, currently always 1
+    #[serde(default = "default_version")]
+    pub version: u32,
+
+    /// Block nodes making up the document
+    #[serde(default)]
+    pub content: Vec<Node>,
+}
+
+/// A node that holds nothing but other nodes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Container {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrs: Option<BTreeMap<String, Value>>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+/// A node without content, such as a rule or a hard break
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Leaf {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrs: Option<BTreeMap<String, Value>>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heading {
+    pub attrs: HeadingAttrs,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeadingAttrs {
+    /// Level of the heading from 1 to 6
+    pub level: u8,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderedList {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrs: Option<OrderedListAttrs>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderedListAttrs {
+    /// Number of the first item
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u64>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CodeBlock {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrs: Option<CodeBlockAttrs>,
+
+    /// Text nodes without marks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CodeBlockAttrs {
+    /// Language used for syntax highlighting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Panel {
+    pub attrs: PanelAttrs,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PanelAttrs {
+    #[serde(rename = "panelType")]
+    pub panel_type: PanelType,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrs: Option<TableAttrs>,
+
+    /// Table rows
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TableAttrs {
+    #[serde(
+        rename = "isNumberColumnEnabled",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub is_number_column_enabled: Option<bool>,
+
+    /// One of "default", "full-width" or "wide"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TableCell {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrs: Option<TableCellAttrs>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TableCellAttrs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colspan: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rowspan: Option<u32>,
+
+    /// Width of each column the cell spans in pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colwidth: Option<Vec<u32>>,
+
+    /// Background colour as a hex code, e.g. "#ffffff"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaSingle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrs: Option<MediaSingleAttrs>,
+
+    /// A single media node
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Node>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaSingleAttrs {
+    /// One of "center", "wrap-left", "wrap-right", "wide" or "full-width"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+
+    /// Width as a percentage of the page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Media {
+    pub attrs: MediaAttrs,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaAttrs {
+    #[serde(rename = "type")]
+    pub media_type: MediaType,
+
+    /// Media services id of a file, not set for external media
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Media services collection the file is in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<String>,
+
+    /// Link to external media
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Alternative text shown when the media can not be displayed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Text {
+    pub text: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub marks: Vec<Mark>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attrs: Option<BTreeMap<String, Value>>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mention {
+    pub attrs: MentionAttrs,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MentionAttrs {
+    /// Account id of the mentioned user
+    pub id: String,
+
+    /// Name shown for the mention, usually "@" followed by the display name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    #[serde(
+        rename = "accessLevel",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub access_level: Option<String>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Emoji {
+    pub attrs: EmojiAttrs,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EmojiAttrs {
+    /// Short name of the emoji, e.g. ":smile:"
+    #[serde(rename = "shortName")]
+    pub short_name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Unicode text of the emoji
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InlineCard {
+    pub attrs: InlineCardAttrs,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InlineCardAttrs {
+    /// Link shown as a card
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// JSON-LD describing the link, used instead of `url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LinkAttrs {
+    pub href: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextColorAttrs {
+    /// Colour as a hex code, e.g. "#ff5630"
+    pub color: String,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubsupAttrs {
+    #[serde(rename = "type")]
+    pub kind: SubsupType,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+impl Document {
+    /// Creates a document from a list of block nodes
+    pub fn new(content: Vec<Node>) -> Self {
+        Document {
+            kind: DocumentType::Doc,
+            version: default_version(),
+            content,
+        }
+    }
+}
+
+impl Container {
+    /// Creates a container without attributes holding the given nodes
+    pub fn new(content: Vec<Node>) -> Self {
+        Container {
+            content,
+            ..Default::default()
+        }
+    }
+}
+
+impl Node {
+    /// Creates a text node without any marks
+    pub fn text<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Node::Text(Text {
+            text: text.into(),
+            ..Default::default()
+        })
+    }
+
+    /// Creates a paragraph holding the given inline nodes
+    pub fn paragraph(content: Vec<Node>) -> Self {
+        Node::Paragraph(Container::new(content))
+    }
+
+    /// Creates a horizontal rule
+    pub fn rule() -> Self {
+        Node::Rule(Leaf::default())
+    }
+
+    /// Creates a line break inside a paragraph
+    pub fn hard_break() -> Self {
+        Node::HardBreak(Leaf::default())
+    }
+
+    /// The child nodes, empty for leaf nodes and unknown nodes
+    pub fn content(&self) -> &[Node] {
+        use Node::*;
+
+        match self {
+            Paragraph(n) | BulletList(n) | ListItem(n) | Blockquote(n) | TableRow(n)
+            | MediaGroup(n) => &n.content,
+            Heading(n) => &n.content,
+            OrderedList(n) => &n.content,
+            CodeBlock(n) => &n.content,
+            Panel(n) => &n.content,
+            Table(n) => &n.content,
+            TableHeader(n) | TableCell(n) => &n.content,
+            MediaSingle(n) => &n.content,
+            _ => &[],
+        }
+    }
+
+    /// True for nodes that sit inside a paragraph rather than on their own
+    pub fn is_inline(&self) -> bool {
+        use Node::*;
+
+        match self {
+            Text(_) | HardBreak(_) | Mention(_) | Emoji(_) | InlineCard(_) => true,
+            Unknown(v) => v.get("text").is_some(),
+            _ => false,
+        }
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl Default for Document {
+    fn default() -> Self {
+        Document::new(vec![])
+    }
+}
+
+impl std::fmt::Display for Document {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum DocumentType {
+    #[default]
+    Doc,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_round_trip_is_lossless() {
+        let results = fs::read_to_string("tests/assets/adf/document.json")
+            .expect("Unable to read in JSON file");
+        let original: Value = serde_json::from_str(&results).unwrap();
+        let doc: Document = serde_json::from_value(original.clone()).unwrap();
+
+        assert_eq!(serde_json::to_value(&doc).unwrap(), original);
+    }
+
+    #[test]
+    fn test_round_trip_keeps_attrs() {
+        let original = serde_json::json!({
+            "type": "doc",
+            "version": 1,
+            "content": [
+                {
+                    "type": "paragraph",
+                    "attrs": {"localId": "p1"},
+                    "content": [
+                        {"type": "text", "text": "one", "attrs": {"localId": "t1"}},
+                        {"type": "hardBreak", "attrs": {"text": "\n"}},
+                        {"type": "text", "text": "two"}
+                    ]
+                },
+                {"type": "rule", "attrs": {"localId": "r1"}},
+                {
+                    "type": "bulletList",
+                    "attrs": {"localId": "l1"},
+                    "marks": [],
+                    "content": [
+                        {"type": "listItem", "content": [{"type": "paragraph"}]}
+                    ]
+                }
+            ]
+        });
+        let doc: Document = serde_json::from_value(original.clone()).unwrap();
+
+        match &doc.content[0] {
+            Node::Paragraph(p) => {
+                assert_eq!(p.attrs.as_ref().unwrap()["localId"], "p1");
+                assert!(matches!(&p.content[1], Node::HardBreak(b) if b.attrs.is_some()));
+            }
+            n => panic!("expected a paragraph, got {:?}", n),
+        }
+
+        assert_eq!(serde_json::to_value(&doc).unwrap(), original);
+    }
+
+    #[test]
+    fn test_round_trip_keeps_node_marks() {
+        let original = serde_json::json!({
+            "type": "doc",
+            "version": 1,
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": {"level": 1},
+                    "marks": [{"type": "alignment", "attrs": {"align": "center"}}],
+                    "content": [{"type": "text", "text": "Title"}]
+                }
+            ]
+        });
+        let doc: Document = serde_json::from_value(original.clone()).unwrap();
+
+        match &doc.content[0] {
+            Node::Heading(h) => assert!(h.other.contains_key("marks")),
+            n => panic!("expected a heading, got {:?}", n),
+        }
+
+        assert_eq!(serde_json::to_value(&doc).unwrap(), original);
+    }
+
+    #[test]
+    fn test_deserialize_typed_nodes() {
+        let results = fs::read_to_string("tests/assets/adf/document.json")
+            .expect("Unable to read in JSON file");
+        let doc: Document = serde_json::from_str(&results).unwrap();
+
+        match &doc.content[0] {
+            Node::Heading(h) => assert_eq!(h.attrs.level, 2),
+            n => panic!("expected a heading, got {:?}", n),
+        }
+
+        match &doc.content[1].content()[1] {
+            Node::Text(t) => assert_eq!(t.marks, vec![Mark::Strong]),
+            n => panic!("expected text, got {:?}", n),
+        }
+
+        assert!(doc
+            .content
+            .iter()
+            .any(|n| matches!(n, Node::Panel(p) if p.attrs.panel_type == PanelType::Warning)));
+        assert!(doc.content.iter().any(|n| matches!(n, Node::Unknown(_))));
+    }
+}
@@ -0,0 +1,228 @@
+//! An interface for managing epics through the JIRA agile API
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::agile::issues::post_in_chunks;
+use crate::agile::RankPosition;
+use crate::v2::{PaginatedIssues, Pagination};
+use crate::Client;
+use crate::Response;
+use crate::Result;
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EpicColour {
+    /// Key of the colour, e.g. "color_4"
+    #[serde(default)]
+    pub key: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Epic {
+    /// Internal id of the epic
+    #[serde(default)]
+    pub id: i64,
+
+    /// Key of the issue backing the epic
+    #[serde(default)]
+    pub key: String,
+
+    /// REST API link to the epic
+    #[serde(rename = "self", default)]
+    pub self_link: String,
+
+    /// Name of the epic
+    #[serde(default)]
+    pub name: String,
+
+    /// Summary of the epic
+    #[serde(default)]
+    pub summary: String,
+
+    /// Colour of the epic as shown on boards
+    #[serde(rename = "color", default)]
+    pub colour: Option<EpicColour>,
+
+    /// Is the epic done
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// Fields that can be changed on an epic, anything left as `None` is not
+/// touched.
+#[derive(Debug, Default, Serialize)]
+pub struct EpicUpdate {
+    /// New name of the epic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// New summary of the epic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// New colour of the epic
+    #[serde(rename = "color", skip_serializing_if = "Option::is_none")]
+    pub colour: Option<EpicColour>,
+
+    /// Mark the epic as done or not done
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done: Option<bool>,
+}
+
+impl Epic {
+    /// Fetches an epic by id or key. For more info consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/epic-getEpic
+    pub fn from_id_or_key<I>(c: &Client, id: I) -> Response<Epic>
+    where
+        I: Into<String>,
+    {
+        let endpoint = format!("agile/1.0/epic/{}", id.into());
+        c.get(&endpoint)
+    }
+
+    /// Partially updates an epic by id or key. For more info consult the api
+    /// docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/epic-partiallyUpdateEpic
+    pub fn update<I>(c: &Client, id: I, update: EpicUpdate) -> Response<Epic>
+    where
+        I: Into<String>,
+    {
+        let endpoint = format!("agile/1.0/epic/{}", id.into());
+        c.post(&endpoint, update)
+    }
+
+    /// Fetches a paginated list of the issues in an epic. For more info consult
+    /// the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/epic-getIssuesForEpic
+    pub fn issues<I>(c: &Client, id: I, page: Option<Pagination>) -> Response<PaginatedIssues>
+    where
+        I: Into<String>,
+    {
+        let endpoint = format!("agile/1.0/epic/{}/issue", id.into());
+        let query = Client::unpack_options(vec![&page.unwrap_or_default()]);
+
+        c.clone().add_query(query).get(&endpoint)
+    }
+
+    /// Fetches a paginated list of the issues that do not belong to any epic.
+    /// For more info consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/epic-getIssuesWithoutEpic
+    pub fn issues_without_epic(c: &Client, page: Option<Pagination>) -> Response<PaginatedIssues> {
+        let query = Client::unpack_options(vec![&page.unwrap_or_default()]);
+
+        c.clone().add_query(query).get("agile/1.0/epic/none/issue")
+    }
+
+    /// Moves issues (given by key or id) into an epic, this is done 50 issues
+    /// at a time. For more info consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/epic-moveIssuesToEpic
+    pub fn move_issues<I>(c: &Client, id: I, issues: &[String]) -> Result<()>
+    where
+        I: Into<String>,
+    {
+        let endpoint = format!("agile/1.0/epic/{}/issue", id.into());
+        post_in_chunks(c, &endpoint, issues)
+    }
+
+    /// Removes issues (given by key or id) from whichever epic they belong to,
+    /// this is done 50 issues at a time. For more info consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/epic-removeIssuesFromEpic
+    pub fn remove_issues(c: &Client, issues: &[String]) -> Result<()> {
+        post_in_chunks(c, "agile/1.0/epic/none/issue", issues)
+    }
+
+    /// Ranks an epic before or after another epic (by id or key), if
+    /// `custom_field` is not supplied the default rank field is used. For more
+    /// info consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/epic-rankEpics
+    pub fn rank<I>(
+        c: &Client,
+        id: I,
+        position: RankPosition,
+        custom_field: Option<i64>,
+    ) -> Response<()>
+    where
+        I: Into<String>,
+    {
+        #[derive(Serialize)]
+        struct Request {
+            #[serde(rename = "rankBeforeEpic", skip_serializing_if = "Option::is_none")]
+            rank_before_epic: Option<String>,
+            #[serde(rename = "rankAfterEpic", skip_serializing_if = "Option::is_none")]
+            rank_after_epic: Option<String>,
+            #[serde(rename = "rankCustomFieldId", skip_serializing_if = "Option::is_none")]
+            rank_custom_field_id: Option<i64>,
+        }
+
+        let (rank_before_epic, rank_after_epic) = match position {
+            RankPosition::Before(e) => (Some(e), None),
+            RankPosition::After(e) => (None, Some(e)),
+        };
+
+        let endpoint = format!("agile/1.0/epic/{}/rank", id.into());
+
+        c.put(
+            &endpoint,
+            Request {
+                rank_before_epic,
+                rank_after_epic,
+                rank_custom_field_id: custom_field,
+            },
+        )
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for Epic {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_results() {
+        let results = fs::read_to_string("tests/assets/agile/epic.json")
+            .expect("Unable to read in JSON file");
+        let epic: Epic = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(epic.id, 37);
+        assert_eq!(epic.key, "EPIC-1");
+        assert_eq!(
+            epic.self_link,
+            "http://www.example.com/jira/rest/agile/1.0/epic/23"
+        );
+        assert_eq!(epic.name, "epic 1");
+        assert_eq!(epic.summary, "epic 1 summary");
+        assert_eq!(epic.colour.unwrap().key, "color_4");
+        assert!(epic.done);
+    }
+
+    #[test]
+    fn test_serialize_partial_update() {
+        let update = EpicUpdate {
+            name: Some("epic 2".to_string()),
+            done: Some(false),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"name":"epic 2","done":false}"#
+        );
+    }
+}
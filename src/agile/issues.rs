@@ -0,0 +1,36 @@
+//! Helpers for agile endpoints that accept a list of issues, these are capped
+//! by JIRA at 50 issues per request so larger lists are sent in chunks.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::Client;
+use crate::Result;
+use crate::Serialize;
+
+// ============================================================================
+// Constants
+// ============================================================================
+/// The maximum number of issues JIRA accepts in a single agile request
+pub const MAX_ISSUES_PER_REQUEST: usize = 50;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Serialize)]
+pub struct IssueList<'a> {
+    /// Issue keys or ids
+    pub issues: &'a [String],
+}
+
+// ============================================================================
+// Public Functions
+// ============================================================================
+/// Posts the given issues to the endpoint, 50 at a time
+pub fn post_in_chunks(c: &Client, endpoint: &str, issues: &[String]) -> Result<()> {
+    for chunk in issues.chunks(MAX_ISSUES_PER_REQUEST) {
+        c.post::<_, ()>(endpoint, IssueList { issues: chunk })?;
+    }
+
+    Ok(())
+}
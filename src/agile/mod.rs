@@ -0,0 +1,20 @@
+//! Resources served by the JIRA Software (agile) REST API
+
+// ============================================================================
+// Use
+// ============================================================================
+pub use crate::agile::epic::*;
+pub use crate::agile::rank::*;
+pub use crate::agile::sprint::*;
+
+// ============================================================================
+// Private Modules
+// ============================================================================
+mod issues;
+
+// ============================================================================
+// Public Modules
+// ============================================================================
+pub mod epic;
+pub mod rank;
+pub mod sprint;
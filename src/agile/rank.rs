@@ -0,0 +1,501 @@
+//! An interface for ranking issues through the JIRA agile API. Ranking places
+//! a list of issues before or after another issue, keeping the order of the
+//! list intact.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::agile::issues::MAX_ISSUES_PER_REQUEST;
+use crate::Client;
+use crate::Response;
+use crate::Result;
+use crate::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// ============================================================================
+// Public Enums
+// ============================================================================
+/// Where to place the ranked issues relative to another issue
+#[derive(Debug, Clone, PartialEq)]
+pub enum RankPosition {
+    /// Rank the issues before the given issue key or id
+    Before(String),
+    /// Rank the issues after the given issue key or id
+    After(String),
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RankEntry {
+    /// ID of the issue
+    #[serde(rename = "issueId", default)]
+    pub issue_id: i64,
+
+    /// Key of the issue
+    #[serde(rename = "issueKey", default)]
+    pub issue_key: String,
+
+    /// HTTP status of the rank operation for this issue
+    #[serde(default)]
+    pub status: i64,
+
+    /// Errors encountered when ranking this issue
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+/// Returned by JIRA when only some of the issues could be ranked
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RankResult {
+    /// Status of each issue in the request
+    #[serde(default)]
+    pub entries: Vec<RankEntry>,
+}
+
+/// A single rank call, as produced by `Rank::plan`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankOperation {
+    /// Issues to rank, in the order they should appear
+    pub issues: Vec<String>,
+
+    /// Where to place the issues
+    pub position: RankPosition,
+}
+
+pub struct Rank;
+
+impl Rank {
+    /// Ranks the issues before the given issue. At most 50 issues can be
+    /// ranked in one call, if `custom_field` is not supplied the default rank
+    /// field is used. For more info consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/issue-rankIssues
+    pub fn before<I>(
+        c: &Client,
+        issues: &[String],
+        issue: I,
+        custom_field: Option<i64>,
+    ) -> Response<Option<RankResult>>
+    where
+        I: Into<String>,
+    {
+        Rank::rank(c, issues, RankPosition::Before(issue.into()), custom_field)
+    }
+
+    /// Ranks the issues after the given issue, see `before` for details
+    pub fn after<I>(
+        c: &Client,
+        issues: &[String],
+        issue: I,
+        custom_field: Option<i64>,
+    ) -> Response<Option<RankResult>>
+    where
+        I: Into<String>,
+    {
+        Rank::rank(c, issues, RankPosition::After(issue.into()), custom_field)
+    }
+
+    /// Ranks the issues relative to another issue
+    pub fn rank(
+        c: &Client,
+        issues: &[String],
+        position: RankPosition,
+        custom_field: Option<i64>,
+    ) -> Response<Option<RankResult>> {
+        #[derive(Serialize)]
+        struct Request<'a> {
+            issues: &'a [String],
+            #[serde(rename = "rankBeforeIssue", skip_serializing_if = "Option::is_none")]
+            rank_before_issue: Option<String>,
+            #[serde(rename = "rankAfterIssue", skip_serializing_if = "Option::is_none")]
+            rank_after_issue: Option<String>,
+            #[serde(rename = "rankCustomFieldId", skip_serializing_if = "Option::is_none")]
+            rank_custom_field_id: Option<i64>,
+        }
+
+        let (rank_before_issue, rank_after_issue) = match position {
+            RankPosition::Before(i) => (Some(i), None),
+            RankPosition::After(i) => (None, Some(i)),
+        };
+
+        c.put(
+            "agile/1.0/issue/rank",
+            Request {
+                issues,
+                rank_before_issue,
+                rank_after_issue,
+                rank_custom_field_id: custom_field,
+            },
+        )
+    }
+
+    /// Works out the fewest rank calls needed to turn the `current` order
+    /// into the `desired` order, moving as few issues as possible among plans
+    /// with that many calls.
+    ///
+    /// Issues left untouched keep their relative order, so they have to be
+    /// in the same order in `current` and `desired`. An issue missing from
+    /// `current` has no known place and can only stay put on its own. Every
+    /// other issue is moved, and the issues a call places stay together
+    /// unless a later call moves them again, so no untouched issue can end up
+    /// between them. A plan therefore needs at least one call per 50 moved
+    /// issues (rounded up) in each gap between untouched issues. This finds
+    /// the untouched issues that make that sum smallest and moves each gap in
+    /// chunks of 50 placed next to its neighbour, which meets the bound.
+    pub fn plan(current: &[String], desired: &[String]) -> Vec<RankOperation> {
+        plan_in_chunks(current, desired, MAX_ISSUES_PER_REQUEST)
+    }
+
+    /// Reorders issues from the `current` order into the `desired` order using
+    /// the calls worked out by `plan`. Returns the result of each call made,
+    /// issues that could not be ranked are reported in the entries.
+    pub fn reorder(
+        c: &Client,
+        current: &[String],
+        desired: &[String],
+        custom_field: Option<i64>,
+    ) -> Result<Vec<RankResult>> {
+        let mut results = Vec::new();
+
+        for op in Rank::plan(current, desired) {
+            let res = Rank::rank(c, &op.issues, op.position, custom_field)?;
+            results.push(res.data.unwrap_or_default());
+        }
+
+        Ok(results)
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for RankResult {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+/// `Rank::plan` with calls of at most `chunk` issues
+fn plan_in_chunks(current: &[String], desired: &[String], chunk: usize) -> Vec<RankOperation> {
+    let positions: HashMap<&String, usize> =
+        current.iter().enumerate().map(|(i, k)| (k, i)).collect();
+    let sequence: Vec<Option<usize>> = desired.iter().map(|k| positions.get(k).cloned()).collect();
+    let anchors = untouched(&sequence, chunk);
+
+    let mut ops = Vec::new();
+    let mut run: Vec<String> = Vec::new();
+    let mut previous: Option<&String> = None;
+
+    for (i, key) in desired.iter().enumerate() {
+        if anchors[i] {
+            for chunk in run.chunks(chunk) {
+                ops.push(RankOperation {
+                    issues: chunk.to_vec(),
+                    position: RankPosition::Before(key.clone()),
+                });
+            }
+            run.clear();
+            previous = Some(key);
+        } else {
+            run.push(key.clone());
+        }
+    }
+
+    // Whatever is left trails the last anchor, each chunk is placed after
+    // the one before it to keep the order
+    if let Some(anchor) = previous {
+        let mut position = RankPosition::After(anchor.clone());
+
+        for chunk in run.chunks(chunk) {
+            ops.push(RankOperation {
+                issues: chunk.to_vec(),
+                position,
+            });
+            position = RankPosition::After(chunk[chunk.len() - 1].clone());
+        }
+    }
+
+    ops
+}
+
+/// Marks the issues `Rank::plan` leaves in place, given the position of each
+/// desired issue in the current order. Either a single issue or an increasing
+/// run of positions is kept, whichever needs the fewest calls to move the
+/// rest and then moves the fewest issues.
+fn untouched(sequence: &[Option<usize>], chunk: usize) -> Vec<bool> {
+    let n = sequence.len();
+    let mut members = vec![false; n];
+
+    // best[i] is the cost of placing everything up to i with i kept and
+    // parents[i] the kept issue before it
+    let mut best: Vec<Option<(usize, usize)>> = vec![None; n];
+    let mut parents: Vec<Option<usize>> = vec![None; n];
+
+    for (i, value) in sequence.iter().enumerate() {
+        let value = match value {
+            Some(v) => *v,
+            None => continue,
+        };
+        let mut cost = gap_cost(i, chunk);
+
+        for j in 0..i {
+            match (sequence[j], best[j]) {
+                (Some(v), Some(before)) if v < value => {
+                    let through = add(before, gap_cost(i - j - 1, chunk));
+
+                    if through < cost {
+                        cost = through;
+                        parents[i] = Some(j);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        best[i] = Some(cost);
+    }
+
+    // Any one issue, even one missing from the current order, can be kept
+    // and the rest placed around it
+    let mut last = None;
+    let mut cheapest = None;
+
+    for (i, kept) in best.iter().enumerate() {
+        let after = gap_cost(n - 1 - i, chunk);
+        let cost = match *kept {
+            Some(cost) => add(cost, after),
+            None => add(gap_cost(i, chunk), after),
+        };
+
+        if cheapest.is_none() || Some(cost) < cheapest {
+            cheapest = Some(cost);
+            last = Some(i);
+        }
+    }
+
+    while let Some(i) = last {
+        members[i] = true;
+        last = parents[i];
+    }
+
+    members
+}
+
+/// Calls and moved issues needed to place a gap of `len` issues
+fn gap_cost(len: usize, chunk: usize) -> (usize, usize) {
+    (len.div_ceil(chunk), len)
+}
+
+fn add(a: (usize, usize), b: (usize, usize)) -> (usize, usize) {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(k: &[&str]) -> Vec<String> {
+        k.iter().map(|k| k.to_string()).collect()
+    }
+
+    /// Ranks issues the way JIRA does
+    fn apply(order: &[String], ops: &[RankOperation]) -> Vec<String> {
+        let mut order = order.to_vec();
+
+        for op in ops {
+            order.retain(|k| !op.issues.contains(k));
+
+            let at = match &op.position {
+                RankPosition::Before(k) => order.iter().position(|o| o == k).unwrap(),
+                RankPosition::After(k) => order.iter().position(|o| o == k).unwrap() + 1,
+            };
+
+            order.splice(at..at, op.issues.iter().cloned());
+        }
+
+        order
+    }
+
+    /// Searches every sequence of calls of at most `chunk` issues for the
+    /// fewest that reach each order of `current`
+    fn fewest_calls(current: &[String], chunk: usize) -> HashMap<Vec<String>, usize> {
+        let mut calls = HashMap::new();
+        let mut queue = std::collections::VecDeque::new();
+
+        calls.insert(current.to_vec(), 0);
+        queue.push_back(current.to_vec());
+
+        while let Some(order) = queue.pop_front() {
+            let depth = calls[&order];
+            let mut picks: Vec<Vec<String>> = vec![vec![]];
+
+            for _ in 0..chunk {
+                let longer: Vec<Vec<String>> = picks
+                    .iter()
+                    .flat_map(|p| {
+                        order
+                            .iter()
+                            .filter(move |k| !p.contains(k))
+                            .map(move |k| [p.clone(), vec![k.clone()]].concat())
+                    })
+                    .collect();
+                picks.extend(longer);
+            }
+
+            for issues in picks.into_iter().filter(|p| !p.is_empty()) {
+                for anchor in order.iter().filter(|k| !issues.contains(k)) {
+                    for position in [
+                        RankPosition::Before(anchor.clone()),
+                        RankPosition::After(anchor.clone()),
+                    ] {
+                        let op = RankOperation {
+                            issues: issues.clone(),
+                            position,
+                        };
+                        let next = apply(&order, &[op]);
+
+                        if !calls.contains_key(&next) {
+                            calls.insert(next.clone(), depth + 1);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+            }
+        }
+
+        calls
+    }
+
+    #[test]
+    fn test_plan_already_ordered() {
+        let k = keys(&["A", "B", "C"]);
+        assert!(Rank::plan(&k, &k).is_empty());
+    }
+
+    #[test]
+    fn test_plan_single_move() {
+        let ops = Rank::plan(&keys(&["A", "B", "C", "D"]), &keys(&["D", "A", "B", "C"]));
+
+        assert_eq!(
+            ops,
+            vec![RankOperation {
+                issues: keys(&["D"]),
+                position: RankPosition::Before("A".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_groups_adjacent_moves() {
+        let ops = Rank::plan(
+            &keys(&["A", "B", "C", "D", "E"]),
+            &keys(&["A", "E", "D", "B", "C"]),
+        );
+
+        assert_eq!(
+            ops,
+            vec![RankOperation {
+                issues: keys(&["E", "D"]),
+                position: RankPosition::Before("B".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_trailing_moves() {
+        let ops = Rank::plan(&keys(&["C", "A", "B"]), &keys(&["A", "B", "C"]));
+
+        assert_eq!(
+            ops,
+            vec![RankOperation {
+                issues: keys(&["C"]),
+                position: RankPosition::After("B".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_without_anchor() {
+        let ops = Rank::plan(&keys(&["A", "B"]), &keys(&["X", "Y"]));
+
+        assert_eq!(
+            ops,
+            vec![RankOperation {
+                issues: keys(&["Y"]),
+                position: RankPosition::After("X".to_string()),
+            }]
+        );
+
+        let ops = Rank::plan(&[], &keys(&["X", "Y", "Z"]));
+
+        assert_eq!(
+            ops,
+            vec![RankOperation {
+                issues: keys(&["Y", "Z"]),
+                position: RankPosition::After("X".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_not_always_longest_run() {
+        // Keeping A and C or B and D, both longest runs, takes two calls
+        let ops = Rank::plan(&keys(&["A", "B", "C", "D"]), &keys(&["B", "A", "D", "C"]));
+
+        assert_eq!(
+            ops,
+            vec![RankOperation {
+                issues: keys(&["A", "D"]),
+                position: RankPosition::Before("C".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plan_is_minimal() {
+        let current = keys(&["A", "B", "C", "D", "E"]);
+
+        for chunk in 1..=3 {
+            let fewest = fewest_calls(&current, chunk);
+
+            for (desired, calls) in &fewest {
+                let ops = plan_in_chunks(&current, desired, chunk);
+
+                assert_eq!(apply(&current, &ops), *desired);
+                assert_eq!(ops.len(), *calls, "{:?} in chunks of {}", desired, chunk);
+                assert!(ops.iter().all(|o| o.issues.len() <= chunk));
+            }
+
+            assert_eq!(fewest.len(), 120);
+        }
+    }
+
+    #[test]
+    fn test_plan_chunks_large_moves() {
+        let current: Vec<String> = (0..120).map(|i| format!("T-{}", i)).collect();
+        let mut desired = current.clone();
+        desired.rotate_left(1);
+
+        let ops = Rank::plan(&current, &desired);
+
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].position, RankPosition::After("T-119".to_string()));
+
+        let mut desired = current.clone();
+        desired.reverse();
+        let ops = Rank::plan(&current, &desired);
+        let moved: usize = ops.iter().map(|o| o.issues.len()).sum();
+
+        assert_eq!(moved, 119);
+        assert_eq!(ops.len(), 3);
+        assert!(ops.iter().all(|o| o.issues.len() <= MAX_ISSUES_PER_REQUEST));
+    }
+}
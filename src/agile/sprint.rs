@@ -0,0 +1,281 @@
+//! An interface for managing sprints through the JIRA agile API
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::agile::issues::post_in_chunks;
+use crate::v2::{PaginatedIssues, Pagination};
+use crate::Client;
+use crate::Response;
+use crate::Result;
+use crate::{format_agile_timestamp, Timestamp};
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Sprint {
+    /// Internal id of the sprint
+    #[serde(default)]
+    pub id: i64,
+
+    /// REST API link to the sprint
+    #[serde(rename = "self", default)]
+    pub self_link: String,
+
+    /// State of the sprint, one of "future", "active" or "closed"
+    #[serde(default)]
+    pub state: String,
+
+    /// Name of the sprint
+    #[serde(default)]
+    pub name: String,
+
+    /// Date the sprint started in format: "2015-04-11T15:22:00.000+10:00"
+    #[serde(
+        rename = "startDate",
+        default,
+        with = "crate::datetime::option_agile_timestamp"
+    )]
+    pub start_date: Option<Timestamp>,
+
+    /// Date the sprint is due to end in format: "2015-04-20T01:22:00.000+10:00"
+    #[serde(
+        rename = "endDate",
+        default,
+        with = "crate::datetime::option_agile_timestamp"
+    )]
+    pub end_date: Option<Timestamp>,
+
+    /// Date the sprint was completed in format: "2015-04-20T11:04:00.000+10:00"
+    #[serde(
+        rename = "completeDate",
+        default,
+        with = "crate::datetime::option_agile_timestamp"
+    )]
+    pub complete_date: Option<Timestamp>,
+
+    /// The id of the board the sprint was created on
+    #[serde(rename = "originBoardId", default)]
+    pub origin_board_id: i64,
+
+    /// The goal of the sprint
+    #[serde(default)]
+    pub goal: String,
+}
+
+impl Sprint {
+    /// Returns a blank sprint with the given name belonging to the given board
+    pub fn new<N>(name: N, origin_board_id: i64) -> Self
+    where
+        N: Into<String>,
+    {
+        Sprint {
+            name: name.into(),
+            origin_board_id,
+            ..Default::default()
+        }
+    }
+
+    /// Fetches a sprint by id. For more info consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/sprint-getSprint
+    pub fn from_id(c: &Client, id: i64) -> Response<Sprint> {
+        let endpoint = format!("agile/1.0/sprint/{}", id);
+        c.get(&endpoint)
+    }
+
+    /// Creates a new future sprint on the origin board. For more info consult
+    /// the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/sprint-createSprint
+    pub fn create(self, c: &Client) -> Response<Sprint> {
+        #[derive(Serialize)]
+        struct Request {
+            name: String,
+            #[serde(
+                rename = "startDate",
+                skip_serializing_if = "Option::is_none",
+                with = "crate::datetime::option_agile_timestamp"
+            )]
+            start_date: Option<Timestamp>,
+            #[serde(
+                rename = "endDate",
+                skip_serializing_if = "Option::is_none",
+                with = "crate::datetime::option_agile_timestamp"
+            )]
+            end_date: Option<Timestamp>,
+            #[serde(rename = "originBoardId")]
+            origin_board_id: i64,
+            goal: String,
+        }
+
+        c.post(
+            "agile/1.0/sprint",
+            Request {
+                name: self.name,
+                start_date: self.start_date,
+                end_date: self.end_date,
+                origin_board_id: self.origin_board_id,
+                goal: self.goal,
+            },
+        )
+    }
+
+    /// Updates the sprint with the information currently in the struct, any
+    /// field left empty is cleared on the server. For more info consult the
+    /// api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/sprint-updateSprint
+    pub fn update(self, c: &Client) -> Response<Sprint> {
+        let endpoint = format!("agile/1.0/sprint/{}", self.id);
+        c.put(&endpoint, self)
+    }
+
+    /// Deletes a sprint, once deleted all open issues in the sprint are moved
+    /// to the backlog. For more info consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/sprint-deleteSprint
+    pub fn delete(c: &Client, id: i64) -> Response<()> {
+        let endpoint = format!("agile/1.0/sprint/{}", id);
+        c.delete(&endpoint)
+    }
+
+    /// Starts a future sprint, dates must be supplied in the format:
+    /// "2015-04-11T15:22:00.000+10:00"
+    pub fn start<S, E>(c: &Client, id: i64, start_date: S, end_date: E) -> Response<Sprint>
+    where
+        S: Into<String>,
+        E: Into<String>,
+    {
+        #[derive(Serialize)]
+        struct Request {
+            state: &'static str,
+            #[serde(rename = "startDate")]
+            start_date: String,
+            #[serde(rename = "endDate")]
+            end_date: String,
+        }
+
+        let endpoint = format!("agile/1.0/sprint/{}", id);
+
+        c.post(
+            &endpoint,
+            Request {
+                state: "active",
+                start_date: start_date.into(),
+                end_date: end_date.into(),
+            },
+        )
+    }
+
+    /// Starts a future sprint running between the given timestamps
+    pub fn start_between(
+        c: &Client,
+        id: i64,
+        start_date: &Timestamp,
+        end_date: &Timestamp,
+    ) -> Response<Sprint> {
+        Sprint::start(
+            c,
+            id,
+            format_agile_timestamp(start_date),
+            format_agile_timestamp(end_date),
+        )
+    }
+
+    /// Completes an active sprint
+    pub fn complete(c: &Client, id: i64) -> Response<Sprint> {
+        #[derive(Serialize)]
+        struct Request {
+            state: &'static str,
+        }
+
+        let endpoint = format!("agile/1.0/sprint/{}", id);
+        c.post(&endpoint, Request { state: "closed" })
+    }
+
+    /// Fetches a paginated list of the issues in a sprint. For more info
+    /// consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/sprint-getIssuesForSprint
+    pub fn issues(c: &Client, id: i64, page: Option<Pagination>) -> Response<PaginatedIssues> {
+        let endpoint = format!("agile/1.0/sprint/{}/issue", id);
+        let query = Client::unpack_options(vec![&page.unwrap_or_default()]);
+
+        c.clone().add_query(query).get(&endpoint)
+    }
+
+    /// Moves issues (given by key or id) into a sprint, JIRA caps this call at
+    /// 50 issues so larger lists are sent in several requests. For more info
+    /// consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/sprint-moveIssuesToSprint
+    pub fn move_issues(c: &Client, id: i64, issues: &[String]) -> Result<()> {
+        let endpoint = format!("agile/1.0/sprint/{}/issue", id);
+        post_in_chunks(c, &endpoint, issues)
+    }
+
+    /// Moves issues (given by key or id) out of any sprint and back to the
+    /// backlog, as with `move_issues` this is done 50 issues at a time. For
+    /// more info consult the api docs:
+    /// https://docs.atlassian.com/jira-software/REST/8.2.0/#agile/1.0/backlog-moveIssuesToBacklog
+    pub fn move_to_backlog(c: &Client, issues: &[String]) -> Result<()> {
+        post_in_chunks(c, "agile/1.0/backlog/issue", issues)
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for Sprint {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_timestamp;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_results() {
+        let results = fs::read_to_string("tests/assets/agile/sprint.json")
+            .expect("Unable to read in JSON file");
+        let sprint: Sprint = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(sprint.id, 37);
+        assert_eq!(
+            sprint.self_link,
+            "http://www.example.com/jira/rest/agile/1.0/sprint/23"
+        );
+        assert_eq!(sprint.state, "closed");
+        assert_eq!(sprint.name, "sprint 1");
+        assert_eq!(
+            sprint.start_date,
+            parse_timestamp("2015-04-11T15:22:00.000+10:00").ok()
+        );
+        assert_eq!(
+            sprint.end_date,
+            parse_timestamp("2015-04-20T01:22:00.000+10:00").ok()
+        );
+        assert_eq!(
+            sprint.complete_date,
+            parse_timestamp("2015-04-20T11:04:00.000+10:00").ok()
+        );
+        assert_eq!(sprint.origin_board_id, 5);
+        assert_eq!(sprint.goal, "sprint 1 goal");
+    }
+
+    #[test]
+    fn test_serialize_keeps_offset() {
+        let results = fs::read_to_string("tests/assets/agile/sprint.json")
+            .expect("Unable to read in JSON file");
+        let sprint: Sprint = serde_json::from_str(&results).unwrap();
+        let v = serde_json::to_value(&sprint).unwrap();
+
+        assert_eq!(v["startDate"], "2015-04-11T15:22:00.000+10:00");
+        assert_eq!(v["completeDate"], "2015-04-20T11:04:00.000+10:00");
+    }
+}
@@ -0,0 +1,394 @@
+//! Builder for clients that need more than a host and credentials
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::cassette::Cassette;
+use crate::logging::REDACTED;
+use crate::middleware::Middleware;
+use crate::Capabilities;
+use crate::Client;
+use crate::Credentials;
+use crate::Logging;
+use crate::Result;
+use openssl::pkcs12::Pkcs12;
+use openssl::pkey::PKey;
+use openssl::stack::Stack;
+use openssl::x509::X509;
+use reqwest::header::{HeaderName, HeaderValue, USER_AGENT};
+use reqwest::{Certificate, Identity, Proxy, Url};
+use std::io::{Error as IoError, ErrorKind};
+use std::sync::Arc;
+use std::time::Duration;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// Builds a `Client`, start one with `Client::builder`.
+///
+/// ```no_run
+/// use jirac::{Client, Credentials};
+/// use std::time::Duration;
+///
+/// let client = Client::builder("https://corp.example.com", Credentials::Pat("token".into()))
+///     .context_path("/jira")
+///     .proxy("http://proxy.corp.example.com:3128")
+///     .add_root_certificate_pem(std::fs::read("corp-ca.pem").unwrap())
+///     .connect_timeout(Duration::from_secs(5))
+///     .timeout(Duration::from_secs(60))
+///     .user_agent("nightly-sync/1.0")
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// Certificates, proxies and headers are checked by `build`, which fails
+/// with the first problem it finds.
+pub struct ClientBuilder {
+    client: Client,
+    context_path: Option<String>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+    proxy_credentials: Option<(String, String)>,
+    root_certificates: Vec<Vec<u8>>,
+    identity: Option<ClientIdentity>,
+    accept_invalid_hostnames: bool,
+    accept_invalid_certs: bool,
+    headers: Vec<(String, String)>,
+}
+
+impl ClientBuilder {
+    /// Starts from the same client `Client::new` would create
+    pub fn new<H>(host: H, credentials: Credentials) -> ClientBuilder
+    where
+        H: Into<String>,
+    {
+        ClientBuilder {
+            client: Client::new(host, credentials),
+            context_path: None,
+            connect_timeout: None,
+            timeout: None,
+            proxy: None,
+            proxy_credentials: None,
+            root_certificates: vec![],
+            identity: None,
+            accept_invalid_hostnames: false,
+            accept_invalid_certs: false,
+            headers: vec![],
+        }
+    }
+
+    /// Adds a middleware, the first one added runs first on the way out
+    /// and last on the way back
+    pub fn middleware<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware + 'static,
+    {
+        self.client.middleware.push(Arc::new(middleware));
+
+        self
+    }
+
+    /// Records or replays requests, see `Client::with_cassette`
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.client.cassette = Some(Arc::new(cassette));
+
+        self
+    }
+
+    /// Sets what is logged about requests, see `Logging`
+    pub fn logging(mut self, logging: Logging) -> Self {
+        self.client.logging = logging;
+
+        self
+    }
+
+    /// Sets the capabilities without asking the server
+    pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.client.capabilities = Some(capabilities);
+
+        self
+    }
+
+    /// The path JIRA is served under when it is not the root of the host,
+    /// such as `/jira` for `https://corp.example.com/jira/rest/api/2/...`
+    pub fn context_path<P>(mut self, path: P) -> Self
+    where
+        P: Into<String>,
+    {
+        self.context_path = Some(path.into());
+
+        self
+    }
+
+    /// How long to wait for a connection to be established
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+
+        self
+    }
+
+    /// How long to wait on reading or writing a request, 30 seconds unless
+    /// set
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Sends every request through a proxy. `http://` and `https://` URLs
+    /// are supported, `socks5://` ones too with the `socks` feature.
+    pub fn proxy<U>(mut self, url: U) -> Self
+    where
+        U: Into<String>,
+    {
+        self.proxy = Some(url.into());
+
+        self
+    }
+
+    /// Authenticates to the proxy with basic auth
+    pub fn proxy_credentials<U, P>(mut self, username: U, password: P) -> Self
+    where
+        U: Into<String>,
+        P: Into<String>,
+    {
+        self.proxy_credentials = Some((username.into(), password.into()));
+
+        self
+    }
+
+    /// Trusts the certificates in a PEM file, such as an internal CA, as
+    /// well as the system ones. The file may hold several certificates.
+    pub fn add_root_certificate_pem<B>(mut self, pem: B) -> Self
+    where
+        B: Into<Vec<u8>>,
+    {
+        self.root_certificates.push(pem.into());
+
+        self
+    }
+
+    /// Presents a client certificate for mutual TLS, from a PKCS #12
+    /// archive holding the certificate and its private key
+    pub fn client_certificate_pkcs12<B, P>(mut self, der: B, password: P) -> Self
+    where
+        B: Into<Vec<u8>>,
+        P: Into<String>,
+    {
+        self.identity = Some(ClientIdentity::Pkcs12(der.into(), password.into()));
+
+        self
+    }
+
+    /// Presents a client certificate for mutual TLS, from PEM holding the
+    /// certificate, optionally followed by its chain, and PEM holding its
+    /// unencrypted private key
+    pub fn client_certificate_pem<C, K>(mut self, certificate: C, key: K) -> Self
+    where
+        C: Into<Vec<u8>>,
+        K: Into<Vec<u8>>,
+    {
+        self.identity = Some(ClientIdentity::Pem(certificate.into(), key.into()));
+
+        self
+    }
+
+    /// Accepts certificates whose host name does not match the server.
+    /// Only meant for on-prem test instances, it makes the connection easy
+    /// to intercept.
+    pub fn danger_accept_invalid_hostnames(mut self, accept: bool) -> Self {
+        self.accept_invalid_hostnames = accept;
+
+        self
+    }
+
+    /// Accepts any certificate, including expired and self-signed ones.
+    /// Only meant for on-prem test instances, it makes the connection easy
+    /// to intercept.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+
+        self
+    }
+
+    /// The `User-Agent` sent with every request
+    pub fn user_agent<A>(self, agent: A) -> Self
+    where
+        A: Into<String>,
+    {
+        self.header(USER_AGENT.as_str(), agent)
+    }
+
+    /// A header sent with every request, it ends up in `Client::headers`
+    pub fn header<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.headers.push((name.into(), value.into()));
+
+        self
+    }
+
+    /// Creates the client
+    pub fn build(self) -> Result<Client> {
+        let mut client = self.client;
+
+        let host = client.host.trim_end_matches('/');
+        client.host = match self.context_path {
+            Some(ref path) if !path.trim_matches('/').is_empty() => {
+                format!("{}/{}", host, path.trim_matches('/'))
+            }
+            _ => host.to_string(),
+        };
+
+        for (name, value) in self.headers {
+            let invalid =
+                |e| IoError::new(ErrorKind::InvalidInput, format!("header {}: {}", name, e));
+            client.headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|e| invalid(e.to_string()))?,
+                HeaderValue::from_str(&value).map_err(|e| invalid(e.to_string()))?,
+            );
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .danger_accept_invalid_hostnames(self.accept_invalid_hostnames)
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(ref url) = self.proxy {
+            let mut proxy = Proxy::all(url.as_str())?;
+            if let Some((ref user, ref pass)) = self.proxy_credentials {
+                proxy = proxy.basic_auth(user, pass);
+            }
+            builder = builder.proxy(proxy);
+        }
+
+        for pem in &self.root_certificates {
+            let certificates = X509::stack_from_pem(pem)?;
+            if certificates.is_empty() {
+                return Err(IoError::new(ErrorKind::InvalidInput, "no certificate in PEM").into());
+            }
+
+            for certificate in certificates {
+                builder =
+                    builder.add_root_certificate(Certificate::from_der(&certificate.to_der()?)?);
+            }
+        }
+
+        if let Some(identity) = self.identity {
+            builder = builder.identity(identity.into_identity()?);
+        }
+
+        client.client = builder.build()?;
+
+        Ok(client)
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let redacted = format_args!("{}", REDACTED);
+
+        // A proxy url may carry its own credentials
+        let proxy = self.proxy.as_ref().map(|proxy| match Url::parse(proxy) {
+            Ok(mut url) if url.password().is_some() => {
+                let _ = url.set_password(Some(REDACTED));
+                url.to_string()
+            }
+            _ => proxy.clone(),
+        });
+
+        f.debug_struct("ClientBuilder")
+            .field("client", &self.client)
+            .field("context_path", &self.context_path)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("timeout", &self.timeout)
+            .field("proxy", &proxy)
+            .field(
+                "proxy_credentials",
+                &self
+                    .proxy_credentials
+                    .as_ref()
+                    .map(|(user, _)| (user, &redacted)),
+            )
+            .field("root_certificates", &self.root_certificates.len())
+            .field("identity", &self.identity)
+            .field("accept_invalid_hostnames", &self.accept_invalid_hostnames)
+            .field("accept_invalid_certs", &self.accept_invalid_certs)
+            // Values are left out, custom headers often carry tokens
+            .field(
+                "headers",
+                &self
+                    .headers
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for ClientIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let redacted = format_args!("{}", REDACTED);
+
+        match self {
+            ClientIdentity::Pkcs12(..) => f.debug_tuple("Pkcs12").field(&redacted).finish(),
+            ClientIdentity::Pem(certificate, _) => f
+                .debug_tuple("Pem")
+                .field(&String::from_utf8_lossy(certificate))
+                .field(&redacted)
+                .finish(),
+        }
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+enum ClientIdentity {
+    Pkcs12(Vec<u8>, String),
+    Pem(Vec<u8>, Vec<u8>),
+}
+
+impl ClientIdentity {
+    fn into_identity(self) -> Result<Identity> {
+        let (der, password) = match self {
+            ClientIdentity::Pkcs12(der, password) => (der, password),
+            ClientIdentity::Pem(certificate, key) => {
+                // The native TLS backend only takes PKCS #12, so repack them
+                let mut chain = X509::stack_from_pem(&certificate)?.into_iter();
+                let leaf = chain.next().ok_or_else(|| {
+                    IoError::new(ErrorKind::InvalidInput, "no client certificate in PEM")
+                })?;
+                let mut ca = Stack::new()?;
+                for certificate in chain {
+                    ca.push(certificate)?;
+                }
+
+                let key = PKey::private_key_from_pem(&key)?;
+                let archive = Pkcs12::builder()
+                    .name("jirac")
+                    .pkey(&key)
+                    .cert(&leaf)
+                    .ca(ca)
+                    .build2("")?;
+
+                (archive.to_der()?, String::new())
+            }
+        };
+
+        Ok(Identity::from_pkcs12_der(&der, &password)?)
+    }
+}
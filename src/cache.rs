@@ -0,0 +1,436 @@
+//! A response cache for endpoints whose data rarely changes.
+//!
+//! The cache is a middleware, so it is opt in and sits wherever it is added
+//! in the chain. Only successful `GET` requests to endpoints with a TTL are
+//! cached. A fresh entry answers the request without sending it, a stale
+//! entry with an `ETag` is revalidated with `If-None-Match` and reused when
+//! JIRA answers `304 Not Modified`.
+//!
+//! ```
+//! use jirac::cache::Cache;
+//! use jirac::{Client, Credentials};
+//! use std::time::Duration;
+//!
+//! let cache = Cache::new()
+//!     .reference_data(Duration::from_secs(3600))
+//!     .ttl("api/2/project", Duration::from_secs(300));
+//!
+//! let client = Client::builder("https://jira.example.com", Credentials::Pat("token".into()))
+//!     .middleware(cache)
+//!     .build()
+//!     .unwrap();
+//! # let _ = client;
+//! ```
+//!
+//! Entries are keyed on the full URL, not on who asked, so a cache should
+//! not be shared between clients with different credentials. Failing to
+//! read or write an entry never fails the request, it is treated as a miss.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::middleware::{Middleware, Next, Request, Response};
+use crate::Result;
+use crate::{Deserialize, Serialize};
+use openssl::sha::sha256;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ETAG, IF_NONE_MATCH, SET_COOKIE};
+use reqwest::{Method, StatusCode};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// A cached response
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub status: u16,
+
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    #[serde(default)]
+    pub body: String,
+
+    /// The `ETag` JIRA sent with the response, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+
+    /// When the response was stored or last revalidated, in milliseconds
+    /// since the Unix epoch
+    pub stored_at: u64,
+}
+
+/// Keeps entries in memory, the default store
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+/// Keeps entries as JSON files in a directory, so they outlive the process
+/// and can be shared between runs of a batch job
+#[derive(Debug, Clone)]
+pub struct DiskStore {
+    pub dir: PathBuf,
+}
+
+/// The response cache, see the module docs
+#[derive(Debug, Clone)]
+pub struct Cache {
+    /// Endpoint prefixes, relative to `/rest/`, and how long responses
+    /// from them stay fresh
+    pub ttls: Vec<(String, Duration)>,
+
+    store: Arc<dyn CacheStore>,
+}
+
+// ============================================================================
+// Public Traits
+// ============================================================================
+/// Where a `Cache` keeps its entries
+pub trait CacheStore: Debug + Send + Sync {
+    fn get(&self, key: &str) -> Result<Option<Entry>>;
+
+    fn put(&self, key: &str, entry: &Entry) -> Result<()>;
+
+    /// Removes every entry
+    fn clear(&self) -> Result<()>;
+}
+
+// ============================================================================
+// impl Cache
+// ============================================================================
+impl Cache {
+    /// A cache in memory with no endpoints configured
+    pub fn new() -> Cache {
+        Cache::with_store(MemoryStore::default())
+    }
+
+    /// A cache with no endpoints configured keeping its entries in `store`
+    pub fn with_store<S>(store: S) -> Cache
+    where
+        S: CacheStore + 'static,
+    {
+        Cache {
+            ttls: vec![],
+            store: Arc::new(store),
+        }
+    }
+
+    /// Caches responses from the endpoint and everything below it, such as
+    /// `api/2/priority` for `api/2/priority/3`. When several prefixes match
+    /// the longest wins, a zero TTL revalidates on every request.
+    pub fn ttl<E>(mut self, endpoint: E, ttl: Duration) -> Self
+    where
+        E: Into<String>,
+    {
+        let endpoint = endpoint.into();
+        self.ttls
+            .push((endpoint.trim_matches('/').to_string(), ttl));
+
+        self
+    }
+
+    /// Caches the reference data endpoints: priorities, statuses, status
+    /// categories, resolutions, issue types and fields
+    pub fn reference_data(self, ttl: Duration) -> Self {
+        REFERENCE_DATA
+            .iter()
+            .flat_map(|e| vec![format!("api/2/{}", e), format!("api/3/{}", e)])
+            .fold(self, |cache, endpoint| cache.ttl(endpoint, ttl))
+    }
+
+    /// Forgets every cached response
+    pub fn clear(&self) -> Result<()> {
+        self.store.clear()
+    }
+
+    /// How long responses for the URL stay fresh, `None` if they are not
+    /// cached
+    fn ttl_for(&self, url: &str) -> Option<Duration> {
+        let path = url.split('?').next().unwrap_or_default();
+        let endpoint = match path.find("/rest/") {
+            Some(i) => &path[i + "/rest/".len()..],
+            None => path,
+        };
+
+        self.ttls
+            .iter()
+            .filter(|(prefix, _)| {
+                endpoint == prefix
+                    || endpoint
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, ttl)| *ttl)
+    }
+}
+
+// ============================================================================
+// impl Entry
+// ============================================================================
+impl Entry {
+    fn new(response: &Response) -> Entry {
+        Entry {
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                // a replayed cookie would overwrite a newer session
+                .filter(|(n, _)| *n != SET_COOKIE)
+                .filter_map(|(n, v)| Some((n.to_string(), v.to_str().ok()?.to_string())))
+                .collect(),
+            body: response.body.clone(),
+            etag: response
+                .headers
+                .get(ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+            stored_at: now(),
+        }
+    }
+
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        now().saturating_sub(self.stored_at) < ttl.as_millis() as u64
+    }
+
+    fn response(&self) -> Response {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+
+        Response {
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK),
+            headers,
+            body: self.body.clone(),
+        }
+    }
+}
+
+// ============================================================================
+// impl MemoryStore
+// ============================================================================
+impl MemoryStore {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ============================================================================
+// impl DiskStore
+// ============================================================================
+impl DiskStore {
+    /// Stores entries in `dir`, which is created when the first entry is
+    /// written
+    pub fn new<P>(dir: P) -> DiskStore
+    where
+        P: Into<PathBuf>,
+    {
+        DiskStore { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let hash: String = sha256(key.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        self.dir.join(format!("{}.json", hash))
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl Default for Cache {
+    fn default() -> Cache {
+        Cache::new()
+    }
+}
+
+impl Middleware for Cache {
+    fn handle(&self, mut request: Request, next: Next) -> Result<Response> {
+        let ttl = match self.ttl_for(&request.url) {
+            Some(ttl) if request.method == Method::GET => ttl,
+            _ => return next.run(request),
+        };
+
+        let key = request.url.clone();
+        let cached = self.store.get(&key).unwrap_or(None);
+
+        if let Some(ref entry) = cached {
+            if entry.is_fresh(ttl) {
+                return Ok(entry.response());
+            }
+
+            if let Some(etag) = entry
+                .etag
+                .as_ref()
+                .and_then(|e| HeaderValue::from_str(e).ok())
+            {
+                request.headers.insert(IF_NONE_MATCH, etag);
+            }
+        }
+
+        let response = next.run(request)?;
+
+        match cached {
+            Some(mut entry) if response.status == StatusCode::NOT_MODIFIED => {
+                entry.stored_at = now();
+                let _ = self.store.put(&key, &entry);
+
+                Ok(entry.response())
+            }
+            _ => {
+                if response.status == StatusCode::OK {
+                    let _ = self.store.put(&key, &Entry::new(&response));
+                }
+
+                Ok(response)
+            }
+        }
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &str) -> Result<Option<Entry>> {
+        Ok(self.lock().get(key).cloned())
+    }
+
+    fn put(&self, key: &str, entry: &Entry) -> Result<()> {
+        self.lock().insert(key.to_string(), entry.clone());
+
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        self.lock().clear();
+
+        Ok(())
+    }
+}
+
+impl CacheStore for DiskStore {
+    fn get(&self, key: &str) -> Result<Option<Entry>> {
+        match fs::read_to_string(self.path(key)) {
+            Ok(text) => Ok(Some(serde_json::from_str(&text)?)),
+            Err(ref e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn put(&self, key: &str, entry: &Entry) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        // Write then rename, so concurrent readers never see half an entry
+        let path = self.path(key);
+        let partial = path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&partial, serde_json::to_string(entry)?)?;
+        fs::rename(&partial, &path)?;
+
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+const REFERENCE_DATA: &[&str] = &[
+    "priority",
+    "status",
+    "statuscategory",
+    "resolution",
+    "issuetype",
+    "field",
+];
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_for() {
+        let cache = Cache::new()
+            .reference_data(Duration::from_secs(60))
+            .ttl("/api/2/status/", Duration::from_secs(5));
+
+        let ttl = |url| cache.ttl_for(url).map(|t| t.as_secs());
+        assert_eq!(ttl("http://jira/rest/api/2/priority/3"), Some(60));
+        assert_eq!(ttl("http://jira/rest/api/2/priority?expand=x"), Some(60));
+        assert_eq!(ttl("http://jira/rest/api/2/status/1"), Some(5));
+        assert_eq!(ttl("http://jira/rest/api/2/statuscategory/1"), Some(60));
+        assert_eq!(ttl("http://jira/rest/api/2/issue/TEST-1"), None);
+        assert_eq!(ttl("http://jira/rest/api/2/fieldx"), None);
+    }
+
+    #[test]
+    fn test_freshness() {
+        let mut entry = Entry::new(&Response::new(StatusCode::OK, "{}"));
+        assert!(entry.is_fresh(Duration::from_secs(60)));
+        assert!(!entry.is_fresh(Duration::from_secs(0)));
+
+        entry.stored_at -= 61_000;
+        assert!(!entry.is_fresh(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_disk_store() {
+        let dir = std::env::temp_dir().join(format!("jirac-cache-{}", std::process::id()));
+        let store = DiskStore::new(&dir);
+
+        let mut response = Response::new(StatusCode::OK, r#"{"id": "3"}"#);
+        response
+            .headers
+            .insert(ETAG, HeaderValue::from_static("\"v1\""));
+        let entry = Entry::new(&response);
+
+        assert_eq!(store.get("a").unwrap(), None);
+        store.put("a", &entry).unwrap();
+        assert_eq!(store.get("a").unwrap(), Some(entry.clone()));
+        assert_eq!(entry.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(entry.response().headers[ETAG], "\"v1\"");
+
+        store.clear().unwrap();
+        assert_eq!(store.get("a").unwrap(), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+//! Detects what kind of JIRA deployment the client is talking to so calls
+//! that only exist on Cloud or on Server can pick the right endpoint, or fail
+//! before a request is sent.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::v2::ServerInfo;
+
+// ============================================================================
+// Public Enums
+// ============================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deployment {
+    Cloud,
+    Server,
+    DataCenter,
+}
+
+/// Features that are only available on some deployments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// The v3 REST API, Cloud only
+    RestV3,
+    /// Identifying users by account id, Cloud only
+    AccountIds,
+    /// Identifying users by username or key, removed from Cloud
+    Usernames,
+    /// Webhooks registered by apps through `/rest/api/2/webhook`, Cloud only
+    DynamicWebhooks,
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The kind of deployment
+    pub deployment: Deployment,
+
+    /// Major, minor and patch version numbers, Cloud reports 1001.0.0 or
+    /// similar so this is only meaningful on Server and Data Center
+    pub version: Vec<u32>,
+}
+
+impl Capabilities {
+    pub fn new(deployment: Deployment, version: Vec<u32>) -> Capabilities {
+        Capabilities {
+            deployment,
+            version,
+        }
+    }
+
+    pub fn from_server_info(info: &ServerInfo) -> Capabilities {
+        let deployment = match info.deployment_type.to_lowercase().as_str() {
+            "cloud" => Deployment::Cloud,
+            "datacenter" | "data center" => Deployment::DataCenter,
+            _ => Deployment::Server,
+        };
+
+        Capabilities::new(deployment, info.version_numbers.clone())
+    }
+
+    pub fn is_cloud(&self) -> bool {
+        self.deployment == Deployment::Cloud
+    }
+
+    /// Returns true if the version is the same as or newer than the given
+    /// version, e.g. `at_least(&[8, 14])`
+    pub fn at_least(&self, version: &[u32]) -> bool {
+        for (i, wanted) in version.iter().enumerate() {
+            let actual = self.version.get(i).copied().unwrap_or(0);
+            if actual != *wanted {
+                return actual > *wanted;
+            }
+        }
+
+        true
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::RestV3 | Feature::AccountIds | Feature::DynamicWebhooks => self.is_cloud(),
+            Feature::Usernames => !self.is_cloud(),
+        }
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for Deployment {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Deployment::Cloud => write!(f, "Cloud"),
+            Deployment::Server => write!(f, "Server"),
+            Deployment::DataCenter => write!(f, "Data Center"),
+        }
+    }
+}
+
+impl std::fmt::Display for Feature {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Feature::RestV3 => write!(f, "the v3 REST API"),
+            Feature::AccountIds => write!(f, "account ids"),
+            Feature::Usernames => write!(f, "usernames and user keys"),
+            Feature::DynamicWebhooks => write!(f, "dynamic webhooks"),
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at_least() {
+        let c = Capabilities::new(Deployment::DataCenter, vec![8, 14, 2]);
+
+        assert!(c.at_least(&[8]));
+        assert!(c.at_least(&[8, 14]));
+        assert!(c.at_least(&[8, 14, 2]));
+        assert!(c.at_least(&[7, 20]));
+        assert!(!c.at_least(&[8, 15]));
+        assert!(!c.at_least(&[9]));
+    }
+
+    #[test]
+    fn test_supports() {
+        let cloud = Capabilities::new(Deployment::Cloud, vec![1001, 0, 0]);
+        let server = Capabilities::new(Deployment::Server, vec![8, 5, 4]);
+
+        assert!(cloud.supports(Feature::AccountIds));
+        assert!(!cloud.supports(Feature::Usernames));
+        assert!(server.supports(Feature::Usernames));
+        assert!(!server.supports(Feature::RestV3));
+    }
+}
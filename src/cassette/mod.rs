@@ -0,0 +1,540 @@
+//! Records the client's HTTP traffic to a cassette file and replays it, so
+//! tests written against a real JIRA can run offline.
+//!
+//! ```no_run
+//! use jirac::cassette::Cassette;
+//! use jirac::{Client, Credentials};
+//!
+//! // Records on the first run, replays from then on
+//! let cassette = Cassette::auto("tests/cassettes/search.yaml").unwrap();
+//! let client = Client::new(
+//!     "https://staging.example.com",
+//!     Credentials::new_basic("jdoe", "token").unwrap(),
+//! )
+//! .with_cassette(cassette);
+//! ```
+//!
+//! The file is YAML when its extension is `.yaml` or `.yml`, which needs the
+//! `yaml` feature, and JSON otherwise. Credentials are never written: the client adds them after
+//! recording, and the values of sensitive headers and query parameters are
+//! replaced with `REDACTED`. When replaying, each request is matched on its
+//! method, path, query and body against the recorded interactions that have
+//! not been used yet, a request that matches none fails with
+//! `Error::UnmatchedRequest` without anything being sent.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::{Deserialize, Serialize};
+use crate::{Error, Result};
+use reqwest::Url;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Error as IoError, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+// ============================================================================
+// Public Enums
+// ============================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Requests are sent and saved along with their responses
+    Record,
+    /// Responses come from the cassette, nothing is sent
+    Replay,
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// A request and the response it got
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+
+    /// Path and query, relative to the client's host
+    pub uri: String,
+
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedResponse {
+    pub status: u16,
+
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    #[serde(default)]
+    pub body: String,
+}
+
+/// A cassette file, attach it to a client with `Client::with_cassette`
+#[derive(Debug)]
+pub struct Cassette {
+    pub path: PathBuf,
+    pub mode: Mode,
+
+    /// Header names, lower case, whose values are redacted
+    pub redacted_headers: Vec<String>,
+
+    /// Query parameters whose values are redacted
+    pub redacted_params: Vec<String>,
+
+    state: Mutex<State>,
+}
+
+impl Cassette {
+    /// Starts an empty cassette that is written to `path` as requests are
+    /// made, replacing any existing file
+    pub fn record<P>(path: P) -> Cassette
+    where
+        P: Into<PathBuf>,
+    {
+        Cassette::new(path.into(), Mode::Record, vec![])
+    }
+
+    /// Loads a cassette to replay
+    pub fn replay<P>(path: P) -> Result<Cassette>
+    where
+        P: Into<PathBuf>,
+    {
+        let path = path.into();
+        let text = fs::read_to_string(&path)?;
+        let file: File = if is_yaml(&path) {
+            from_yaml(&text)?
+        } else {
+            serde_json::from_str(&text)?
+        };
+
+        Ok(Cassette::new(path, Mode::Replay, file.interactions))
+    }
+
+    /// Replays the cassette if the file exists, records it otherwise
+    pub fn auto<P>(path: P) -> Result<Cassette>
+    where
+        P: Into<PathBuf>,
+    {
+        let path = path.into();
+
+        if path.exists() {
+            Cassette::replay(path)
+        } else {
+            Ok(Cassette::record(path))
+        }
+    }
+
+    /// Redacts another header, on top of `Authorization`, `Cookie`,
+    /// `Set-Cookie` and `Proxy-Authorization`
+    pub fn redact_header<H>(mut self, header: H) -> Cassette
+    where
+        H: Into<String>,
+    {
+        self.redacted_headers.push(header.into().to_lowercase());
+        self
+    }
+
+    /// Redacts another query parameter, on top of `jwt`
+    pub fn redact_param<P>(mut self, param: P) -> Cassette
+    where
+        P: Into<String>,
+    {
+        self.redacted_params.push(param.into());
+        self
+    }
+
+    /// The interactions recorded so far, or loaded to replay
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.lock().interactions.clone()
+    }
+
+    /// Finds the first unused interaction matching the request
+    pub(crate) fn replay_request(&self, request: &RecordedRequest) -> Result<RecordedResponse> {
+        let request = self.redact(request.clone());
+        let mut state = self.lock();
+        let State { interactions, used } = &mut *state;
+
+        let found = interactions
+            .iter()
+            .zip(used.iter_mut())
+            .find(|(i, used)| !**used && matches(&i.request, &request));
+
+        match found {
+            Some((interaction, used)) => {
+                *used = true;
+                Ok(interaction.response.clone())
+            }
+            None => Err(Error::UnmatchedRequest {
+                method: request.method,
+                uri: request.uri,
+            }),
+        }
+    }
+
+    /// Adds an interaction and writes the cassette out
+    pub(crate) fn record_interaction(&self, interaction: Interaction) -> Result<()> {
+        let mut response = interaction.response;
+        self.redact_map(&mut response.headers);
+
+        let interaction = Interaction {
+            request: self.redact(interaction.request),
+            response,
+        };
+
+        let mut state = self.lock();
+        state.interactions.push(interaction);
+        state.used.push(true);
+
+        self.save(&state.interactions)
+    }
+
+    fn new(path: PathBuf, mode: Mode, interactions: Vec<Interaction>) -> Cassette {
+        Cassette {
+            path,
+            mode,
+            redacted_headers: [
+                "authorization",
+                "cookie",
+                "set-cookie",
+                "proxy-authorization",
+            ]
+            .iter()
+            .map(|h| h.to_string())
+            .collect(),
+            redacted_params: vec!["jwt".to_string()],
+            state: Mutex::new(State {
+                used: vec![false; interactions.len()],
+                interactions,
+            }),
+        }
+    }
+
+    fn save(&self, interactions: &[Interaction]) -> Result<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = File {
+            interactions: interactions.to_vec(),
+        };
+        let text = if is_yaml(&self.path) {
+            to_yaml(&file)?
+        } else {
+            serde_json::to_string_pretty(&file)?
+        };
+
+        Ok(fs::write(&self.path, text)?)
+    }
+
+    fn redact(&self, mut request: RecordedRequest) -> RecordedRequest {
+        self.redact_map(&mut request.headers);
+
+        if let Some((path, query)) = split_uri(&request.uri) {
+            if query.iter().any(|(k, _)| self.redacted_params.contains(k)) {
+                let mut url = Url::parse("http://localhost/").unwrap();
+                url.set_path(&path);
+                url.query_pairs_mut()
+                    .extend_pairs(query.into_iter().map(|(k, v)| {
+                        match self.redacted_params.contains(&k) {
+                            true => (k, REDACTED.to_string()),
+                            false => (k, v),
+                        }
+                    }));
+
+                request.uri = format!("{}?{}", url.path(), url.query().unwrap_or_default());
+            }
+        }
+
+        request
+    }
+
+    fn redact_map(&self, headers: &mut BTreeMap<String, String>) {
+        for (name, value) in headers.iter_mut() {
+            if self.redacted_headers.contains(&name.to_lowercase()) {
+                *value = REDACTED.to_string();
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+const REDACTED: &str = "REDACTED";
+
+#[derive(Serialize, Deserialize)]
+struct File {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug)]
+struct State {
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+}
+
+fn is_yaml(path: &Path) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"),
+        None => false,
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn from_yaml(text: &str) -> Result<File> {
+    serde_yaml::from_str(text).map_err(|e| IoError::new(ErrorKind::InvalidData, e).into())
+}
+
+#[cfg(feature = "yaml")]
+fn to_yaml(file: &File) -> Result<String> {
+    serde_yaml::to_string(file).map_err(|e| IoError::new(ErrorKind::InvalidData, e).into())
+}
+
+#[cfg(not(feature = "yaml"))]
+fn from_yaml(_text: &str) -> Result<File> {
+    Err(yaml_unsupported())
+}
+
+#[cfg(not(feature = "yaml"))]
+fn to_yaml(_file: &File) -> Result<String> {
+    Err(yaml_unsupported())
+}
+
+#[cfg(not(feature = "yaml"))]
+fn yaml_unsupported() -> Error {
+    let message = "YAML cassettes need the yaml feature, use a .json file instead";
+    IoError::new(ErrorKind::Unsupported, message).into()
+}
+
+/// Splits a uri into its path and query pairs
+fn split_uri(uri: &str) -> Option<(String, Vec<(String, String)>)> {
+    let url = Url::parse("http://localhost/").ok()?.join(uri).ok()?;
+
+    Some((
+        url.path().to_string(),
+        url.query_pairs().into_owned().collect(),
+    ))
+}
+
+/// Requests match when the method, path, query in any order and body are
+/// the same, JSON bodies are compared as JSON
+fn matches(recorded: &RecordedRequest, request: &RecordedRequest) -> bool {
+    if !recorded.method.eq_ignore_ascii_case(&request.method) {
+        return false;
+    }
+
+    match (split_uri(&recorded.uri), split_uri(&request.uri)) {
+        (Some((a_path, mut a_query)), Some((b_path, mut b_query))) => {
+            a_query.sort();
+            b_query.sort();
+            if a_path != b_path || a_query != b_query {
+                return false;
+            }
+        }
+        _ => return false,
+    }
+
+    let body = |b: &Option<String>| b.clone().filter(|b| !b.is_empty());
+    match (body(&recorded.body), body(&request.body)) {
+        (Some(a), Some(b)) => match (
+            serde_json::from_str::<Value>(&a),
+            serde_json::from_str::<Value>(&b),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => a == b,
+        },
+        (a, b) => a == b,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, uri: &str, body: Option<&str>) -> RecordedRequest {
+        RecordedRequest {
+            method: method.to_string(),
+            uri: uri.to_string(),
+            headers: BTreeMap::new(),
+            body: body.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let recorded = request(
+            "POST",
+            "/rest/api/2/search?a=1&b=2",
+            Some(r#"{"x": 1, "y": 2}"#),
+        );
+
+        assert!(matches(
+            &recorded,
+            &request(
+                "post",
+                "/rest/api/2/search?b=2&a=1",
+                Some(r#"{"y":2,"x":1}"#)
+            )
+        ));
+        assert!(!matches(
+            &recorded,
+            &request(
+                "POST",
+                "/rest/api/2/search?a=1",
+                Some(r#"{"x": 1, "y": 2}"#)
+            )
+        ));
+        assert!(!matches(
+            &recorded,
+            &request("POST", "/rest/api/2/search?a=1&b=2", Some(r#"{"x": 2}"#))
+        ));
+        assert!(matches(
+            &request("GET", "/rest/api/2/myself", None),
+            &request("GET", "/rest/api/2/myself", Some(""))
+        ));
+    }
+
+    #[test]
+    fn test_redact() {
+        let cassette = Cassette::record("unused.json").redact_header("X-Secret");
+        let mut req = request("GET", "/rest/api/2/myself?jwt=abc&expand=groups", None);
+        req.headers
+            .insert("Authorization".into(), "Basic abc".into());
+        req.headers.insert("x-secret".into(), "s".into());
+        req.headers
+            .insert("accept".into(), "application/json".into());
+
+        let req = cassette.redact(req);
+
+        assert_eq!(req.uri, "/rest/api/2/myself?jwt=REDACTED&expand=groups");
+        assert_eq!(req.headers["Authorization"], "REDACTED");
+        assert_eq!(req.headers["x-secret"], "REDACTED");
+        assert_eq!(req.headers["accept"], "application/json");
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let exts: &[&str] = match cfg!(feature = "yaml") {
+            true => &["json", "yaml"],
+            false => &["json"],
+        };
+
+        for ext in exts {
+            let path = std::env::temp_dir()
+                .join(format!("jirac-cassette-{}", std::process::id()))
+                .join(format!("cassette.{}", ext));
+
+            let cassette = Cassette::record(&path);
+            for n in 0..2 {
+                cassette
+                    .record_interaction(Interaction {
+                        request: request("GET", "/rest/api/2/issue/TEST-1", None),
+                        response: RecordedResponse {
+                            status: 200,
+                            headers: BTreeMap::new(),
+                            body: format!("{{\"n\": {}, \"text\": \"a: \\\"b\\\"\\n\"}}", n),
+                        },
+                    })
+                    .unwrap();
+            }
+
+            let replay = Cassette::replay(&path).unwrap();
+            assert_eq!(replay.interactions(), cassette.interactions());
+
+            let get = request("GET", "/rest/api/2/issue/TEST-1", None);
+            assert!(replay
+                .replay_request(&get)
+                .unwrap()
+                .body
+                .contains("\"n\": 0"));
+            assert!(replay
+                .replay_request(&get)
+                .unwrap()
+                .body
+                .contains("\"n\": 1"));
+            match replay.replay_request(&get) {
+                Err(Error::UnmatchedRequest { method, uri }) => {
+                    assert_eq!(method, "GET");
+                    assert_eq!(uri, "/rest/api/2/issue/TEST-1");
+                }
+                other => panic!("unexpected result {:?}", other),
+            }
+
+            fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_hand_written_yaml() {
+        let file = from_yaml(
+            r#"
+# edited by hand
+interactions:
+- request:
+    method: GET
+    uri: '/rest/api/2/issue/TEST-1?it''s=1'
+  response:
+    status: 200
+    headers:
+      content-type: application/json
+    body: |
+      {"key": "TEST-1",
+       "summary": "\x41"}
+- request:
+    method: GET
+    uri: "/rest/api/2/myself?q=\x41"
+  response:
+    status: 204
+"#,
+        )
+        .unwrap();
+
+        let first = &file.interactions[0];
+        assert_eq!(first.request.uri, "/rest/api/2/issue/TEST-1?it's=1");
+        assert_eq!(
+            first.response.body,
+            "{\"key\": \"TEST-1\",\n \"summary\": \"\\x41\"}\n"
+        );
+        assert_eq!(file.interactions[1].request.uri, "/rest/api/2/myself?q=A");
+        assert_eq!(file.interactions[1].response.body, "");
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    #[test]
+    fn test_yaml_needs_feature() {
+        let path = std::env::temp_dir().join("jirac-no-yaml.yaml");
+        let cassette = Cassette::record(&path);
+
+        let result = cassette.record_interaction(Interaction {
+            request: request("GET", "/rest/api/2/myself", None),
+            response: RecordedResponse {
+                status: 200,
+                headers: BTreeMap::new(),
+                body: "{}".to_string(),
+            },
+        });
+
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+}
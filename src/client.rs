@@ -3,16 +3,26 @@
 // ============================================================================
 // Use
 // ============================================================================
+use crate::cassette::{Cassette, Interaction, Mode, RecordedRequest, RecordedResponse};
+use crate::logging::RequestSpan;
+use crate::middleware::{Middleware, Next, Request as HttpRequest, Response as HttpResponse};
+use crate::v2::ServerInfo;
+use crate::ClientBuilder;
 use crate::Credentials;
+use crate::Logging;
 use crate::Options;
 use crate::Resp;
 use crate::Response;
+use crate::Result;
 use crate::Serialize;
-use crate::{Error, Errors};
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use crate::{Capabilities, Feature};
+use crate::{Error, HttpContext};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE, COOKIE};
 use reqwest::{Method, StatusCode};
 use serde::de::DeserializeOwned;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Error as IoError, ErrorKind};
+use std::sync::Arc;
 
 // ============================================================================
 // Public Structures
@@ -26,6 +36,19 @@ pub struct Client {
     pub credentials: Credentials,
     pub headers: HeaderMap,
     pub query: HashMap<String, String>,
+
+    /// What the instance supports, unknown until `detect_capabilities` or
+    /// `with_capabilities` is called
+    pub(crate) capabilities: Option<Capabilities>,
+
+    /// Records or replays the requests made, see `with_cassette`
+    pub(crate) cassette: Option<Arc<Cassette>>,
+
+    /// Runs around every request in order, see `with_middleware`
+    pub(crate) middleware: Vec<Arc<dyn Middleware>>,
+
+    /// What is logged about requests when the `tracing` feature is enabled
+    pub(crate) logging: Logging,
 }
 
 impl Client {
@@ -40,6 +63,104 @@ impl Client {
             credentials,
             headers: HeaderMap::new(),
             query: HashMap::new(),
+            capabilities: None,
+            cassette: None,
+            middleware: vec![],
+            logging: Logging::new(),
+        }
+    }
+
+    /// Starts building a client, for when more than the host and
+    /// credentials need setting
+    pub fn builder<H>(host: H, credentials: Credentials) -> ClientBuilder
+    where
+        H: Into<String>,
+    {
+        ClientBuilder::new(host, credentials)
+    }
+
+    /// Fetches the server info to find out whether the instance is Cloud,
+    /// Server or Data Center and which version it runs. Calls to features
+    /// the instance does not support fail with `Error::Unsupported` after
+    /// this.
+    pub fn detect_capabilities(&mut self) -> Result<Capabilities> {
+        let info = ServerInfo::get(self)?.data;
+        let capabilities = Capabilities::from_server_info(&info);
+
+        self.capabilities = Some(capabilities.clone());
+
+        Ok(capabilities)
+    }
+
+    /// Sets the capabilities without asking the server, useful when the
+    /// deployment is known up front
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+
+        self
+    }
+
+    /// Records requests and their responses to the cassette, or answers them
+    /// from it without sending anything, depending on its mode. Clones of
+    /// the client share the cassette.
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(Arc::new(cassette));
+
+        self
+    }
+
+    /// Adds a middleware after those already added, so it runs closer to
+    /// the network. Clones of the client share it.
+    pub fn with_middleware<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware + 'static,
+    {
+        self.middleware.push(Arc::new(middleware));
+
+        self
+    }
+
+    /// What the instance supports, `None` until it has been detected or set
+    pub fn capabilities(&self) -> Option<&Capabilities> {
+        self.capabilities.as_ref()
+    }
+
+    /// The cassette requests are recorded to or replayed from, if any
+    pub fn cassette(&self) -> Option<&Cassette> {
+        self.cassette.as_deref()
+    }
+
+    /// What is logged about requests when the `tracing` feature is enabled
+    pub fn logging(&self) -> &Logging {
+        &self.logging
+    }
+
+    /// Fails with `Error::Unsupported` if the instance is known not to support
+    /// the feature, when capabilities have not been detected everything is
+    /// assumed to be supported.
+    pub fn ensure(&self, feature: Feature) -> Result<()> {
+        match &self.capabilities {
+            Some(c) if !c.supports(feature) => Err(Error::Unsupported {
+                feature,
+                deployment: c.deployment,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns true if the instance is known to be Cloud
+    pub fn is_cloud(&self) -> bool {
+        self.capabilities
+            .as_ref()
+            .is_some_and(Capabilities::is_cloud)
+    }
+
+    /// Ends the session when the client uses session credentials, does
+    /// nothing otherwise
+    pub fn logout(&self) -> Result<()> {
+        match self.credentials {
+            Credentials::Session(ref session) => session.logout(self),
+            _ => Ok(()),
         }
     }
 
@@ -116,46 +237,236 @@ impl Client {
         self.request::<D>(Method::GET, url, None)
     }
 
+    pub fn delete<D>(&self, url: &str) -> Response<D>
+    where
+        D: DeserializeOwned,
+    {
+        self.request::<D>(Method::DELETE, url, None)
+    }
+
     pub fn request<D>(&self, method: Method, url: &str, body: Option<Vec<u8>>) -> Response<D>
     where
         D: DeserializeOwned,
     {
-        let query = Client::unpack_query(&self.query);
+        let mut query = Client::unpack_query(&self.query);
+        if url.contains('?') && !query.is_empty() {
+            query.replace_range(..1, "&");
+        }
+        let mut span = RequestSpan::new(&method, url);
         let url = format!("{}/rest/{}{}", self.host, url, query);
+        let res = self.exchange(&mut span, method.clone(), &url, body);
+        span.finish(&res);
+        let res = res?;
+
+        if let Credentials::Session(ref session) = self.credentials {
+            session.update(&res.headers);
+        }
+
+        let HttpResponse {
+            status,
+            headers,
+            body,
+        } = res;
+        let data = if body.is_empty() { "null" } else { &body };
+        let context = || HttpContext::new(method.clone(), &url, status, &headers, &body);
+
+        if status.is_client_error() || status.is_server_error() {
+            return Err(Error::from_status(context()));
+        }
+
+        match serde_json::from_str::<D>(data) {
+            Ok(data) => Ok(Resp { data, headers }),
+            // Not JSON at all, rather than JSON of the wrong shape
+            Err(_) if serde_json::from_str::<serde::de::IgnoredAny>(data).is_err() => {
+                Err(Error::UnexpectedContentType(Box::new(context())))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Sends the request, retrying once if the credentials need renewing
+    fn exchange(
+        &self,
+        span: &mut RequestSpan,
+        method: Method,
+        url: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<HttpResponse> {
+        let res = self.send(span, method.clone(), url, body.clone())?;
+
+        // The access token may have been revoked or expired early, or the
+        // session timed out, refresh it and retry once
+        if res.status == StatusCode::UNAUTHORIZED {
+            let replaying = self
+                .cassette
+                .as_ref()
+                .is_some_and(|c| c.mode == Mode::Replay);
+
+            match self.credentials {
+                // The retry was recorded too, logging in would go to JIRA
+                Credentials::OAuth2(_) | Credentials::Session(_) if replaying => {}
+                Credentials::OAuth2(ref provider) => {
+                    provider.refresh(self)?;
+                }
+                Credentials::Session(ref session) => {
+                    session.login(self)?;
+                }
+                _ => return Ok(res),
+            }
+
+            span.retry();
+            return self.send(span, method, url, body);
+        }
+
+        Ok(res)
+    }
+
+    fn send(
+        &self,
+        span: &RequestSpan,
+        method: Method,
+        url: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<HttpResponse> {
+        let mut request = HttpRequest::new(method, url);
+        request.body = body;
+        request
+            .headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for (name, value) in self.headers.iter() {
+            request.headers.insert(name, value.clone());
+        }
+
+        span.request(&self.logging, &request);
+        let response = Next::new(self).run(request)?;
+        span.response(&self.logging, &response);
+
+        Ok(response)
+    }
+
+    /// Sends a request that made it through every middleware, or answers it
+    /// from the cassette
+    pub(crate) fn dispatch(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let recorded = self
+            .cassette
+            .as_ref()
+            .map(|cassette| (cassette, self.recorded_request(&request)));
+
+        if let Some((cassette, ref recorded)) = recorded {
+            if cassette.mode == Mode::Replay {
+                return replayed(cassette.replay_request(recorded)?);
+            }
+        }
+
+        let HttpRequest {
+            method,
+            url,
+            headers,
+            body,
+        } = request;
+        let signature = match self.credentials {
+            Credentials::OAuth1(ref o) => Some(o.authorization(method.as_str(), &url)?),
+            Credentials::Connect(ref app) => {
+                Some(app.authorization(method.as_str(), &url, &self.host)?)
+            }
+            _ => None,
+        };
         let req = self.client.request(method, &url);
         let builder = match self.credentials {
             Credentials::Basic(ref user, ref pass) => req
                 .basic_auth(user.to_owned(), Some(pass.to_owned())),
+            Credentials::Pat(ref token) => req
+                .bearer_auth(token),
             Credentials::OAuth(ref token) => req
-                .bearer_auth(token)
-        }.header(CONTENT_TYPE, HeaderValue::from_static("application/json")).headers(self.headers.clone());
+                .bearer_auth(token),
+            Credentials::OAuth1(_) | Credentials::Connect(_) => req
+                .header(AUTHORIZATION, signature.unwrap_or_default()),
+            Credentials::OAuth2(ref provider) => req
+                .bearer_auth(provider.token(self)?),
+            Credentials::Session(ref session) => req
+                .header(COOKIE, session.header(self)?),
+        }.headers(headers);
 
         let mut res = match body {
             Some(body) => builder.body(body).send()?,
             _ => builder.send()?,
         };
+        let response = HttpResponse {
+            status: res.status(),
+            headers: res.headers().clone(),
+            body: res.text()?,
+        };
 
-        let body = res.text()?;
-        let data = if body == "" { "null" } else { &body };
-
-        match res.status() {
-            StatusCode::UNAUTHORIZED => Err(Error::Unauthorized),
-            StatusCode::METHOD_NOT_ALLOWED => Err(Error::MethodNotAllowed),
-            StatusCode::NOT_FOUND => Err(Error::NotFound),
-            StatusCode::PRECONDITION_FAILED => Err(Error::PreconditionFailed),
-            StatusCode::FORBIDDEN => Err(Error::Forbidden),
-            client_err if client_err.is_client_error() => Err(Error::Fault {
-                code: res.status(),
-                errors: serde_json::from_str::<Errors>(&body)?,
-            }),
-            _ => Ok(Resp {
-                data: serde_json::from_str::<D>(data)?,
-                headers: res.headers().clone(),
-            }),
+        if let Some((cassette, request)) = recorded {
+            cassette.record_interaction(Interaction {
+                request,
+                response: RecordedResponse {
+                    status: response.status.as_u16(),
+                    headers: header_map(&response.headers),
+                    body: response.body.clone(),
+                },
+            })?;
+        }
+
+        Ok(response)
+    }
+
+    /// The request as it is written to a cassette, credentials are added
+    /// after this so they are never part of it
+    fn recorded_request(&self, request: &HttpRequest) -> RecordedRequest {
+        let url = request.url.as_str();
+
+        RecordedRequest {
+            method: request.method.to_string(),
+            uri: url
+                .strip_prefix(self.host.as_str())
+                .unwrap_or(url)
+                .to_string(),
+            headers: header_map(&request.headers),
+            body: request
+                .body
+                .as_ref()
+                .map(|b| String::from_utf8_lossy(b).into_owned()),
         }
     }
 }
 
+// ============================================================================
+// Private
+// ============================================================================
+/// Turns a recorded response back into one the client can use
+fn replayed(recorded: RecordedResponse) -> Result<HttpResponse> {
+    let status = StatusCode::from_u16(recorded.status).map_err(|_| {
+        IoError::new(
+            ErrorKind::InvalidData,
+            format!("invalid recorded status {}", recorded.status),
+        )
+    })?;
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in recorded.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+
+    Ok(HttpResponse {
+        status,
+        headers,
+        body: recorded.body,
+    })
+}
+
+fn header_map(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -0,0 +1,623 @@
+//! Atlassian Connect JWT authentication.
+//!
+//! A Connect app signs the requests it makes to JIRA with a HS256 JWT using
+//! the shared secret it was given in the `installed` lifecycle callback, use
+//! `Credentials::new_connect` and the client does this for every request.
+//! Requests JIRA makes to the app, lifecycle callbacks and webhooks, carry a
+//! JWT signed the same way which `verify` checks.
+//!
+//! Lifecycle callbacks of apps that opt into signed installs are signed with
+//! RS256 instead, by a key Atlassian publishes under the `kid` of the token
+//! header. `install_key` fetches it and `verify_install` checks the token
+//! against it, along with `aud`, which has to be the app's base URL:
+//!
+//! ```no_run
+//! # fn main() -> jirac::Result<()> {
+//! # let (token, http) = ("", reqwest::Client::new());
+//! use jirac::connect::{install_key, key_id, verify_install};
+//!
+//! let key = install_key(&http, &key_id(token)?)?;
+//! let claims = verify_install(token, &key, "POST", "/installed", "https://app.example.com")?;
+//! // claims.iss is the client key of the tenant installing the app
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Both bind the token to the request through the query string hash, a
+//! SHA-256 of the method, path and sorted query, see `query_string_hash`.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::oauth1::encode;
+use crate::oauth2::base64_url;
+use crate::{Deserialize, Serialize};
+use crate::{Error, Result};
+use openssl::hash::{hash, MessageDigest};
+use openssl::pkey::PKey;
+use openssl::sign::{Signer, Verifier};
+use reqwest::Url;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Constants
+// ============================================================================
+/// Where Atlassian publishes the public keys signed installs are verified with
+pub const INSTALL_KEYS_URL: &str = "https://connect-install-keys.atlassian.com";
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// The app key and the shared secret JIRA gave the app for one tenant
+#[derive(Clone)]
+pub struct ConnectApp {
+    /// The `key` from the app descriptor, sent as the token issuer
+    pub key: String,
+
+    /// How long signed tokens are valid for in seconds, 180 by default
+    pub expires_in: u64,
+
+    shared_secret: String,
+}
+
+/// The claims of a Connect JWT
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Claims {
+    /// The app key on outgoing tokens, the tenant's client key on incoming
+    /// ones
+    pub iss: String,
+    pub iat: u64,
+    pub exp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qsh: Option<String>,
+    /// The account id of the user, when the request is made on behalf of one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<Value>,
+}
+
+impl ConnectApp {
+    pub fn new<K, S>(key: K, shared_secret: S) -> ConnectApp
+    where
+        K: Into<String>,
+        S: Into<String>,
+    {
+        ConnectApp {
+            key: key.into(),
+            expires_in: 180,
+            shared_secret: shared_secret.into(),
+        }
+    }
+
+    /// Signs a token for a request to `url`, which is either absolute or
+    /// relative to `base_url`, the base URL of the JIRA instance
+    pub fn token(&self, method: &str, url: &str, base_url: &str) -> Result<String> {
+        let iat = now();
+        let claims = Claims {
+            iss: self.key.clone(),
+            iat,
+            exp: iat + self.expires_in,
+            qsh: Some(query_string_hash(method, url, base_url)?),
+            sub: None,
+            aud: None,
+            context: None,
+        };
+
+        sign(&claims, &self.shared_secret)
+    }
+
+    /// The value of the Authorization header for a request
+    pub fn authorization(&self, method: &str, url: &str, base_url: &str) -> Result<String> {
+        Ok(format!("JWT {}", self.token(method, url, base_url)?))
+    }
+}
+
+impl Claims {
+    /// Reads the claims without checking the signature, used to find the
+    /// `iss` of an incoming token and so which tenant's shared secret
+    /// verifies it
+    pub fn decode_unverified(token: &str) -> Result<Claims> {
+        let (_, claims, _) = split(token)?;
+
+        serde_json::from_slice(&base64_url_decode(claims)?)
+            .map_err(|_| Error::InvalidJwt("malformed claims".to_string()))
+    }
+}
+
+// ============================================================================
+// Public Functions
+// ============================================================================
+/// Builds the canonical request the query string hash is computed over:
+/// the upper case method, the path relative to `base_url` and the query
+/// sorted by parameter with the `jwt` parameter left out, joined with `&`
+pub fn canonical_request(method: &str, url: &str, base_url: &str) -> Result<String> {
+    let base =
+        Url::parse(base_url).map_err(|_| Error::InvalidJwt(format!("bad url {}", base_url)))?;
+    let url = base
+        .join(url)
+        .map_err(|_| Error::InvalidJwt(format!("bad url {}", url)))?;
+
+    let prefix = base.path().trim_end_matches('/');
+    let path = url.path();
+    let path = match path.strip_prefix(prefix) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+        _ => path,
+    };
+    let path = match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        path => path.replace('&', "%26"),
+    };
+
+    let mut params: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (key, value) in url.query_pairs() {
+        if key != "jwt" {
+            params.entry(encode(&key)).or_default().push(encode(&value));
+        }
+    }
+
+    let query = params
+        .into_iter()
+        .map(|(key, mut values)| {
+            values.sort();
+            format!("{}={}", key, values.join(","))
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    Ok(format!("{}&{}&{}", method.to_uppercase(), path, query))
+}
+
+/// The hex encoded SHA-256 of the canonical request
+pub fn query_string_hash(method: &str, url: &str, base_url: &str) -> Result<String> {
+    let digest = hash(
+        MessageDigest::sha256(),
+        canonical_request(method, url, base_url)?.as_bytes(),
+    )?;
+
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Verifies a HS256 JWT JIRA sent to the app, `url` is the path and query
+/// the request was made to and `base_url` the app's base URL from its
+/// descriptor. Checks the signature, that `iss` is the expected client key,
+/// that the token has not expired and that its `qsh` matches the request.
+/// RS256 tokens of signed installs are rejected, see `verify_install`.
+pub fn verify(
+    token: &str,
+    shared_secret: &str,
+    client_key: &str,
+    method: &str,
+    url: &str,
+    base_url: &str,
+) -> Result<Claims> {
+    let (_, _, signature) = split(token)?;
+
+    match header(token)?.alg.as_str() {
+        "HS256" => {}
+        "RS256" => {
+            return Err(Error::InvalidJwt(
+                "RS256 tokens are verified with verify_install".to_string(),
+            ))
+        }
+        alg => return Err(Error::InvalidJwt(format!("unsupported alg {}", alg))),
+    }
+
+    let signed = &token[..token.len() - signature.len() - 1];
+    let expected = hmac(shared_secret, signed)?;
+    let signature = base64_url_decode(signature)?;
+    if expected.len() != signature.len() || !openssl::memcmp::eq(&expected, &signature) {
+        return Err(Error::InvalidJwt("bad signature".to_string()));
+    }
+
+    let claims = Claims::decode_unverified(token)?;
+    if claims.iss != client_key {
+        return Err(Error::InvalidJwt(format!("unexpected iss {}", claims.iss)));
+    }
+
+    check_request(claims, method, url, base_url)
+}
+
+/// Verifies a RS256 JWT sent with a signed install or uninstall callback
+/// against the PEM encoded public key published under its `kid`. Checks the
+/// signature, that `aud` is `base_url`, that the token has not expired and
+/// that its `qsh` matches the request. The `iss` of the claims returned is
+/// the client key of the tenant.
+pub fn verify_install(
+    token: &str,
+    public_key: &str,
+    method: &str,
+    url: &str,
+    base_url: &str,
+) -> Result<Claims> {
+    let (_, _, signature) = split(token)?;
+
+    let alg = header(token)?.alg;
+    if alg != "RS256" {
+        return Err(Error::InvalidJwt(format!("unsupported alg {}", alg)));
+    }
+
+    let key = PKey::public_key_from_pem(public_key.as_bytes())
+        .map_err(|_| Error::InvalidJwt("malformed install key".to_string()))?;
+    let signed = &token[..token.len() - signature.len() - 1];
+    let signature = base64_url_decode(signature)?;
+
+    let mut verifier = Verifier::new(MessageDigest::sha256(), &key)?;
+    verifier.update(signed.as_bytes())?;
+    if !verifier.verify(&signature).unwrap_or(false) {
+        return Err(Error::InvalidJwt("bad signature".to_string()));
+    }
+
+    let claims = Claims::decode_unverified(token)?;
+    let app = base_url.trim_end_matches('/');
+    let audience = |aud: &Value| aud.as_str().map(|a| a.trim_end_matches('/')) == Some(app);
+    let aud_matches = match claims.aud {
+        Some(Value::Array(ref auds)) => auds.iter().any(audience),
+        Some(ref aud) => audience(aud),
+        None => false,
+    };
+    if !aud_matches {
+        return Err(Error::InvalidJwt(
+            "aud is not the app's base url".to_string(),
+        ));
+    }
+
+    check_request(claims, method, url, base_url)
+}
+
+/// The `kid` of the token header, the id of the key a signed install was
+/// signed with
+pub fn key_id(token: &str) -> Result<String> {
+    header(token)?
+        .kid
+        .ok_or_else(|| Error::InvalidJwt("missing kid".to_string()))
+}
+
+/// Fetches the PEM encoded public key with the given id from Atlassian
+pub fn install_key(http: &reqwest::Client, kid: &str) -> Result<String> {
+    install_key_from(http, INSTALL_KEYS_URL, kid)
+}
+
+/// Fetches the PEM encoded public key with the given id from `keys_url`
+pub fn install_key_from(http: &reqwest::Client, keys_url: &str, kid: &str) -> Result<String> {
+    // The id ends up in the URL path, it must not be able to point elsewhere
+    if kid.is_empty()
+        || !kid
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::InvalidJwt(format!("bad kid {}", kid)));
+    }
+
+    let url = format!("{}/{}", keys_url.trim_end_matches('/'), kid);
+    let mut res = http.get(&url).send()?;
+
+    if res.status().is_success() {
+        Ok(res.text()?)
+    } else {
+        Err(Error::InvalidJwt(format!("no install key {}", kid)))
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Debug for ConnectApp {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ConnectApp")
+            .field("key", &self.key)
+            .field("expires_in", &self.expires_in)
+            .finish()
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+#[derive(Serialize, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default)]
+    typ: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kid: Option<String>,
+}
+
+fn header(token: &str) -> Result<JwtHeader> {
+    let (header, _, _) = split(token)?;
+
+    serde_json::from_slice(&base64_url_decode(header)?)
+        .map_err(|_| Error::InvalidJwt("malformed header".to_string()))
+}
+
+/// The checks every incoming token goes through once its signature is good
+fn check_request(claims: Claims, method: &str, url: &str, base_url: &str) -> Result<Claims> {
+    // allow for a little clock skew
+    if claims.exp + 30 < now() {
+        return Err(Error::InvalidJwt("expired".to_string()));
+    }
+    if claims.qsh.as_deref() != Some(query_string_hash(method, url, base_url)?.as_str()) {
+        return Err(Error::InvalidJwt(
+            "qsh does not match the request".to_string(),
+        ));
+    }
+
+    Ok(claims)
+}
+
+fn sign(claims: &Claims, secret: &str) -> Result<String> {
+    let header = JwtHeader {
+        alg: "HS256".to_string(),
+        typ: Some("JWT".to_string()),
+        kid: None,
+    };
+
+    let signed = format!(
+        "{}.{}",
+        base64_url(serde_json::to_string(&header)?.as_bytes()),
+        base64_url(serde_json::to_string(claims)?.as_bytes())
+    );
+    let signature = base64_url(&hmac(secret, &signed)?);
+
+    Ok(format!("{}.{}", signed, signature))
+}
+
+fn hmac(secret: &str, data: &str) -> Result<Vec<u8>> {
+    let key = PKey::hmac(secret.as_bytes())?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(data.as_bytes())?;
+
+    Ok(signer.sign_to_vec()?)
+}
+
+fn split(token: &str) -> Result<(&str, &str, &str)> {
+    let mut parts = token.split('.');
+
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(header), Some(claims), Some(signature), None) => Ok((header, claims, signature)),
+        _ => Err(Error::InvalidJwt("malformed token".to_string())),
+    }
+}
+
+fn base64_url_decode(text: &str) -> Result<Vec<u8>> {
+    let mut text = text.replace('-', "+").replace('_', "/");
+    while !text.len().is_multiple_of(4) {
+        text.push('=');
+    }
+
+    openssl::base64::decode_block(&text)
+        .map_err(|_| Error::InvalidJwt("malformed base64".to_string()))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::pkey::Private;
+    use openssl::rsa::Rsa;
+
+    const BASE: &str = "https://app.example.com/connect";
+
+    fn incoming(claims: &Claims, secret: &str) -> String {
+        sign(claims, secret).unwrap()
+    }
+
+    fn signed_install(claims: &Claims, key: &PKey<Private>) -> String {
+        let header = JwtHeader {
+            alg: "RS256".to_string(),
+            typ: Some("JWT".to_string()),
+            kid: Some("key-1".to_string()),
+        };
+        let signed = format!(
+            "{}.{}",
+            base64_url(serde_json::to_string(&header).unwrap().as_bytes()),
+            base64_url(serde_json::to_string(claims).unwrap().as_bytes())
+        );
+
+        let mut signer = Signer::new(MessageDigest::sha256(), key).unwrap();
+        signer.update(signed.as_bytes()).unwrap();
+
+        format!("{}.{}", signed, base64_url(&signer.sign_to_vec().unwrap()))
+    }
+
+    fn rsa_key() -> (PKey<Private>, String) {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let public = String::from_utf8(key.public_key_to_pem().unwrap()).unwrap();
+
+        (key, public)
+    }
+
+    fn claims(url: &str) -> Claims {
+        Claims {
+            iss: "client-key".to_string(),
+            iat: now(),
+            exp: now() + 60,
+            qsh: Some(query_string_hash("POST", url, BASE).unwrap()),
+            sub: None,
+            aud: None,
+            context: None,
+        }
+    }
+
+    #[test]
+    fn test_canonical_request() {
+        assert_eq!(
+            canonical_request(
+                "get",
+                "https://x.atlassian.net/rest/api/2/search?jql=a b&b=2&a=z&a=y&jwt=x",
+                "https://x.atlassian.net"
+            )
+            .unwrap(),
+            "GET&/rest/api/2/search&a=y,z&b=2&jql=a%20b"
+        );
+        assert_eq!(
+            canonical_request("POST", "/connect/installed/", BASE).unwrap(),
+            "POST&/installed&"
+        );
+        assert_eq!(
+            canonical_request("GET", "/connect", BASE).unwrap(),
+            "GET&/&"
+        );
+        assert_eq!(
+            canonical_request("GET", "/connect/a&b?x=1&y=%2A", BASE).unwrap(),
+            "GET&/a%26b&x=1&y=%2A"
+        );
+    }
+
+    #[test]
+    fn test_query_string_hash() {
+        let qsh =
+            query_string_hash("GET", "/rest/api/2/myself", "https://x.atlassian.net").unwrap();
+        let digest = hash(MessageDigest::sha256(), b"GET&/rest/api/2/myself&").unwrap();
+
+        assert_eq!(qsh.len(), 64);
+        assert_eq!(
+            qsh,
+            digest
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        );
+    }
+
+    #[test]
+    fn test_token_round_trip() {
+        let app = ConnectApp::new("my-app", "secret");
+        let token = app
+            .token(
+                "GET",
+                "https://x.atlassian.net/rest/api/2/myself",
+                "https://x.atlassian.net",
+            )
+            .unwrap();
+
+        let claims = verify(
+            &token,
+            "secret",
+            "my-app",
+            "GET",
+            "/rest/api/2/myself",
+            "https://x.atlassian.net",
+        )
+        .unwrap();
+
+        assert_eq!(claims.exp - claims.iat, 180);
+        assert_eq!(Claims::decode_unverified(&token).unwrap(), claims);
+    }
+
+    #[test]
+    fn test_verify_rejects() {
+        let url = "/connect/webhook?issue=TEST-1";
+        let token = incoming(&claims(url), "secret");
+
+        let reason = |r: Result<Claims>| match r {
+            Err(Error::InvalidJwt(reason)) => reason,
+            other => panic!("unexpected result {:?}", other),
+        };
+
+        assert!(verify(&token, "secret", "client-key", "POST", url, BASE).is_ok());
+        assert_eq!(
+            reason(verify(&token, "other", "client-key", "POST", url, BASE)),
+            "bad signature"
+        );
+        assert_eq!(
+            reason(verify(&token, "secret", "someone", "POST", url, BASE)),
+            "unexpected iss client-key"
+        );
+        assert_eq!(
+            reason(verify(
+                &token,
+                "secret",
+                "client-key",
+                "POST",
+                "/connect/webhook?issue=TEST-2",
+                BASE
+            )),
+            "qsh does not match the request"
+        );
+
+        let mut expired = claims(url);
+        expired.exp = now() - 300;
+        assert_eq!(
+            reason(verify(
+                &incoming(&expired, "secret"),
+                "secret",
+                "client-key",
+                "POST",
+                url,
+                BASE
+            )),
+            "expired"
+        );
+
+        assert_eq!(
+            reason(verify("a.b", "secret", "client-key", "POST", url, BASE)),
+            "malformed token"
+        );
+    }
+
+    #[test]
+    fn test_verify_install() {
+        let url = "/connect/installed";
+        let (key, public) = rsa_key();
+        let mut install = claims(url);
+        install.aud = Some(Value::String(BASE.to_string()));
+        let token = signed_install(&install, &key);
+
+        let reason = |r: Result<Claims>| match r {
+            Err(Error::InvalidJwt(reason)) => reason,
+            other => panic!("unexpected result {:?}", other),
+        };
+
+        assert_eq!(key_id(&token).unwrap(), "key-1");
+        assert_eq!(
+            verify_install(&token, &public, "POST", url, BASE).unwrap(),
+            install
+        );
+        assert_eq!(
+            reason(verify_install(&token, &rsa_key().1, "POST", url, BASE)),
+            "bad signature"
+        );
+        assert_eq!(
+            reason(verify_install(
+                &token,
+                &public,
+                "POST",
+                url,
+                "https://evil.example.com"
+            )),
+            "aud is not the app's base url"
+        );
+        assert_eq!(
+            reason(verify(&token, "secret", "client-key", "POST", url, BASE)),
+            "RS256 tokens are verified with verify_install"
+        );
+        assert_eq!(
+            reason(verify_install(
+                &incoming(&install, "secret"),
+                &public,
+                "POST",
+                url,
+                BASE
+            )),
+            "unsupported alg HS256"
+        );
+
+        install.aud = Some(serde_json::json!(["https://other.example.com", BASE]));
+        let token = signed_install(&install, &key);
+        assert!(verify_install(&token, &public, "POST", url, BASE).is_ok());
+    }
+}
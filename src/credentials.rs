@@ -1,27 +1,49 @@
-//! Handles JIRA credentials, currently only basic authentication is supported
+//! Handles JIRA credentials, either built directly or looked up through a
+//! `CredentialProvider`, see the `providers` module for the built in ones
 
 // ============================================================================
 // Use
 // ============================================================================
-use crate::Result;
+use crate::connect::ConnectApp;
+use crate::logging::REDACTED;
+use crate::oauth1::OAuth1;
+use crate::oauth2::TokenProvider;
+use crate::session::Session;
+use crate::{Error, Result};
+use std::fmt::Debug;
+use std::sync::Arc;
 
 // ============================================================================
 // Public Enums
 // ============================================================================
-/// An enumeration of the authentication types available for the Jira subcommand
-/// OAuth is currently not supported.
-#[derive(Clone, Debug)]
+/// An enumeration of the authentication types the client can send
+#[derive(Clone)]
 pub enum Credentials {
-    /// username and password credentials
+    /// username and password credentials, on Cloud the password is an API
+    /// token
     Basic(String, String),
+    /// A JIRA Server or Data Center personal access token, sent in the Bearer
+    /// Auth header
+    Pat(String),
+    /// Logs in once through `/rest/auth/1/session` and sends the session
+    /// cookie, logging in again when it expires
+    Session(Session),
     /// Supply the access token here, your app should be responsible for
     /// defining scope and doing the OAuth dance.
-    OAuth(String)
+    OAuth(String),
+    /// OAuth 1.0a as used by JIRA Server application links, every request is
+    /// signed with RSA-SHA1
+    OAuth1(OAuth1),
+    /// Atlassian Connect apps, every request carries a JWT signed with the
+    /// shared secret
+    Connect(ConnectApp),
+    /// OAuth 2.0 access tokens that are fetched from the provider before each
+    /// request and refreshed when they expire
+    OAuth2(Arc<dyn TokenProvider>),
 }
 
 impl Credentials {
-    /// Builds new credentials given a username and password, if the password
-    /// is not supplied it is requested through the tty automatically
+    /// Builds new credentials given a username and password or API token
     pub fn new_basic<U, P>(username: U, password: P) -> Result<Credentials>
     where
         U: Into<String>,
@@ -30,20 +52,154 @@ impl Credentials {
         Ok(Credentials::Basic(username.into(), password.into()))
     }
 
-    /// Builds new credentials given an OAuth access token, you must do the
-    /// OAuth dance as part of your application and pass the access token in.
-    /// 
-    /// The reason for this is that I do not know the scope of your application
-    /// and it would be improper for this library to add infinite scoping to
-    /// cover all its potential use-cases.
-    /// 
-    /// Whatever you supply here will be placed in the Bearer Auth header
+    /// Builds new credentials given a personal access token
+    pub fn new_pat<T>(token: T) -> Result<Credentials>
+    where
+        T: Into<String>,
+    {
+        Ok(Credentials::Pat(token.into()))
+    }
+
+    /// Builds credentials that log in with the username and password once
+    /// and then use a session cookie, see the `session` module
+    pub fn new_session<U, P>(username: U, password: P) -> Result<Credentials>
+    where
+        U: Into<String>,
+        P: Into<String>,
+    {
+        Ok(Credentials::Session(Session::new(username, password)))
+    }
+
+    /// Asks the provider for the credentials to use with `host`, fails with
+    /// `Error::MissingCredentials` when it has none.
+    ///
+    /// ```no_run
+    /// use jirac::providers::{Env, Netrc};
+    /// use jirac::{CredentialProvider, Credentials};
+    ///
+    /// let providers: Vec<Box<dyn CredentialProvider>> =
+    ///     vec![Box::new(Env::new()), Box::new(Netrc::new())];
+    /// let credentials = Credentials::from_provider(&providers, "https://jira.example.com");
+    /// ```
+    pub fn from_provider<P>(provider: &P, host: &str) -> Result<Credentials>
+    where
+        P: CredentialProvider + ?Sized,
+    {
+        provider
+            .credentials(host)?
+            .ok_or_else(|| Error::MissingCredentials(host.to_string()))
+    }
+
+    /// Builds new credentials given a static OAuth access token, whatever you
+    /// supply here will be placed in the Bearer Auth header.
+    ///
+    /// Access tokens expire, to have them refreshed automatically use
+    /// `new_oauth2` with a provider from the `oauth2` module instead.
     pub fn new_oauth<A>(access_token: A) -> Result<Credentials>
     where
         A: Into<String>
     {
         Ok(Credentials::OAuth(access_token.into()))
     }
+
+    /// Builds new credentials that ask the provider for an access token
+    /// before each request, see `oauth2::RefreshingToken`
+    pub fn new_oauth2<P>(provider: P) -> Result<Credentials>
+    where
+        P: TokenProvider + 'static,
+    {
+        Ok(Credentials::OAuth2(Arc::new(provider)))
+    }
+
+    /// Builds credentials for a Connect app given the app key from its
+    /// descriptor and the shared secret from the `installed` callback
+    pub fn new_connect<K, S>(key: K, shared_secret: S) -> Result<Credentials>
+    where
+        K: Into<String>,
+        S: Into<String>,
+    {
+        Ok(Credentials::Connect(ConnectApp::new(key, shared_secret)))
+    }
+
+    /// Builds OAuth 1.0a credentials given the consumer key and PEM encoded
+    /// private key of a JIRA application link along with an access token
+    /// and secret, see `oauth1::OAuth1Flow` for obtaining the access token.
+    pub fn new_oauth1<C, K, T, S>(
+        consumer_key: C,
+        private_key_pem: K,
+        token: T,
+        token_secret: S,
+    ) -> Result<Credentials>
+    where
+        C: Into<String>,
+        K: AsRef<[u8]>,
+        T: Into<String>,
+        S: Into<String>,
+    {
+        Ok(Credentials::OAuth1(OAuth1::new(
+            consumer_key,
+            private_key_pem,
+            token,
+            token_secret,
+        )?))
+    }
+}
+
+// ============================================================================
+// Public Traits
+// ============================================================================
+/// Looks up the credentials to use for a JIRA site
+pub trait CredentialProvider: Debug {
+    /// Returns the credentials for `host`, the base url the client is built
+    /// with, or `None` when the provider has nothing for it so the next
+    /// provider can be tried
+    fn credentials(&self, host: &str) -> Result<Option<Credentials>>;
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let redacted = format_args!("{}", REDACTED);
+
+        match self {
+            Credentials::Basic(user, _) => {
+                f.debug_tuple("Basic").field(user).field(&redacted).finish()
+            }
+            Credentials::Pat(_) => f.debug_tuple("Pat").field(&redacted).finish(),
+            Credentials::Session(session) => f.debug_tuple("Session").field(session).finish(),
+            Credentials::OAuth(_) => f.debug_tuple("OAuth").field(&redacted).finish(),
+            Credentials::OAuth1(oauth1) => f.debug_tuple("OAuth1").field(oauth1).finish(),
+            Credentials::Connect(app) => f.debug_tuple("Connect").field(app).finish(),
+            Credentials::OAuth2(provider) => f.debug_tuple("OAuth2").field(provider).finish(),
+        }
+    }
+}
+
+/// Tries each provider in order and returns the first credentials found
+impl<P: CredentialProvider> CredentialProvider for [P] {
+    fn credentials(&self, host: &str) -> Result<Option<Credentials>> {
+        for provider in self {
+            if let Some(credentials) = provider.credentials(host)? {
+                return Ok(Some(credentials));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl<P: CredentialProvider> CredentialProvider for Vec<P> {
+    fn credentials(&self, host: &str) -> Result<Option<Credentials>> {
+        self.as_slice().credentials(host)
+    }
+}
+
+impl<P: CredentialProvider + ?Sized> CredentialProvider for Box<P> {
+    fn credentials(&self, host: &str) -> Result<Option<Credentials>> {
+        (**self).credentials(host)
+    }
 }
 
 // ============================================================================
@@ -52,6 +208,7 @@ impl Credentials {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::oauth2::{MemoryStore, OAuth2Config, RefreshingToken, Token};
 
     #[test]
     fn test_instantiate_basic_credentials() {
@@ -70,4 +227,34 @@ mod tests {
                 assert_eq!(a, "some_token");
             }
     }
+
+    #[test]
+    fn test_debug_hides_secrets() {
+        let basic = Credentials::new_basic("test", "password").unwrap();
+        assert_eq!(format!("{:?}", basic), r#"Basic("test", [REDACTED])"#);
+
+        let pat = format!("{:?}", Credentials::new_pat("personal-token").unwrap());
+        assert_eq!(pat, "Pat([REDACTED])");
+
+        let oauth = format!("{:?}", Credentials::new_oauth("some_token").unwrap());
+        assert!(!oauth.contains("some_token"));
+    }
+
+    #[test]
+    fn test_debug_hides_oauth2_tokens() {
+        let config = OAuth2Config::new("client", "http://localhost/cb", vec!["read:jira-work"])
+            .with_secret("client-secret");
+        let token = Token {
+            access_token: "access-token".to_string(),
+            refresh_token: Some("refresh-token".to_string()),
+            expires_at: None,
+            scope: "read:jira-work".to_string(),
+        };
+        let provider = RefreshingToken::with_token(config, MemoryStore::default(), token).unwrap();
+
+        let debug = format!("{:?}", Credentials::new_oauth2(provider).unwrap());
+        assert!(!debug.contains("client-secret"));
+        assert!(!debug.contains("access-token"));
+        assert!(!debug.contains("refresh-token"));
+    }
 }
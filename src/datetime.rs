@@ -0,0 +1,352 @@
+//! Date and time values returned by JIRA.
+//!
+//! JIRA sends timestamps in the format "2020-03-09T20:40:15.922+0000" and
+//! dates in the format "2020-03-09", the agile API writes the offset with a
+//! colon instead ("2015-04-11T15:22:00.000+10:00"). By default these are
+//! kept as strings, with the `chrono` feature enabled they are parsed into
+//! `DateTime<FixedOffset>` and `NaiveDate` and written back out in the same
+//! format the API they came from uses.
+
+// ============================================================================
+// Use
+// ============================================================================
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serializer};
+
+// ============================================================================
+// Constants
+// ============================================================================
+/// The format JIRA uses for timestamps
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%z";
+
+/// The format the JIRA agile API uses for timestamps
+pub const AGILE_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
+
+/// The format JIRA uses for dates
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// ============================================================================
+// Types
+// ============================================================================
+/// A point in time with the offset JIRA reported it in
+#[cfg(feature = "chrono")]
+pub type Timestamp = chrono::DateTime<chrono::FixedOffset>;
+
+/// A point in time in the format "2020-03-09T20:40:15.922+0000"
+#[cfg(not(feature = "chrono"))]
+pub type Timestamp = String;
+
+/// A calendar date without a time
+#[cfg(feature = "chrono")]
+pub type Date = chrono::NaiveDate;
+
+/// A calendar date in the format "2020-03-09"
+#[cfg(not(feature = "chrono"))]
+pub type Date = String;
+
+/// A timestamp JIRA may leave out, `None` when it is missing or empty
+#[cfg(feature = "chrono")]
+pub type OptionalTimestamp = Option<Timestamp>;
+
+/// A timestamp JIRA may leave out, an empty string when it is missing
+#[cfg(not(feature = "chrono"))]
+pub type OptionalTimestamp = String;
+
+/// A date JIRA may leave out, `None` when it is missing or empty
+#[cfg(feature = "chrono")]
+pub type OptionalDate = Option<Date>;
+
+/// A date JIRA may leave out, an empty string when it is missing
+#[cfg(not(feature = "chrono"))]
+pub type OptionalDate = String;
+
+// ============================================================================
+// Public Functions
+// ============================================================================
+/// Parses a timestamp as sent by JIRA, the agile API uses RFC 3339 offsets
+/// ("+10:00" or "Z") so those are accepted too.
+#[cfg(feature = "chrono")]
+pub fn parse_timestamp(s: &str) -> Result<Timestamp, String> {
+    chrono::DateTime::parse_from_str(s, TIMESTAMP_FORMAT)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(s))
+        .map_err(|e| format!("invalid timestamp {:?}: {}", s, e))
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn parse_timestamp(s: &str) -> Result<Timestamp, String> {
+    Ok(s.to_string())
+}
+
+/// Formats a timestamp the way JIRA does
+#[cfg(feature = "chrono")]
+pub fn format_timestamp(t: &Timestamp) -> String {
+    t.format(TIMESTAMP_FORMAT).to_string()
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn format_timestamp(t: &Timestamp) -> String {
+    t.clone()
+}
+
+/// Formats a timestamp the way the JIRA agile API does
+#[cfg(feature = "chrono")]
+pub fn format_agile_timestamp(t: &Timestamp) -> String {
+    t.format(AGILE_TIMESTAMP_FORMAT).to_string()
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn format_agile_timestamp(t: &Timestamp) -> String {
+    t.clone()
+}
+
+/// Parses a date as sent by JIRA
+#[cfg(feature = "chrono")]
+pub fn parse_date(s: &str) -> Result<Date, String> {
+    chrono::NaiveDate::parse_from_str(s, DATE_FORMAT)
+        .map_err(|e| format!("invalid date {:?}: {}", s, e))
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn parse_date(s: &str) -> Result<Date, String> {
+    Ok(s.to_string())
+}
+
+/// Formats a date the way JIRA does
+#[cfg(feature = "chrono")]
+pub fn format_date(d: &Date) -> String {
+    d.format(DATE_FORMAT).to_string()
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn format_date(d: &Date) -> String {
+    d.clone()
+}
+
+/// Formats a timestamp JIRA may leave out, an empty string when it did
+#[cfg(feature = "chrono")]
+pub fn format_optional_timestamp(t: &OptionalTimestamp) -> String {
+    t.as_ref().map(format_timestamp).unwrap_or_default()
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn format_optional_timestamp(t: &OptionalTimestamp) -> String {
+    t.clone()
+}
+
+/// Formats a date JIRA may leave out, an empty string when it did
+#[cfg(feature = "chrono")]
+pub fn format_optional_date(d: &OptionalDate) -> String {
+    d.as_ref().map(format_date).unwrap_or_default()
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn format_optional_date(d: &OptionalDate) -> String {
+    d.clone()
+}
+
+// ============================================================================
+// Serde Modules
+// ============================================================================
+// These are used with `#[serde(with = "...")]` on every date and time field so
+// the same attributes work with and without the `chrono` feature.
+pub(crate) mod option_timestamp {
+    use super::*;
+
+    pub fn serialize<S>(t: &Option<Timestamp>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match t {
+            Some(t) => s.serialize_some(&format_timestamp(t)),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<Timestamp>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(d)? {
+            Some(t) => parse_timestamp(&t).map(Some).map_err(D::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+pub(crate) mod option_agile_timestamp {
+    use super::*;
+
+    pub fn serialize<S>(t: &Option<Timestamp>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match t {
+            Some(t) => s.serialize_some(&format_agile_timestamp(t)),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<Timestamp>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        option_timestamp::deserialize(d)
+    }
+}
+
+pub(crate) mod option_date {
+    use super::*;
+
+    pub fn serialize<S>(d: &Option<Date>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match d {
+            Some(d) => s.serialize_some(&format_date(d)),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<Date>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(d)? {
+            Some(d) => parse_date(&d).map(Some).map_err(D::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+// Fields that were plain strings before the `chrono` feature existed keep that
+// type without it. With it an empty string reads as `None` and `None` is
+// written back as an empty string, so the JSON is the same either way.
+pub(crate) mod optional_timestamp {
+    use super::*;
+
+    pub fn serialize<S>(t: &OptionalTimestamp, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_str(&format_optional_timestamp(t))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn deserialize<'de, D>(d: D) -> Result<OptionalTimestamp, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(d)? {
+            Some(t) if !t.is_empty() => parse_timestamp(&t).map(Some).map_err(D::Error::custom),
+            _ => Ok(None),
+        }
+    }
+
+    #[cfg(not(feature = "chrono"))]
+    pub fn deserialize<'de, D>(d: D) -> Result<OptionalTimestamp, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(d)
+    }
+}
+
+pub(crate) mod optional_date {
+    use super::*;
+
+    pub fn serialize<S>(d: &OptionalDate, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_str(&format_optional_date(d))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn deserialize<'de, D>(d: D) -> Result<OptionalDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<String>::deserialize(d)? {
+            Some(d) if !d.is_empty() => parse_date(&d).map(Some).map_err(D::Error::custom),
+            _ => Ok(None),
+        }
+    }
+
+    #[cfg(not(feature = "chrono"))]
+    pub fn deserialize<'de, D>(d: D) -> Result<OptionalDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(d)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Timelike};
+
+    #[test]
+    fn test_timestamp_round_trip() {
+        let t = parse_timestamp("2020-03-09T20:40:15.922+0000").unwrap();
+
+        assert_eq!(t.hour(), 20);
+        assert_eq!(t.nanosecond(), 922_000_000);
+        assert_eq!(t.offset().local_minus_utc(), 0);
+        assert_eq!(format_timestamp(&t), "2020-03-09T20:40:15.922+0000");
+    }
+
+    #[test]
+    fn test_timestamp_keeps_offset() {
+        let t = parse_timestamp("2015-04-11T15:22:00.000+10:00").unwrap();
+
+        assert_eq!(t.offset().local_minus_utc(), 10 * 60 * 60);
+        assert_eq!(format_timestamp(&t), "2015-04-11T15:22:00.000+1000");
+        assert_eq!(format_agile_timestamp(&t), "2015-04-11T15:22:00.000+10:00");
+    }
+
+    #[test]
+    fn test_date_round_trip() {
+        let d = parse_date("2010-07-06").unwrap();
+
+        assert_eq!(d.year(), 2010);
+        assert_eq!(format_date(&d), "2010-07-06");
+        assert!(parse_date("6/Jul/2010").is_err());
+    }
+
+    #[derive(Debug, Deserialize, serde::Serialize)]
+    struct Dated {
+        #[serde(default, with = "optional_timestamp")]
+        created: OptionalTimestamp,
+
+        #[serde(default, with = "optional_date")]
+        due: OptionalDate,
+    }
+
+    #[test]
+    fn test_optional_missing_is_none() {
+        let d: Dated = serde_json::from_str(r#"{"due": ""}"#).unwrap();
+
+        assert!(d.created.is_none());
+        assert!(d.due.is_none());
+        assert_eq!(
+            serde_json::to_value(&d).unwrap(),
+            serde_json::json!({"created": "", "due": ""})
+        );
+    }
+
+    #[test]
+    fn test_optional_round_trip() {
+        let json = serde_json::json!({
+            "created": "2020-03-08T14:49:58.599+0000",
+            "due": "2010-07-06"
+        });
+        let d: Dated = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(d.due.as_ref().unwrap().year(), 2010);
+        assert_eq!(serde_json::to_value(&d).unwrap(), json);
+    }
+}
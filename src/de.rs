@@ -0,0 +1,19 @@
+//! Helpers for deserializing values that JIRA is inconsistent about, for
+//! example fields that are sometimes missing and sometimes explicitly null.
+
+// ============================================================================
+// Use
+// ============================================================================
+use serde::{Deserialize, Deserializer};
+
+// ============================================================================
+// Public Functions
+// ============================================================================
+/// Deserializes an explicit null as the default value of the type
+pub fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
@@ -3,8 +3,11 @@
 // ============================================================================
 // Use
 // ============================================================================
+use crate::{Deployment, Feature};
+use openssl::error::ErrorStack;
+use reqwest::header::{HeaderMap, CONTENT_TYPE, SET_COOKIE};
 use reqwest::Error as HttpError;
-use reqwest::StatusCode;
+use reqwest::{Method, StatusCode};
 use serde::Deserialize;
 use serde_json::error::Error as SerdeError;
 use std::collections::BTreeMap;
@@ -14,13 +17,32 @@ use std::io::Error as IoError;
 // Public Structures
 // ============================================================================
 /// represents a general jira error response
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct Errors {
-    #[serde(rename = "errorMessages")]
+    #[serde(rename = "errorMessages", default)]
     pub error_messages: Vec<String>,
+    #[serde(default)]
     pub errors: BTreeMap<String, String>,
 }
 
+/// The request that failed and what JIRA, or something in front of it,
+/// answered
+#[derive(Debug)]
+pub struct HttpContext {
+    pub method: Method,
+    pub url: String,
+    pub status: StatusCode,
+
+    /// The response headers, without `Set-Cookie`
+    pub headers: HeaderMap,
+
+    /// The start of the response body, at most `BODY_SNIPPET_LEN` bytes
+    pub body: String,
+
+    /// The body parsed as JIRA's error payload, if it was one
+    pub errors: Option<Errors>,
+}
+
 // ============================================================================
 // Public Enums
 // ============================================================================
@@ -34,18 +56,143 @@ pub enum Error {
     IO(IoError),
     /// error associated with parsing or serializing
     Serde(SerdeError),
-    /// client request errors
-    Fault { code: StatusCode, errors: Errors },
+    /// client request errors, `errors` is empty when the body was not
+    /// JIRA's error payload
+    Fault {
+        code: StatusCode,
+        errors: Errors,
+        context: Box<HttpContext>,
+    },
     /// invalid credentials
-    Unauthorized,
+    Unauthorized(Box<HttpContext>),
     /// Precondition failed
-    PreconditionFailed,
+    PreconditionFailed(Box<HttpContext>),
     /// Forbidden action
-    Forbidden,
+    Forbidden(Box<HttpContext>),
     /// HTTP method is not allowed
-    MethodNotAllowed,
+    MethodNotAllowed(Box<HttpContext>),
     /// Page not found
-    NotFound,
+    NotFound(Box<HttpContext>),
+    /// JIRA, or a proxy in front of it, failed with a 5xx status
+    ServerError(Box<HttpContext>),
+    /// A successful response whose body was not JSON, such as a login or
+    /// error page served by a proxy
+    UnexpectedContentType(Box<HttpContext>),
+    /// error associated with signing requests
+    Crypto(ErrorStack),
+    /// A client replaying a cassette got a request that was not recorded
+    UnmatchedRequest { method: String, uri: String },
+    /// No credential provider had credentials for the host
+    MissingCredentials(String),
+    /// A Connect JWT failed verification, holds the reason
+    InvalidJwt(String),
+    /// JIRA rejected an OAuth request, holds the `oauth_problem` it sent
+    OAuthProblem(String),
+    /// The feature is not available on the deployment the client talks to,
+    /// returned before a request is sent
+    Unsupported {
+        feature: Feature,
+        deployment: Deployment,
+    },
+}
+
+// ============================================================================
+// impl HttpContext
+// ============================================================================
+/// How much of a response body an `HttpContext` keeps
+pub const BODY_SNIPPET_LEN: usize = 1024;
+
+impl HttpContext {
+    pub(crate) fn new(
+        method: Method,
+        url: &str,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &str,
+    ) -> HttpContext {
+        let mut headers = headers.clone();
+        headers.remove(SET_COOKIE);
+
+        let mut end = body.len().min(BODY_SNIPPET_LEN);
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        HttpContext {
+            method,
+            url: url.to_string(),
+            status,
+            headers,
+            body: body[..end].to_string(),
+            errors: serde_json::from_str::<Errors>(body).ok(),
+        }
+    }
+
+    /// Reads the rest of a response that is not going to be used otherwise
+    pub(crate) fn read(method: Method, res: &mut reqwest::Response) -> Result<HttpContext, Error> {
+        let body = res.text()?;
+
+        Ok(HttpContext::new(
+            method,
+            res.url().as_str(),
+            res.status(),
+            res.headers(),
+            &body,
+        ))
+    }
+
+    /// The `Content-Type` of the response, if it had one
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok())
+    }
+}
+
+// ============================================================================
+// impl Error
+// ============================================================================
+impl Error {
+    /// The request and response behind the error, for errors caused by
+    /// what JIRA answered
+    pub fn context(&self) -> Option<&HttpContext> {
+        use crate::Error::*;
+
+        match *self {
+            Fault { ref context, .. }
+            | Unauthorized(ref context)
+            | PreconditionFailed(ref context)
+            | Forbidden(ref context)
+            | MethodNotAllowed(ref context)
+            | NotFound(ref context)
+            | ServerError(ref context)
+            | UnexpectedContentType(ref context) => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The status JIRA answered with, for errors caused by a response
+    pub fn status(&self) -> Option<StatusCode> {
+        self.context().map(|c| c.status)
+    }
+
+    /// The error for a response with a client or server error status,
+    /// anything else that is not a success counts as a server error
+    pub(crate) fn from_status(context: HttpContext) -> Error {
+        let context = Box::new(context);
+
+        match context.status {
+            StatusCode::UNAUTHORIZED => Error::Unauthorized(context),
+            StatusCode::METHOD_NOT_ALLOWED => Error::MethodNotAllowed(context),
+            StatusCode::NOT_FOUND => Error::NotFound(context),
+            StatusCode::PRECONDITION_FAILED => Error::PreconditionFailed(context),
+            StatusCode::FORBIDDEN => Error::Forbidden(context),
+            code if code.is_client_error() => Error::Fault {
+                code,
+                errors: context.errors.clone().unwrap_or_default(),
+                context,
+            },
+            _ => Error::ServerError(context),
+        }
+    }
 }
 
 // ============================================================================
@@ -63,6 +210,12 @@ impl From<HttpError> for Error {
     }
 }
 
+impl From<ErrorStack> for Error {
+    fn from(error: ErrorStack) -> Error {
+        Error::Crypto(error)
+    }
+}
+
 impl From<IoError> for Error {
     fn from(error: IoError) -> Error {
         Error::IO(error)
@@ -77,11 +230,53 @@ impl ::std::fmt::Display for Error {
             Http(ref e) => writeln!(f, "Http Error: {}", e),
             IO(ref e) => writeln!(f, "IO Error: {}", e),
             Serde(ref e) => writeln!(f, "Serialization Error: {}", e),
+            Crypto(ref e) => writeln!(f, "Crypto Error: {}", e),
+            OAuthProblem(ref p) => writeln!(f, "OAuth Error: {}", p),
+            InvalidJwt(ref r) => writeln!(f, "Invalid JWT: {}", r),
+            UnmatchedRequest {
+                ref method,
+                ref uri,
+            } => writeln!(f, "No recorded response for {} {}", method, uri),
+            MissingCredentials(ref h) => writeln!(f, "No credentials found for {}", h),
             Fault {
                 ref code,
                 ref errors,
-            } => writeln!(f, "Jira Client Error ({}):\n{:#?}", code, errors),
-            _ => writeln!(f, "Could not connect to Jira: {}!", self),
+                ref context,
+            } => writeln!(
+                f,
+                "Jira Client Error ({}) for {} {}:\n{:#?}",
+                code, context.method, context.url, errors
+            ),
+            Unauthorized(ref c) => writeln!(f, "Unauthorized: {}", c),
+            PreconditionFailed(ref c) => writeln!(f, "Precondition Failed: {}", c),
+            Forbidden(ref c) => writeln!(f, "Forbidden: {}", c),
+            MethodNotAllowed(ref c) => writeln!(f, "Method Not Allowed: {}", c),
+            NotFound(ref c) => writeln!(f, "Not Found: {}", c),
+            ServerError(ref c) => writeln!(f, "Jira Server Error: {}\n{}", c, c.body),
+            UnexpectedContentType(ref c) => writeln!(
+                f,
+                "Unexpected {} response: {}\n{}",
+                c.content_type().unwrap_or("untyped"),
+                c,
+                c.body
+            ),
+            Unsupported {
+                ref feature,
+                ref deployment,
+            } => writeln!(f, "Jira {} does not support {}", deployment, feature),
+        }
+    }
+}
+
+impl ::std::fmt::Display for HttpContext {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "{} {} returned {}", self.method, self.url, self.status)?;
+
+        match self.errors {
+            Some(ref e) if !e.error_messages.is_empty() => {
+                write!(f, ": {}", e.error_messages.join(", "))
+            }
+            _ => Ok(()),
         }
     }
 }
@@ -94,8 +289,83 @@ impl ::std::error::Error for Error {
             Http(ref e) => Some(e),
             IO(ref e) => Some(e),
             Serde(ref e) => Some(e),
+            Crypto(ref e) => Some(e),
             Fault { .. } => None,
             _ => None,
         }
     }
 }
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_context_snippet() {
+        let mut headers = HeaderMap::new();
+        headers.insert(SET_COOKIE, HeaderValue::from_static("JSESSIONID=secret"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+
+        // a multi-byte character straddles the cut off
+        let body = format!("{}é and more", "a".repeat(BODY_SNIPPET_LEN - 1));
+        let context = HttpContext::new(
+            Method::GET,
+            "http://jira/rest/api/2/myself",
+            StatusCode::BAD_GATEWAY,
+            &headers,
+            &body,
+        );
+
+        assert_eq!(context.body, "a".repeat(BODY_SNIPPET_LEN - 1));
+        assert_eq!(context.content_type(), Some("text/plain"));
+        assert!(context.headers.get(SET_COOKIE).is_none());
+        assert!(context.errors.is_none());
+        assert_eq!(
+            context.to_string(),
+            "GET http://jira/rest/api/2/myself returned 502 Bad Gateway"
+        );
+    }
+
+    #[test]
+    fn test_context_errors() {
+        let context = HttpContext::new(
+            Method::POST,
+            "http://jira/rest/api/2/issue",
+            StatusCode::BAD_REQUEST,
+            &HeaderMap::new(),
+            r#"{"errorMessages": ["Bad"], "errors": {"summary": "required"}}"#,
+        );
+        let error = Error::Fault {
+            code: context.status,
+            errors: context.errors.clone().unwrap(),
+            context: Box::new(context),
+        };
+
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            error.context().unwrap().to_string(),
+            "POST http://jira/rest/api/2/issue returned 400 Bad Request: Bad"
+        );
+        assert!(Error::MissingCredentials("jira".into()).context().is_none());
+    }
+
+    #[test]
+    fn test_display_status_errors() {
+        let context = HttpContext::new(
+            Method::GET,
+            "http://jira/rest/api/2/myself",
+            StatusCode::UNAUTHORIZED,
+            &HeaderMap::new(),
+            "",
+        );
+
+        assert_eq!(
+            Error::Unauthorized(Box::new(context)).to_string(),
+            "Unauthorized: GET http://jira/rest/api/2/myself returned 401 Unauthorized\n"
+        );
+    }
+}
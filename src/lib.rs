@@ -4,6 +4,8 @@
 // ============================================================================
 // External Crates
 // ============================================================================
+extern crate openssl;
+extern crate pulldown_cmark;
 extern crate reqwest;
 extern crate serde;
 extern crate serde_json;
@@ -11,24 +13,50 @@ extern crate serde_json;
 // ============================================================================
 // Public Modules
 // ============================================================================
+pub mod adf;
+pub mod agile;
+pub mod cache;
+pub mod cassette;
 pub mod client;
+pub mod connect;
 pub mod credentials;
+pub mod middleware;
+pub mod oauth1;
+pub mod oauth2;
+pub mod providers;
+pub mod session;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod v2;
+pub mod v3;
+pub mod webhook;
+pub mod wiki;
 
 // ============================================================================
 // Private Modules
 // ============================================================================
+mod builder;
+mod capabilities;
+mod datetime;
+mod de;
 mod errors;
+mod logging;
 mod options;
+mod validation;
 
 // ============================================================================
 // Use
 // ============================================================================
+pub use crate::builder::*;
+pub use crate::capabilities::*;
 pub use crate::client::*;
 pub use crate::credentials::*;
+pub use crate::datetime::*;
 pub use crate::errors::*;
+pub use crate::logging::Logging;
 pub use crate::options::*;
 pub use crate::serde::{Deserialize, Serialize};
+pub use crate::validation::*;
 
 // ============================================================================
 // Public Structures
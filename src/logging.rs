@@ -0,0 +1,347 @@
+//! Logging of the requests the client sends.
+//!
+//! With the `tracing` feature every API call runs in a `jira.request` span
+//! recording its method, endpoint template, status, latency, retry count
+//! and response size. Each attempt logs its headers at debug level, with
+//! secrets redacted, and its bodies too when `Logging::log_bodies` is set.
+//! Without the feature nothing is logged.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::middleware::{Request, Response};
+use crate::Result;
+use reqwest::header::{HeaderMap, AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION, SET_COOKIE};
+use reqwest::Method;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// What the client logs about its requests and what it hides.
+///
+/// With the `tracing` feature each API call gets a `jira.request` span with
+/// `method`, `endpoint`, `status`, `latency_ms`, `retries` and
+/// `response_size` fields, and each attempt logs its headers and, if
+/// enabled, bodies at debug level. Without the feature nothing is logged.
+#[derive(Clone, Debug)]
+pub struct Logging {
+    /// Log request and response bodies at debug level
+    pub bodies: bool,
+    redacted_headers: Vec<String>,
+    redacted_fields: Vec<String>,
+}
+
+impl Logging {
+    /// Logs headers but not bodies, hiding credentials, cookies and any
+    /// JSON field that usually holds a secret
+    pub fn new() -> Logging {
+        Logging {
+            bodies: false,
+            redacted_headers: [AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE, SET_COOKIE]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            redacted_fields: [
+                "password",
+                "token",
+                "accessToken",
+                "access_token",
+                "refreshToken",
+                "refresh_token",
+                "clientSecret",
+                "client_secret",
+                "sharedSecret",
+            ]
+            .iter()
+            .map(|f| f.to_lowercase())
+            .collect(),
+        }
+    }
+
+    /// Also logs bodies, JSON bodies have their secret fields redacted
+    pub fn log_bodies(mut self, bodies: bool) -> Self {
+        self.bodies = bodies;
+
+        self
+    }
+
+    /// Hides the value of a header, names are case insensitive
+    pub fn redact_header<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.redacted_headers.push(name.into().to_lowercase());
+
+        self
+    }
+
+    /// Hides a field wherever it appears in a JSON body, names are case
+    /// insensitive
+    pub fn redact_field<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.redacted_fields.push(name.into().to_lowercase());
+
+        self
+    }
+
+    /// The headers as they are logged
+    pub fn headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = match self.redacted_headers.iter().any(|h| h == name.as_str()) {
+                    true => REDACTED.to_string(),
+                    false => String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                };
+
+                (name.to_string(), value)
+            })
+            .collect()
+    }
+
+    /// The body as it is logged, bodies which are not JSON are logged as
+    /// they are
+    pub fn body(&self, body: &str) -> String {
+        match serde_json::from_str::<Value>(body) {
+            Ok(mut value) => {
+                self.redact(&mut value);
+                value.to_string()
+            }
+            Err(_) => body.to_string(),
+        }
+    }
+
+    fn redact(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.redacted_fields.contains(&key.to_lowercase()) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact(value);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact(v)),
+            _ => {}
+        }
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl Default for Logging {
+    fn default() -> Logging {
+        Logging::new()
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// The span covering one API call, including any retry
+pub(crate) struct RequestSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+    start: Instant,
+    retries: u32,
+}
+
+impl RequestSpan {
+    /// Starts the span for a call to `url`, relative to `/rest/`
+    pub(crate) fn new(method: &Method, url: &str) -> RequestSpan {
+        #[cfg(not(feature = "tracing"))]
+        let _ = (method, url);
+
+        RequestSpan {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "jira.request",
+                method = %method,
+                endpoint = %endpoint_template(url),
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+                retries = 0,
+                response_size = tracing::field::Empty,
+            )
+            .entered(),
+            start: Instant::now(),
+            retries: 0,
+        }
+    }
+
+    pub(crate) fn retry(&mut self) {
+        self.retries += 1;
+
+        #[cfg(feature = "tracing")]
+        self.span.record("retries", self.retries);
+    }
+
+    pub(crate) fn request(&self, logging: &Logging, request: &Request) {
+        #[cfg(feature = "tracing")]
+        {
+            let body = request
+                .body
+                .as_ref()
+                .filter(|_| logging.bodies)
+                .map(|b| logging.body(&String::from_utf8_lossy(b)));
+
+            tracing::debug!(
+                url = %request.url,
+                headers = ?logging.headers(&request.headers),
+                body = body.as_deref(),
+                "sending request"
+            );
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        let _ = (logging, request);
+    }
+
+    pub(crate) fn response(&self, logging: &Logging, response: &Response) {
+        #[cfg(feature = "tracing")]
+        {
+            let body = Some(&response.body)
+                .filter(|_| logging.bodies)
+                .map(|b| logging.body(b));
+
+            tracing::debug!(
+                status = response.status.as_u16(),
+                headers = ?logging.headers(&response.headers),
+                body = body.as_deref(),
+                "received response"
+            );
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        let _ = (logging, response);
+    }
+
+    /// Records how the call ended and closes the span
+    pub(crate) fn finish(self, result: &Result<Response>) {
+        let latency = self.start.elapsed();
+
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("latency_ms", latency.as_millis() as u64);
+
+            match result {
+                Ok(response) => {
+                    self.span.record("status", response.status.as_u16());
+                    self.span
+                        .record("response_size", response.body.len() as u64);
+                }
+                Err(e) => tracing::warn!(error = %e, "request failed"),
+            }
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        let _ = (latency, result);
+    }
+}
+
+/// The endpoint without its query string, numeric ids replaced by `{id}`
+/// and issue keys by `{key}`, so calls to the same endpoint group together.
+/// The first two segments name the API and its version and are kept.
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+pub(crate) fn endpoint_template(url: &str) -> String {
+    let path = url.split('?').next().unwrap_or_default();
+
+    path.split('/')
+        .enumerate()
+        .map(|(i, segment)| {
+            if i < 2 {
+                segment
+            } else if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+                "{id}"
+            } else if is_issue_key(segment) {
+                "{key}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+fn is_issue_key(segment: &str) -> bool {
+    match segment.rsplit_once('-') {
+        Some((project, number)) => {
+            project.starts_with(|c: char| c.is_ascii_uppercase())
+                && project
+                    .bytes()
+                    .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
+                && !number.is_empty()
+                && number.bytes().all(|b| b.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use serde_json::json;
+
+    #[test]
+    fn test_endpoint_template() {
+        assert_eq!(
+            endpoint_template("api/2/issue/TEST-12/comment/10001?expand=x"),
+            "api/2/issue/{key}/comment/{id}"
+        );
+        assert_eq!(
+            endpoint_template("api/2/project/TEST"),
+            "api/2/project/TEST"
+        );
+        assert_eq!(
+            endpoint_template("agile/1.0/board/7"),
+            "agile/1.0/board/{id}"
+        );
+    }
+
+    #[test]
+    fn test_redaction() {
+        let logging = Logging::new()
+            .redact_field("customfield_10400")
+            .redact_header("X-Secret");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Basic c2VjcmV0"));
+        headers.insert("x-secret", HeaderValue::from_static("hidden"));
+        headers.insert("accept", HeaderValue::from_static("application/json"));
+
+        let logged = logging.headers(&headers);
+        assert_eq!(logged["authorization"], REDACTED);
+        assert_eq!(logged["x-secret"], REDACTED);
+        assert_eq!(logged["accept"], "application/json");
+
+        let body = json!({
+            "username": "jdoe",
+            "Password": "hunter2",
+            "fields": [{"customfield_10400": "pin", "summary": "kept"}]
+        });
+        let logged: Value = serde_json::from_str(&logging.body(&body.to_string())).unwrap();
+        assert_eq!(
+            logged,
+            json!({
+                "username": "jdoe",
+                "Password": REDACTED,
+                "fields": [{"customfield_10400": REDACTED, "summary": "kept"}]
+            })
+        );
+        assert_eq!(logging.body("not json"), "not json");
+    }
+}
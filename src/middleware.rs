@@ -0,0 +1,156 @@
+//! Hooks around every request the client sends.
+//!
+//! A middleware sees each request before it goes out and the response
+//! after it comes back. It can change either, time the call, or answer it
+//! itself without calling the rest of the chain.
+//!
+//! ```
+//! use jirac::middleware::{Middleware, Next, Request, Response};
+//! use jirac::{Client, Credentials, Result};
+//! use reqwest::header::HeaderValue;
+//!
+//! #[derive(Debug)]
+//! struct Audit;
+//!
+//! impl Middleware for Audit {
+//!     fn handle(&self, mut request: Request, next: Next) -> Result<Response> {
+//!         request
+//!             .headers
+//!             .insert("x-audit", HeaderValue::from_static("nightly-sync"));
+//!
+//!         next.run(request)
+//!     }
+//! }
+//!
+//! let client = Client::builder("https://jira.example.com", Credentials::Pat("token".into()))
+//!     .middleware(Audit)
+//!     .build()
+//!     .unwrap();
+//! # let _ = client;
+//! ```
+//!
+//! Middleware runs in the order it was added, the first one added is the
+//! outermost. Credentials are applied after the last middleware, so a
+//! middleware setting `Authorization` itself overrides them. When the
+//! client retries a request after refreshing its credentials the chain runs
+//! again.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::Client;
+use crate::Result;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// A request about to be sent, the URL includes the host and query string
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+/// A response read in full, from the network, a cassette or a middleware
+#[derive(Clone, Debug)]
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+/// The rest of the chain after the current middleware
+pub struct Next<'a> {
+    client: &'a Client,
+    rest: &'a [Arc<dyn Middleware>],
+}
+
+// ============================================================================
+// Public Traits
+// ============================================================================
+/// Inspects or changes requests and responses, see the module docs
+pub trait Middleware: Debug + Send + Sync {
+    /// Handles a request, usually by passing it on with `next.run`
+    fn handle(&self, request: Request, next: Next) -> Result<Response>;
+}
+
+// ============================================================================
+// impl Request
+// ============================================================================
+impl Request {
+    /// Creates a request with no headers or body
+    pub fn new<U>(method: Method, url: U) -> Request
+    where
+        U: Into<String>,
+    {
+        Request {
+            method,
+            url: url.into(),
+            headers: HeaderMap::new(),
+            body: None,
+        }
+    }
+}
+
+// ============================================================================
+// impl Response
+// ============================================================================
+impl Response {
+    /// Creates a response with no headers, for middleware answering
+    /// requests itself
+    pub fn new<B>(status: StatusCode, body: B) -> Response
+    where
+        B: Into<String>,
+    {
+        Response {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+// ============================================================================
+// impl Next
+// ============================================================================
+impl<'a> Next<'a> {
+    pub(crate) fn new(client: &'a Client) -> Next<'a> {
+        Next {
+            client,
+            rest: &client.middleware,
+        }
+    }
+
+    /// Passes the request to the next middleware, or sends it when there
+    /// are none left
+    pub fn run(self, request: Request) -> Result<Response> {
+        match self.rest.split_first() {
+            Some((first, rest)) => first.handle(
+                request,
+                Next {
+                    client: self.client,
+                    rest,
+                },
+            ),
+            None => self.client.dispatch(request),
+        }
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl<M> Middleware for Arc<M>
+where
+    M: Middleware + ?Sized,
+{
+    fn handle(&self, request: Request, next: Next) -> Result<Response> {
+        (**self).handle(request, next)
+    }
+}
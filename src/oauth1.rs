@@ -0,0 +1,460 @@
+//! OAuth 1.0a request signing with RSA-SHA1, as used by JIRA Server and Data
+//! Center application links.
+//!
+//! Once you have an access token use `Credentials::new_oauth1` and every
+//! request made by the client is signed. To get an access token in the first
+//! place use `OAuth1Flow`:
+//!
+//! 1. `request_token` fetches a temporary token
+//! 2. the user visits `authorize_url` and approves access, JIRA shows them a
+//!    verification code or passes it to your callback
+//! 3. `access_token` exchanges the temporary token and verification code for
+//!    credentials
+//!
+//! Both calls take the `reqwest::Client` to send them with, pass
+//! `&client.client` of a client made by `ClientBuilder` to go through its
+//! proxy and trust its certificates.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::Credentials;
+use crate::{Error, Result};
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey, Private};
+use openssl::sign::Signer;
+use reqwest::header::AUTHORIZATION;
+use reqwest::Url;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// The key and token used to sign requests
+#[derive(Clone)]
+pub struct OAuth1 {
+    /// Consumer key configured in the JIRA application link
+    pub consumer_key: String,
+
+    /// Access token
+    pub token: String,
+
+    /// Access token secret, unused by RSA-SHA1 signatures but kept as JIRA
+    /// hands it out alongside the token
+    pub token_secret: String,
+
+    key: PKey<Private>,
+}
+
+/// A temporary token returned by `OAuth1Flow::request_token`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestToken {
+    pub token: String,
+    pub secret: String,
+}
+
+/// Helpers for the request token, authorize and access token steps needed
+/// to obtain an access token from JIRA
+#[derive(Clone)]
+pub struct OAuth1Flow {
+    /// Base URL of the JIRA instance
+    pub host: String,
+
+    /// Consumer key configured in the JIRA application link
+    pub consumer_key: String,
+
+    /// Where JIRA should send the user after they approve access, "oob"
+    /// shows them a verification code instead
+    pub callback: String,
+
+    key: PKey<Private>,
+}
+
+impl OAuth1 {
+    /// Creates a signer given the consumer key, the PEM encoded private key
+    /// registered with the application link and an access token
+    pub fn new<C, K, T, S>(
+        consumer_key: C,
+        private_key_pem: K,
+        token: T,
+        token_secret: S,
+    ) -> Result<OAuth1>
+    where
+        C: Into<String>,
+        K: AsRef<[u8]>,
+        T: Into<String>,
+        S: Into<String>,
+    {
+        Ok(OAuth1 {
+            consumer_key: consumer_key.into(),
+            token: token.into(),
+            token_secret: token_secret.into(),
+            key: PKey::private_key_from_pem(private_key_pem.as_ref())?,
+        })
+    }
+
+    /// Builds the `Authorization` header value for a request
+    pub fn authorization(&self, method: &str, url: &str) -> Result<String> {
+        let params = [("oauth_token", self.token.as_str())];
+
+        authorization(
+            &self.key,
+            &self.consumer_key,
+            &params,
+            method,
+            url,
+            &nonce()?,
+            now(),
+        )
+    }
+
+    /// Builds the `Authorization` header value with a fixed nonce and
+    /// timestamp, this is mostly useful for testing
+    pub fn authorization_with(
+        &self,
+        method: &str,
+        url: &str,
+        nonce: &str,
+        timestamp: u64,
+    ) -> Result<String> {
+        let params = [("oauth_token", self.token.as_str())];
+
+        authorization(
+            &self.key,
+            &self.consumer_key,
+            &params,
+            method,
+            url,
+            nonce,
+            timestamp,
+        )
+    }
+}
+
+impl OAuth1Flow {
+    pub fn new<H, C, K>(host: H, consumer_key: C, private_key_pem: K) -> Result<OAuth1Flow>
+    where
+        H: Into<String>,
+        C: Into<String>,
+        K: AsRef<[u8]>,
+    {
+        Ok(OAuth1Flow {
+            host: host.into(),
+            consumer_key: consumer_key.into(),
+            callback: "oob".to_string(),
+            key: PKey::private_key_from_pem(private_key_pem.as_ref())?,
+        })
+    }
+
+    /// Sets the callback JIRA redirects the user to after approving access
+    pub fn with_callback<U>(mut self, callback: U) -> Self
+    where
+        U: Into<String>,
+    {
+        self.callback = callback.into();
+        self
+    }
+
+    /// Fetches a temporary request token
+    pub fn request_token(&self, http: &reqwest::Client) -> Result<RequestToken> {
+        let params = [("oauth_callback", self.callback.as_str())];
+        let res = self.post(http, "request-token", &params)?;
+
+        Ok(RequestToken {
+            token: res.get("oauth_token").cloned().unwrap_or_default(),
+            secret: res.get("oauth_token_secret").cloned().unwrap_or_default(),
+        })
+    }
+
+    /// The page the user has to visit to approve access
+    pub fn authorize_url(&self, token: &RequestToken) -> String {
+        format!(
+            "{}/plugins/servlet/oauth/authorize?oauth_token={}",
+            self.host,
+            encode(&token.token)
+        )
+    }
+
+    /// Exchanges an approved request token and the verification code for
+    /// credentials that sign every request
+    pub fn access_token<V>(
+        &self,
+        http: &reqwest::Client,
+        token: &RequestToken,
+        verifier: V,
+    ) -> Result<Credentials>
+    where
+        V: Into<String>,
+    {
+        let verifier = verifier.into();
+        let params = [
+            ("oauth_token", token.token.as_str()),
+            ("oauth_verifier", verifier.as_str()),
+        ];
+        let res = self.post(http, "access-token", &params)?;
+
+        Ok(Credentials::OAuth1(OAuth1 {
+            consumer_key: self.consumer_key.clone(),
+            token: res.get("oauth_token").cloned().unwrap_or_default(),
+            token_secret: res.get("oauth_token_secret").cloned().unwrap_or_default(),
+            key: self.key.clone(),
+        }))
+    }
+
+    fn post(
+        &self,
+        http: &reqwest::Client,
+        step: &str,
+        params: &[(&str, &str)],
+    ) -> Result<HashMap<String, String>> {
+        let url = format!("{}/plugins/servlet/oauth/{}", self.host, step);
+        let header = authorization(
+            &self.key,
+            &self.consumer_key,
+            params,
+            "POST",
+            &url,
+            &nonce()?,
+            now(),
+        )?;
+
+        let mut res = http.post(&url).header(AUTHORIZATION, header).send()?;
+        let body = res.text()?;
+        let values = parse_form(&body);
+
+        match values.get("oauth_problem") {
+            Some(problem) => Err(Error::OAuthProblem(problem.clone())),
+            None if !res.status().is_success() => Err(Error::OAuthProblem(body)),
+            None => Ok(values),
+        }
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Debug for OAuth1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("OAuth1")
+            .field("consumer_key", &self.consumer_key)
+            .field("token", &self.token)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for OAuth1Flow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("OAuth1Flow")
+            .field("host", &self.host)
+            .field("consumer_key", &self.consumer_key)
+            .field("callback", &self.callback)
+            .finish()
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+fn authorization(
+    key: &PKey<Private>,
+    consumer_key: &str,
+    extra: &[(&str, &str)],
+    method: &str,
+    url: &str,
+    nonce: &str,
+    timestamp: u64,
+) -> Result<String> {
+    let timestamp = timestamp.to_string();
+    let mut oauth = vec![
+        ("oauth_consumer_key", consumer_key),
+        ("oauth_nonce", nonce),
+        ("oauth_signature_method", "RSA-SHA1"),
+        ("oauth_timestamp", timestamp.as_str()),
+        ("oauth_version", "1.0"),
+    ];
+    oauth.extend_from_slice(extra);
+
+    let base = base_string(method, url, &oauth)?;
+    let mut signer = Signer::new(MessageDigest::sha1(), key)?;
+    signer.update(base.as_bytes())?;
+    let signature = openssl::base64::encode_block(&signer.sign_to_vec()?);
+
+    oauth.push(("oauth_signature", signature.as_str()));
+    oauth.sort();
+
+    let fields = oauth
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", encode(k), encode(v)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(format!("OAuth {}", fields))
+}
+
+/// The signature base string, see section 3.4.1 of RFC 5849
+fn base_string(method: &str, url: &str, oauth: &[(&str, &str)]) -> Result<String> {
+    let url = Url::parse(url).map_err(|e| Error::OAuthProblem(e.to_string()))?;
+
+    let mut base_url = format!(
+        "{}://{}",
+        url.scheme(),
+        url.host_str().unwrap_or_default().to_lowercase()
+    );
+    if let Some(port) = url.port() {
+        base_url.push_str(&format!(":{}", port));
+    }
+    base_url.push_str(url.path());
+
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (encode(&k), encode(&v)))
+        .chain(oauth.iter().map(|(k, v)| (encode(k), encode(v))))
+        .collect();
+    params.sort();
+
+    let params = params
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    Ok(format!(
+        "{}&{}&{}",
+        method.to_uppercase(),
+        encode(&base_url),
+        encode(&params)
+    ))
+}
+
+/// Percent encodes everything except the unreserved characters of RFC 3986
+pub(crate) fn encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(b as char)
+            }
+            b => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    out
+}
+
+fn parse_form(body: &str) -> HashMap<String, String> {
+    let query = format!("http://localhost/?{}", body.trim());
+
+    match Url::parse(&query) {
+        Ok(url) => url.query_pairs().into_owned().collect(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn nonce() -> Result<String> {
+    let mut buf = [0; 16];
+    openssl::rand::rand_bytes(&mut buf)?;
+
+    Ok(buf.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::rsa::Rsa;
+    use openssl::sign::Verifier;
+
+    fn header_params(header: &str) -> HashMap<String, String> {
+        header
+            .trim_start_matches("OAuth ")
+            .split(", ")
+            .map(|field| {
+                let (k, v) = field.split_at(field.find('=').unwrap());
+                let v = v[1..].trim_matches('"');
+                let url = Url::parse(&format!("http://localhost/?v={}", v)).unwrap();
+                (
+                    k.to_string(),
+                    url.query_pairs().next().unwrap().1.into_owned(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("a b&c=d~e"), "a%20b%26c%3Dd~e");
+        assert_eq!(encode("ü"), "%C3%BC");
+    }
+
+    #[test]
+    fn test_base_string() {
+        let base = base_string(
+            "get",
+            "HTTP://Jira.Example.com:8080/rest/api/2/search?jql=project%20%3D%20EX&b=2",
+            &[("oauth_nonce", "n")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            base,
+            "GET&http%3A%2F%2Fjira.example.com%3A8080%2Frest%2Fapi%2F2%2Fsearch&b%3D2%26jql%3Dproject%2520%253D%2520EX%26oauth_nonce%3Dn"
+        );
+    }
+
+    #[test]
+    fn test_signature_verifies() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let pem = rsa.private_key_to_pem().unwrap();
+        let public = PKey::public_key_from_pem(&rsa.public_key_to_pem().unwrap()).unwrap();
+
+        let oauth = OAuth1::new("jirac", pem, "token", "secret").unwrap();
+        let url = "https://jira.example.com/rest/api/2/issue/EX-1?expand=names";
+        let header = oauth
+            .authorization_with("GET", url, "abc123", 1_577_836_800)
+            .unwrap();
+        let params = header_params(&header);
+
+        assert_eq!(params["oauth_consumer_key"], "jirac");
+        assert_eq!(params["oauth_token"], "token");
+        assert_eq!(params["oauth_signature_method"], "RSA-SHA1");
+        assert_eq!(params["oauth_timestamp"], "1577836800");
+
+        // Rebuild the base string from the header the way JIRA does
+        let signed: Vec<(&str, &str)> = params
+            .iter()
+            .filter(|(k, _)| *k != "oauth_signature")
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let base = base_string("GET", url, &signed).unwrap();
+        let signature = openssl::base64::decode_block(&params["oauth_signature"]).unwrap();
+
+        let mut verifier = Verifier::new(MessageDigest::sha1(), &public).unwrap();
+        verifier.update(base.as_bytes()).unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+
+        // A different request must not verify with the same signature
+        let other = base_string("POST", url, &signed).unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha1(), &public).unwrap();
+        verifier.update(other.as_bytes()).unwrap();
+        assert!(!verifier.verify(&signature).unwrap());
+    }
+
+    #[test]
+    fn test_parse_form() {
+        let values = parse_form("oauth_token=abc&oauth_token_secret=d%2Be\n");
+
+        assert_eq!(values["oauth_token"], "abc");
+        assert_eq!(values["oauth_token_secret"], "d+e");
+    }
+}
@@ -0,0 +1,654 @@
+//! OAuth 2.0 authorization code grants (3LO) for JIRA Cloud, with PKCE and
+//! tokens that refresh themselves.
+//!
+//! The client asks its `TokenProvider` for an access token before every
+//! request and asks it to refresh when JIRA answers 401. `RefreshingToken`
+//! is the provider for Atlassian's authorization server, it refreshes tokens
+//! shortly before they expire and saves them to a `TokenStore` so a restarted
+//! process can carry on without sending the user through the flow again.
+//!
+//! 1. `OAuth2Config::authorize_url` is where the user approves access
+//! 2. `OAuth2Config::exchange_code` swaps the code JIRA sends to your
+//!    redirect uri for a token
+//! 3. `Credentials::new_oauth2(RefreshingToken::new(config, store))` keeps it
+//!    fresh
+//!
+//! Every call to the authorization server takes the `reqwest::Client` to send
+//! it with, pass `&client.client` of a client made by `ClientBuilder` so the
+//! proxy, certificates and timeouts configured there apply to these calls
+//! too. Refreshes made while sending a request use the client's own.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::logging::REDACTED;
+use crate::oauth1::encode;
+use crate::Client;
+use crate::{Deserialize, Serialize};
+use crate::{Error, Result};
+use openssl::hash::{hash, MessageDigest};
+use std::fmt::Debug;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Traits
+// ============================================================================
+/// Supplies access tokens to the client
+pub trait TokenProvider: Debug + Send + Sync {
+    /// Returns a valid access token, refreshing it first if it has expired
+    fn token(&self, client: &Client) -> Result<String>;
+
+    /// Refreshes the access token, called when JIRA rejects the current one
+    fn refresh(&self, client: &Client) -> Result<String>;
+}
+
+/// Persists tokens between runs
+pub trait TokenStore: Debug + Send + Sync {
+    fn load(&self) -> Result<Option<Token>>;
+
+    fn save(&self, token: &Token) -> Result<()>;
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Token {
+    /// Access token sent as a bearer token
+    pub access_token: String,
+
+    /// Refresh token, only handed out when the `offline_access` scope is
+    /// requested
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+
+    /// When the access token expires in seconds since the epoch
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+
+    /// Scopes granted
+    #[serde(default)]
+    pub scope: String,
+}
+
+/// Client settings registered in the Atlassian developer console
+#[derive(Clone)]
+pub struct OAuth2Config {
+    pub client_id: String,
+
+    /// Not needed when PKCE is used by a public client
+    pub client_secret: Option<String>,
+
+    pub redirect_uri: String,
+
+    /// Scopes to request such as "read:jira-work", include "offline_access"
+    /// to get a refresh token
+    pub scopes: Vec<String>,
+
+    /// Authorization page, defaults to Atlassian's
+    pub authorize_url: String,
+
+    /// Token endpoint, defaults to Atlassian's
+    pub token_url: String,
+}
+
+/// A PKCE code verifier and its S256 challenge, see RFC 7636
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+/// A site the token grants access to, the client's host should be set to
+/// `api_url()`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessibleResource {
+    /// The cloud id of the site
+    #[serde(default)]
+    pub id: String,
+
+    /// URL of the site, e.g. "https://your-domain.atlassian.net"
+    #[serde(default)]
+    pub url: String,
+
+    #[serde(default)]
+    pub name: String,
+
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+/// Keeps tokens in memory only
+#[derive(Default)]
+pub struct MemoryStore(Mutex<Option<Token>>);
+
+/// Keeps tokens in a JSON file
+#[derive(Debug, Clone)]
+pub struct FileStore(pub PathBuf);
+
+/// A `TokenProvider` that refreshes tokens with the authorization server
+pub struct RefreshingToken<S> {
+    config: OAuth2Config,
+    store: S,
+    token: Mutex<Option<Token>>,
+
+    /// Tokens are refreshed this many seconds before they expire
+    pub leeway: u64,
+}
+
+impl OAuth2Config {
+    pub fn new<C, R>(client_id: C, redirect_uri: R, scopes: Vec<&str>) -> OAuth2Config
+    where
+        C: Into<String>,
+        R: Into<String>,
+    {
+        OAuth2Config {
+            client_id: client_id.into(),
+            client_secret: None,
+            redirect_uri: redirect_uri.into(),
+            scopes: scopes.into_iter().map(String::from).collect(),
+            authorize_url: "https://auth.atlassian.com/authorize".to_string(),
+            token_url: "https://auth.atlassian.com/oauth/token".to_string(),
+        }
+    }
+
+    pub fn with_secret<S>(mut self, secret: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.client_secret = Some(secret.into());
+        self
+    }
+
+    /// The page the user has to visit to approve access, `state` is sent back
+    /// to your redirect uri and should be checked against the one you sent
+    pub fn authorize_url(&self, state: &str, pkce: Option<&Pkce>) -> String {
+        let mut params = vec![
+            ("audience", "api.atlassian.com".to_string()),
+            ("client_id", self.client_id.clone()),
+            ("scope", self.scopes.join(" ")),
+            ("redirect_uri", self.redirect_uri.clone()),
+            ("state", state.to_string()),
+            ("response_type", "code".to_string()),
+            ("prompt", "consent".to_string()),
+        ];
+
+        if let Some(pkce) = pkce {
+            params.push(("code_challenge", pkce.challenge.clone()));
+            params.push(("code_challenge_method", "S256".to_string()));
+        }
+
+        let query = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        format!("{}?{}", self.authorize_url, query)
+    }
+
+    /// Exchanges the code sent to the redirect uri for a token
+    pub fn exchange_code(
+        &self,
+        http: &reqwest::Client,
+        code: &str,
+        pkce: Option<&Pkce>,
+    ) -> Result<Token> {
+        self.token_request(
+            http,
+            TokenRequest {
+                grant_type: "authorization_code",
+                client_id: &self.client_id,
+                client_secret: self.client_secret.as_deref(),
+                code: Some(code),
+                redirect_uri: Some(&self.redirect_uri),
+                code_verifier: pkce.map(|p| p.verifier.as_str()),
+                refresh_token: None,
+            },
+        )
+    }
+
+    /// Exchanges a refresh token for a new token, Atlassian rotates refresh
+    /// tokens so the old one stops working
+    pub fn refresh(&self, http: &reqwest::Client, refresh_token: &str) -> Result<Token> {
+        self.token_request(
+            http,
+            TokenRequest {
+                grant_type: "refresh_token",
+                client_id: &self.client_id,
+                client_secret: self.client_secret.as_deref(),
+                code: None,
+                redirect_uri: None,
+                code_verifier: None,
+                refresh_token: Some(refresh_token),
+            },
+        )
+    }
+
+    fn token_request(&self, http: &reqwest::Client, request: TokenRequest) -> Result<Token> {
+        let mut res = http.post(&self.token_url).json(&request).send()?;
+        let body = res.text()?;
+
+        if !res.status().is_success() {
+            let problem = serde_json::from_str::<TokenError>(&body)
+                .map(|e| e.description.unwrap_or(e.error))
+                .unwrap_or(body);
+            return Err(Error::OAuthProblem(problem));
+        }
+
+        let res: TokenResponse = serde_json::from_str(&body)?;
+
+        Ok(Token {
+            access_token: res.access_token,
+            refresh_token: res
+                .refresh_token
+                .or(request.refresh_token.map(String::from)),
+            expires_at: res.expires_in.map(|e| now() + e),
+            scope: res.scope,
+        })
+    }
+}
+
+impl Pkce {
+    /// Generates a random verifier
+    pub fn new() -> Result<Pkce> {
+        let mut buf = [0; 48];
+        openssl::rand::rand_bytes(&mut buf)?;
+
+        Pkce::from_verifier(base64_url(&buf))
+    }
+
+    pub fn from_verifier<V>(verifier: V) -> Result<Pkce>
+    where
+        V: Into<String>,
+    {
+        let verifier = verifier.into();
+        let digest = hash(MessageDigest::sha256(), verifier.as_bytes())?;
+
+        Ok(Pkce {
+            challenge: base64_url(&digest),
+            verifier,
+        })
+    }
+}
+
+impl Token {
+    /// Returns true if the token expires within `leeway` seconds
+    pub fn expires_within(&self, leeway: u64) -> bool {
+        self.expires_at.is_some_and(|e| e <= now() + leeway)
+    }
+}
+
+impl AccessibleResource {
+    /// Fetches the sites an access token grants access to
+    pub fn all(http: &reqwest::Client, access_token: &str) -> Result<Vec<AccessibleResource>> {
+        AccessibleResource::all_from(
+            http,
+            "https://api.atlassian.com/oauth/token/accessible-resources",
+            access_token,
+        )
+    }
+
+    /// Same as `all` with a different endpoint, mostly useful for testing
+    pub fn all_from(
+        http: &reqwest::Client,
+        url: &str,
+        access_token: &str,
+    ) -> Result<Vec<AccessibleResource>> {
+        let mut res = http.get(url).bearer_auth(access_token).send()?;
+
+        if res.status().is_success() {
+            Ok(res.json()?)
+        } else {
+            Err(Error::OAuthProblem(res.text()?))
+        }
+    }
+
+    /// The host to give `Client::new` for this site
+    pub fn api_url(&self) -> String {
+        format!("https://api.atlassian.com/ex/jira/{}", self.id)
+    }
+}
+
+impl<S> RefreshingToken<S>
+where
+    S: TokenStore,
+{
+    pub fn new(config: OAuth2Config, store: S) -> RefreshingToken<S> {
+        RefreshingToken {
+            config,
+            store,
+            token: Mutex::new(None),
+            leeway: 60,
+        }
+    }
+
+    /// Starts with a token, e.g. one returned by `exchange_code`, which is
+    /// saved to the store
+    pub fn with_token(config: OAuth2Config, store: S, token: Token) -> Result<RefreshingToken<S>> {
+        store.save(&token)?;
+
+        Ok(RefreshingToken {
+            config,
+            store,
+            token: Mutex::new(Some(token)),
+            leeway: 60,
+        })
+    }
+
+    fn current(&self, http: &reqwest::Client, force_refresh: bool) -> Result<String> {
+        let mut guard = self.token.lock().unwrap_or_else(|e| e.into_inner());
+
+        if guard.is_none() {
+            *guard = self.store.load()?;
+        }
+
+        let token = match guard.as_ref() {
+            Some(token) => token,
+            None => {
+                return Err(Error::OAuthProblem(
+                    "no token stored, complete the authorization flow first".to_string(),
+                ))
+            }
+        };
+
+        if !force_refresh && !token.expires_within(self.leeway) {
+            return Ok(token.access_token.clone());
+        }
+
+        let refresh_token = match &token.refresh_token {
+            Some(refresh_token) => refresh_token.clone(),
+            None if force_refresh => {
+                return Err(Error::OAuthProblem(
+                    "the token was rejected and can't be refreshed".to_string(),
+                ))
+            }
+            // Without a refresh token all we can do is try the old one
+            None => return Ok(token.access_token.clone()),
+        };
+
+        let refreshed = self.config.refresh(http, &refresh_token)?;
+        self.store.save(&refreshed)?;
+
+        let access_token = refreshed.access_token.clone();
+        *guard = Some(refreshed);
+
+        Ok(access_token)
+    }
+}
+
+impl TokenStore for MemoryStore {
+    fn load(&self) -> Result<Option<Token>> {
+        Ok(self.0.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    fn save(&self, token: &Token) -> Result<()> {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(token.clone());
+        Ok(())
+    }
+}
+
+impl TokenStore for FileStore {
+    fn load(&self) -> Result<Option<Token>> {
+        if !self.0.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_str(&fs::read_to_string(&self.0)?)?))
+    }
+
+    /// Refresh tokens are as good as a password, on Unix the file is only
+    /// readable by its owner
+    fn save(&self, token: &Token) -> Result<()> {
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        let mut file = options.open(&self.0)?;
+
+        // The mode only applies to new files, tighten one made before
+        #[cfg(unix)]
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+
+        file.write_all(serde_json::to_string_pretty(token)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl<T> TokenStore for Arc<T>
+where
+    T: TokenStore,
+{
+    fn load(&self) -> Result<Option<Token>> {
+        (**self).load()
+    }
+
+    fn save(&self, token: &Token) -> Result<()> {
+        (**self).save(token)
+    }
+}
+
+impl<S> TokenProvider for RefreshingToken<S>
+where
+    S: TokenStore,
+{
+    fn token(&self, client: &Client) -> Result<String> {
+        self.current(&client.client, false)
+    }
+
+    fn refresh(&self, client: &Client) -> Result<String> {
+        self.current(&client.client, true)
+    }
+}
+
+impl std::fmt::Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let redacted = format_args!("{}", REDACTED);
+
+        f.debug_struct("Token")
+            .field("access_token", &redacted)
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| &redacted),
+            )
+            .field("expires_at", &self.expires_at)
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for OAuth2Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let redacted = format_args!("{}", REDACTED);
+
+        f.debug_struct("OAuth2Config")
+            .field("client_id", &self.client_id)
+            .field(
+                "client_secret",
+                &self.client_secret.as_ref().map(|_| &redacted),
+            )
+            .field("redirect_uri", &self.redirect_uri)
+            .field("scopes", &self.scopes)
+            .field("authorize_url", &self.authorize_url)
+            .field("token_url", &self.token_url)
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for MemoryStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("MemoryStore")
+            .field(&format_args!("{}", REDACTED))
+            .finish()
+    }
+}
+
+impl<S> std::fmt::Debug for RefreshingToken<S>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("RefreshingToken")
+            .field("config", &self.config)
+            .field("store", &self.store)
+            .field("leeway", &self.leeway)
+            .finish()
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+#[derive(Serialize)]
+struct TokenRequest<'a> {
+    grant_type: &'a str,
+    client_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_secret: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redirect_uri: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code_verifier: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    scope: String,
+}
+
+#[derive(Deserialize)]
+struct TokenError {
+    error: String,
+    #[serde(rename = "error_description", default)]
+    description: Option<String>,
+}
+
+/// Base64 with the URL safe alphabet and no padding
+pub(crate) fn base64_url(bytes: &[u8]) -> String {
+    openssl::base64::encode_block(bytes)
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_")
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkce_challenge() {
+        // Example from appendix B of RFC 7636
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk").unwrap();
+        assert_eq!(
+            pkce.challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let random = Pkce::new().unwrap();
+        assert_eq!(random.verifier.len(), 64);
+        assert_ne!(random, Pkce::new().unwrap());
+    }
+
+    #[test]
+    fn test_authorize_url() {
+        let config = OAuth2Config::new(
+            "client",
+            "https://example.com/callback",
+            vec!["read:jira-work", "offline_access"],
+        );
+        let pkce = Pkce::from_verifier("verifier").unwrap();
+
+        assert_eq!(
+            config.authorize_url("xyz", Some(&pkce)),
+            format!(
+                "https://auth.atlassian.com/authorize?audience=api.atlassian.com&client_id=client\
+                 &scope=read%3Ajira-work%20offline_access&redirect_uri=https%3A%2F%2Fexample.com%2Fcallback\
+                 &state=xyz&response_type=code&prompt=consent&code_challenge={}&code_challenge_method=S256",
+                pkce.challenge
+            )
+        );
+    }
+
+    #[test]
+    fn test_token_expiry() {
+        let token = Token {
+            access_token: "a".to_string(),
+            refresh_token: None,
+            expires_at: Some(now() + 30),
+            scope: String::new(),
+        };
+
+        assert!(token.expires_within(60));
+        assert!(!token.expires_within(0));
+    }
+
+    #[test]
+    fn test_file_store() {
+        let path = std::env::temp_dir().join(format!("jirac-token-{}.json", std::process::id()));
+        let store = FileStore(path.clone());
+
+        assert!(store.load().unwrap().is_none());
+
+        let token = Token {
+            access_token: "a".to_string(),
+            refresh_token: Some("r".to_string()),
+            expires_at: Some(1),
+            scope: "read:jira-work".to_string(),
+        };
+        store.save(&token).unwrap();
+
+        let loaded = store.load().unwrap().unwrap();
+        assert_eq!(loaded.refresh_token.unwrap(), "r");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_store_permissions() {
+        let path = std::env::temp_dir().join(format!("jirac-mode-{}.json", std::process::id()));
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let token = Token {
+            access_token: "a".to_string(),
+            refresh_token: Some("r".to_string()),
+            expires_at: None,
+            scope: String::new(),
+        };
+        FileStore(path.clone()).save(&token).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,665 @@
+//! Built in credential providers, they can be combined in a `Vec` where the
+//! first one that has credentials for the host wins.
+//!
+//! ```no_run
+//! use jirac::providers::{Command, Env, Netrc, Profiles};
+//! use jirac::{Client, CredentialProvider, Credentials};
+//!
+//! let host = "https://jira.example.com";
+//! let providers: Vec<Box<dyn CredentialProvider>> = vec![
+//!     Box::new(Env::new()),
+//!     Box::new(Netrc::new()),
+//!     Box::new(Profiles::load_default().unwrap()),
+//!     Box::new(Command::new("git").arg("credential").arg("fill")),
+//! ];
+//!
+//! let client = Client::new(host, Credentials::from_provider(&providers, host).unwrap());
+//! ```
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::logging::REDACTED;
+use crate::Deserialize;
+use crate::{CredentialProvider, Credentials, Result};
+use reqwest::Url;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{Error as IoError, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Stdio};
+use toml::value::{Table, Value};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// Reads credentials from environment variables. `JIRA_PAT` is used as a
+/// personal access token, otherwise `JIRA_USER` and `JIRA_API_TOKEN` are used
+/// for basic auth. The credentials are used whatever the host is.
+#[derive(Debug, Clone)]
+pub struct Env {
+    prefix: String,
+}
+
+/// Reads credentials from a netrc file, a `machine` entry matching the host
+/// name is used before the `default` entry. An entry with only a password is
+/// used as a personal access token.
+#[derive(Debug, Clone)]
+pub struct Netrc {
+    path: Option<PathBuf>,
+}
+
+/// A named JIRA site in a profile file
+#[derive(Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Profile {
+    #[serde(skip)]
+    pub name: String,
+    pub host: Option<String>,
+    pub user: Option<String>,
+    /// API token or password used with `user`
+    pub token: Option<String>,
+    /// Personal access token, used over `user` and `token`
+    pub pat: Option<String>,
+}
+
+/// A TOML file with one table per site, the profile whose host matches is
+/// used. Nested tables are named after their path and keys jirac doesn't
+/// know about are left for other tools.
+///
+/// ```toml
+/// default = "work"
+///
+/// [work]
+/// host = "https://work.atlassian.net"
+/// user = "jdoe@example.com"
+/// token = "api-token"
+///
+/// [onprem.prod]
+/// host = "https://jira.example.com"
+/// pat = "personal-access-token"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profiles {
+    /// Name of the profile to use when none is asked for
+    pub default: Option<String>,
+    pub profiles: Vec<Profile>,
+}
+
+/// Runs an external command speaking git's credential helper protocol. The
+/// command is given `protocol` and `host` on stdin and answers with
+/// `username` and `password` lines, a password without a username is used as
+/// a personal access token. No output means it has no credentials.
+#[derive(Debug, Clone)]
+pub struct Command {
+    program: String,
+    args: Vec<String>,
+}
+
+// ============================================================================
+// impl Env
+// ============================================================================
+impl Env {
+    /// Reads the `JIRA_` variables
+    pub fn new() -> Env {
+        Env::with_prefix("JIRA")
+    }
+
+    /// Reads `<prefix>_PAT`, `<prefix>_USER` and `<prefix>_API_TOKEN`
+    pub fn with_prefix<P>(prefix: P) -> Env
+    where
+        P: Into<String>,
+    {
+        Env {
+            prefix: prefix.into(),
+        }
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        env::var(format!("{}_{}", self.prefix, name))
+            .ok()
+            .filter(|v| !v.is_empty())
+    }
+}
+
+// ============================================================================
+// impl Netrc
+// ============================================================================
+impl Netrc {
+    /// Reads `~/.netrc`, or `~/_netrc` on Windows
+    pub fn new() -> Netrc {
+        let name = if cfg!(windows) { "_netrc" } else { ".netrc" };
+
+        Netrc {
+            path: home_dir().map(|h| h.join(name)),
+        }
+    }
+
+    pub fn from_path<P>(path: P) -> Netrc
+    where
+        P: Into<PathBuf>,
+    {
+        Netrc {
+            path: Some(path.into()),
+        }
+    }
+
+    /// Finds the credentials for a host name in netrc formatted text
+    pub fn parse(text: &str, host: &str) -> Option<Credentials> {
+        let mut tokens = text.split_whitespace();
+        let mut entries: Vec<(Option<&str>, Option<&str>, Option<&str>)> = vec![];
+
+        while let Some(token) = tokens.next() {
+            match token {
+                "machine" => entries.push((tokens.next(), None, None)),
+                "default" => entries.push((None, None, None)),
+                "login" => {
+                    let login = tokens.next();
+                    if let Some(entry) = entries.last_mut() {
+                        entry.1 = login;
+                    }
+                }
+                "password" => {
+                    let password = tokens.next();
+                    if let Some(entry) = entries.last_mut() {
+                        entry.2 = password;
+                    }
+                }
+                "account" => {
+                    tokens.next();
+                }
+                // macro definitions run until an empty line, which
+                // split_whitespace can't see, so stop at the definition
+                "macdef" => break,
+                _ => {}
+            }
+        }
+
+        let entry = entries
+            .iter()
+            .find(|e| e.0 == Some(host))
+            .or_else(|| entries.iter().find(|e| e.0.is_none()))?;
+
+        match *entry {
+            (_, Some(login), Some(password)) => {
+                Some(Credentials::Basic(login.to_string(), password.to_string()))
+            }
+            (_, None, Some(password)) => Some(Credentials::Pat(password.to_string())),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// impl Profile
+// ============================================================================
+impl Profile {
+    /// The profile's credentials, `None` if it has neither a `pat` nor a
+    /// `user` and `token`
+    pub fn credentials(&self) -> Option<Credentials> {
+        match (&self.pat, &self.user, &self.token) {
+            (Some(pat), _, _) => Some(Credentials::Pat(pat.clone())),
+            (None, Some(user), Some(token)) => {
+                Some(Credentials::Basic(user.clone(), token.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// impl Profiles
+// ============================================================================
+impl Profiles {
+    /// The file named by `JIRAC_CONFIG`, or `~/.config/jirac/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        match env::var_os("JIRAC_CONFIG") {
+            Some(path) => Some(PathBuf::from(path)),
+            None => home_dir().map(|h| h.join(".config").join("jirac").join("config.toml")),
+        }
+    }
+
+    /// Loads the file at `default_path`, no file means no profiles
+    pub fn load_default() -> Result<Profiles> {
+        match Profiles::default_path() {
+            Some(ref path) if path.exists() => Profiles::load(path),
+            _ => Ok(Profiles::default()),
+        }
+    }
+
+    pub fn load<P>(path: P) -> Result<Profiles>
+    where
+        P: AsRef<Path>,
+    {
+        Profiles::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses a profile file, every table with values of its own is a
+    /// profile, e.g. `[onprem.prod]` is named "onprem.prod"
+    pub fn parse(text: &str) -> Result<Profiles> {
+        let root: Table =
+            toml::from_str(text).map_err(|e| IoError::new(ErrorKind::InvalidData, e))?;
+
+        let default = match root.get("default") {
+            None | Some(Value::Table(_)) => None,
+            Some(Value::String(name)) => Some(name.clone()),
+            Some(_) => {
+                let message = "default: expected the name of a profile";
+                return Err(IoError::new(ErrorKind::InvalidData, message).into());
+            }
+        };
+
+        let mut profiles = Profiles {
+            default,
+            profiles: vec![],
+        };
+        collect_profiles("", &root, &mut profiles.profiles)?;
+
+        Ok(profiles)
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// The profile named by `default`, or the only one if there is just one
+    pub fn default_profile(&self) -> Option<&Profile> {
+        match self.default {
+            Some(ref name) => self.profile(name),
+            None if self.profiles.len() == 1 => self.profiles.first(),
+            None => None,
+        }
+    }
+}
+
+// ============================================================================
+// impl Command
+// ============================================================================
+impl Command {
+    pub fn new<P>(program: P) -> Command
+    where
+        P: Into<String>,
+    {
+        Command {
+            program: program.into(),
+            args: vec![],
+        }
+    }
+
+    pub fn arg<A>(mut self, arg: A) -> Command
+    where
+        A: Into<String>,
+    {
+        self.args.push(arg.into());
+        self
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl Default for Env {
+    fn default() -> Env {
+        Env::new()
+    }
+}
+
+impl Default for Netrc {
+    fn default() -> Netrc {
+        Netrc::new()
+    }
+}
+
+// Profiles are printed through this too, a provider chain is logged with
+// `{:?}` so neither secret may show up
+impl std::fmt::Debug for Profile {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let redacted = format_args!("{}", REDACTED);
+
+        f.debug_struct("Profile")
+            .field("name", &self.name)
+            .field("host", &self.host)
+            .field("user", &self.user)
+            .field("token", &self.token.as_ref().map(|_| &redacted))
+            .field("pat", &self.pat.as_ref().map(|_| &redacted))
+            .finish()
+    }
+}
+
+impl CredentialProvider for Env {
+    fn credentials(&self, _host: &str) -> Result<Option<Credentials>> {
+        if let Some(pat) = self.var("PAT") {
+            return Ok(Some(Credentials::Pat(pat)));
+        }
+
+        Ok(match (self.var("USER"), self.var("API_TOKEN")) {
+            (Some(user), Some(token)) => Some(Credentials::Basic(user, token)),
+            _ => None,
+        })
+    }
+}
+
+impl CredentialProvider for Netrc {
+    fn credentials(&self, host: &str) -> Result<Option<Credentials>> {
+        let path = match self.path {
+            Some(ref path) if path.exists() => path,
+            _ => return Ok(None),
+        };
+
+        Ok(Netrc::parse(&fs::read_to_string(path)?, &host_name(host)))
+    }
+}
+
+impl CredentialProvider for Profiles {
+    fn credentials(&self, host: &str) -> Result<Option<Credentials>> {
+        let name = host_name(host);
+
+        Ok(self
+            .profiles
+            .iter()
+            .find(|p| p.host.as_ref().map(|h| host_name(h)) == Some(name.clone()))
+            .and_then(Profile::credentials))
+    }
+}
+
+impl CredentialProvider for Command {
+    fn credentials(&self, host: &str) -> Result<Option<Credentials>> {
+        let (protocol, name) = match Url::parse(host) {
+            Ok(url) => (url.scheme().to_string(), host_name(host)),
+            Err(_) => ("https".to_string(), host.to_string()),
+        };
+
+        let mut child = process::Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // a helper may exit without reading its input
+        if let Some(mut stdin) = child.stdin.take() {
+            match write!(stdin, "protocol={}\nhost={}\n\n", protocol, name) {
+                Err(ref e) if e.kind() == ErrorKind::BrokenPipe => {}
+                result => result?,
+            }
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(IoError::other(format!(
+                "{} failed: {}",
+                self.program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+            .into());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let values: BTreeMap<&str, &str> = stdout
+            .lines()
+            .filter_map(|line| {
+                let eq = line.find('=')?;
+                Some((&line[..eq], &line[eq + 1..]))
+            })
+            .collect();
+
+        Ok(match (values.get("username"), values.get("password")) {
+            (Some(user), Some(password)) => {
+                Some(Credentials::Basic(user.to_string(), password.to_string()))
+            }
+            (None, Some(password)) => Some(Credentials::Pat(password.to_string())),
+            _ => None,
+        })
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// The host name of a url, or the text itself when it isn't one
+fn host_name(host: &str) -> String {
+    Url::parse(host)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| host.to_string())
+}
+
+/// Adds the profiles in a table and the tables nested in it
+fn collect_profiles(prefix: &str, table: &Table, profiles: &mut Vec<Profile>) -> Result<()> {
+    for (key, value) in table {
+        let table = match value {
+            Value::Table(table) => table,
+            _ => continue,
+        };
+        let name = match prefix {
+            "" => key.clone(),
+            prefix => format!("{}.{}", prefix, key),
+        };
+
+        // `[onprem.prod]` alone makes an `onprem` table, it isn't a profile
+        if table.values().any(|v| !v.is_table()) {
+            let profile: Profile = Value::Table(table.clone())
+                .try_into()
+                .map_err(|e| IoError::new(ErrorKind::InvalidData, format!("[{}]: {}", name, e)))?;
+
+            profiles.push(Profile {
+                name: name.clone(),
+                ..profile
+            });
+        }
+
+        collect_profiles(&name, table, profiles)?;
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn basic(credentials: Option<Credentials>) -> Option<(String, String)> {
+        match credentials {
+            Some(Credentials::Basic(u, p)) => Some((u, p)),
+            _ => None,
+        }
+    }
+
+    fn pat(credentials: Option<Credentials>) -> Option<String> {
+        match credentials {
+            Some(Credentials::Pat(t)) => Some(t),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_env() {
+        env::set_var("JIRAC_ENV_TEST_USER", "jdoe");
+        env::set_var("JIRAC_ENV_TEST_API_TOKEN", "secret");
+
+        let provider = Env::with_prefix("JIRAC_ENV_TEST");
+        assert_eq!(
+            basic(provider.credentials("https://a.com").unwrap()),
+            Some(("jdoe".to_string(), "secret".to_string()))
+        );
+
+        env::set_var("JIRAC_ENV_TEST_PAT", "pat");
+        assert_eq!(
+            pat(provider.credentials("https://a.com").unwrap()),
+            Some("pat".to_string())
+        );
+
+        assert!(Env::with_prefix("JIRAC_ENV_UNSET")
+            .credentials("https://a.com")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_netrc() {
+        let netrc = "machine jira.example.com login jdoe password secret\n\
+                     machine dc.example.com\n  password pat\n\
+                     default login anon password anon\n";
+
+        assert_eq!(
+            basic(Netrc::parse(netrc, "jira.example.com")),
+            Some(("jdoe".to_string(), "secret".to_string()))
+        );
+        assert_eq!(
+            pat(Netrc::parse(netrc, "dc.example.com")),
+            Some("pat".to_string())
+        );
+        assert_eq!(
+            basic(Netrc::parse(netrc, "other.example.com")),
+            Some(("anon".to_string(), "anon".to_string()))
+        );
+        assert!(Netrc::parse("machine a.com login a password b", "b.com").is_none());
+    }
+
+    #[test]
+    fn test_profiles() {
+        let profiles = Profiles::parse(
+            r#"
+# sites
+default = "work"
+
+[work]
+host = "https://work.atlassian.net"
+user = "jdoe@example.com"
+token = 'api\token' # literal
+
+["on prem"]
+host = "https://jira.example.com/jira"
+pat = "p\"até"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(profiles.default_profile().unwrap().name, "work");
+        assert_eq!(
+            profiles.profile("work").unwrap().token.as_deref(),
+            Some("api\\token")
+        );
+        assert_eq!(
+            basic(profiles.credentials("https://work.atlassian.net").unwrap()),
+            Some(("jdoe@example.com".to_string(), "api\\token".to_string()))
+        );
+        assert_eq!(
+            pat(profiles
+                .credentials("https://jira.example.com/jira")
+                .unwrap()),
+            Some("p\"até".to_string())
+        );
+        assert!(profiles.credentials("https://other.com").unwrap().is_none());
+
+        assert!(Profiles::parse("[work\nhost = \"a\"").is_err());
+        assert!(Profiles::parse("[work]\nhost = 1").is_err());
+        assert!(Profiles::parse("default = 1").is_err());
+    }
+
+    #[test]
+    fn test_profiles_nested() {
+        let profiles = Profiles::parse(
+            r#"
+default = "jira.prod"
+
+[jira]
+timeout = 30
+
+[jira.prod]
+host = "https://jira.example.com"
+pat = "prod-token"
+verify = true
+projects = ["OPS", "WEB"]
+
+[jira.staging]
+host = "https://staging.example.com"
+user = "jdoe"
+token = "staging-token"
+
+[jira.staging.proxy]
+url = "http://proxy.example.com"
+"#,
+        )
+        .unwrap();
+
+        let names: Vec<_> = profiles.profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["jira", "jira.prod", "jira.staging", "jira.staging.proxy"]
+        );
+        assert_eq!(
+            pat(profiles.default_profile().unwrap().credentials()),
+            Some("prod-token".to_string())
+        );
+        assert_eq!(
+            basic(profiles.credentials("https://staging.example.com").unwrap()),
+            Some(("jdoe".to_string(), "staging-token".to_string()))
+        );
+        assert!(profiles.profile("jira").unwrap().credentials().is_none());
+
+        let grouped = Profiles::parse("[a.b]\nhost = \"https://b.example.com\"").unwrap();
+        assert_eq!(grouped.profiles.len(), 1);
+        assert_eq!(grouped.default_profile().unwrap().name, "a.b");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_profiles_debug() {
+        let profiles = Profiles::parse(
+            r#"
+[work]
+host = "https://work.atlassian.net"
+user = "jdoe@example.com"
+token = "api-token"
+
+[onprem]
+host = "https://jira.example.com"
+pat = "personal-access-token"
+"#,
+        )
+        .unwrap();
+
+        let debug = format!("{:?}", profiles);
+        assert!(debug.contains("jdoe@example.com"));
+        assert!(!debug.contains("api-token"));
+        assert!(!debug.contains("personal-access-token"));
+    }
+
+    #[test]
+    fn test_command() {
+        let provider = Command::new("sh")
+            .arg("-c")
+            .arg("read p; read h; echo username=jdoe; echo password=\"$p $h\"");
+
+        assert_eq!(
+            basic(provider.credentials("https://jira.example.com").unwrap()),
+            Some((
+                "jdoe".to_string(),
+                "protocol=https host=jira.example.com".to_string()
+            ))
+        );
+
+        assert!(Command::new("sh")
+            .arg("-c")
+            .arg("exit 0")
+            .credentials("https://a.com")
+            .unwrap()
+            .is_none());
+        assert!(Command::new("sh")
+            .arg("-c")
+            .arg("exit 1")
+            .credentials("https://a.com")
+            .is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! Cookie based sessions through `/rest/auth/1/session`.
+//!
+//! The client logs in the first time a request is sent and sends the
+//! session cookie from then on rather than the username and password, which
+//! avoids the CAPTCHA JIRA Server starts asking for when basic auth is used
+//! heavily. When the session expires the client logs in again and retries.
+//!
+//! ```no_run
+//! use jirac::{Client, Credentials};
+//!
+//! let client = Client::new(
+//!     "https://jira.example.com",
+//!     Credentials::new_session("jdoe", "password").unwrap(),
+//! );
+//!
+//! // ... requests ...
+//!
+//! client.logout().unwrap();
+//! ```
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::Client;
+use crate::{Error, HttpContext, Result};
+use reqwest::header::{HeaderMap, COOKIE, SET_COOKIE};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// A login and the session cookie it was given, clones share the cookie so
+/// clients cloned from one another use the same session
+#[derive(Clone)]
+pub struct Session {
+    pub username: String,
+    password: String,
+    cookie: Arc<Mutex<Option<SessionCookie>>>,
+}
+
+/// The cookie JIRA identifies the session by, normally `JSESSIONID`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SessionCookie {
+    pub name: String,
+    pub value: String,
+}
+
+impl Session {
+    pub fn new<U, P>(username: U, password: P) -> Session
+    where
+        U: Into<String>,
+        P: Into<String>,
+    {
+        Session {
+            username: username.into(),
+            password: password.into(),
+            cookie: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Starts with a cookie from an earlier login, such as one saved by a
+    /// previous run, a new one is fetched if it has expired
+    pub fn with_cookie(self, cookie: SessionCookie) -> Session {
+        *self.lock() = Some(cookie);
+        self
+    }
+
+    /// The current session cookie, `None` before the first login or after
+    /// logging out
+    pub fn cookie(&self) -> Option<SessionCookie> {
+        self.lock().clone()
+    }
+
+    /// Logs in and keeps the new session cookie
+    pub fn login(&self, client: &Client) -> Result<SessionCookie> {
+        let mut cookie = self.lock();
+        let new = self.create(client)?;
+
+        *cookie = Some(new.clone());
+
+        Ok(new)
+    }
+
+    /// Ends the session on the server and forgets the cookie, does nothing
+    /// if there is no session
+    pub fn logout(&self, client: &Client) -> Result<()> {
+        let cookie = match self.lock().take() {
+            Some(cookie) => cookie,
+            None => return Ok(()),
+        };
+
+        let mut res = client
+            .client
+            .delete(&format!("{}/rest/auth/1/session", client.host))
+            .header(COOKIE, cookie.header())
+            .send()?;
+
+        match res.status() {
+            // an expired session is as good as logged out
+            s if s.is_success() || s == StatusCode::UNAUTHORIZED => Ok(()),
+            _ => Err(Error::from_status(HttpContext::read(Method::DELETE, &mut res)?)),
+        }
+    }
+
+    /// The value of the Cookie header, logging in first if needed
+    pub(crate) fn header(&self, client: &Client) -> Result<String> {
+        let mut cookie = self.lock();
+
+        if cookie.is_none() {
+            *cookie = Some(self.create(client)?);
+        }
+
+        Ok(cookie
+            .as_ref()
+            .map(SessionCookie::header)
+            .unwrap_or_default())
+    }
+
+    /// Keeps the cookie when JIRA replaces it in a response
+    pub(crate) fn update(&self, headers: &HeaderMap) {
+        let mut cookie = self.lock();
+        let name = match *cookie {
+            Some(ref c) => c.name.clone(),
+            None => return,
+        };
+
+        let value = headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|h| h.to_str().ok())
+            .filter_map(|h| h.split(';').next())
+            .filter_map(|pair| {
+                let eq = pair.find('=')?;
+                Some((pair[..eq].trim(), pair[eq + 1..].trim()))
+            })
+            .filter(|(n, v)| *n == name && !v.is_empty())
+            .map(|(_, v)| v.to_string())
+            .next_back();
+
+        if let Some(value) = value {
+            *cookie = Some(SessionCookie { name, value });
+        }
+    }
+
+    fn create(&self, client: &Client) -> Result<SessionCookie> {
+        let body = serde_json::to_string(&Login {
+            username: &self.username,
+            password: &self.password,
+        })?;
+
+        let mut res = client
+            .client
+            .post(&format!("{}/rest/auth/1/session", client.host))
+            .header("content-type", "application/json")
+            .body(body)
+            .send()?;
+
+        // A 401 is a bad password, JIRA answers with a 403 once it wants a
+        // CAPTCHA after too many failed logins
+        match res.status() {
+            s if s.is_success() => Ok(res.json::<LoginResponse>()?.session),
+            _ => Err(Error::from_status(HttpContext::read(Method::POST, &mut res)?)),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<SessionCookie>> {
+        self.cookie.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SessionCookie {
+    fn header(&self) -> String {
+        format!("{}={}", self.name, self.value)
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Session")
+            .field("username", &self.username)
+            .field("logged_in", &self.lock().is_some())
+            .finish()
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+#[derive(Serialize)]
+struct Login<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+#[derive(Deserialize)]
+struct LoginResponse {
+    session: SessionCookie,
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_update_from_set_cookie() {
+        let session = Session::new("jdoe", "pw").with_cookie(SessionCookie {
+            name: "JSESSIONID".to_string(),
+            value: "old".to_string(),
+        });
+
+        let mut headers = HeaderMap::new();
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("atlassian.xsrf.token=x; Path=/"),
+        );
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("JSESSIONID=new; Path=/; HttpOnly"),
+        );
+        session.update(&headers);
+
+        assert_eq!(session.cookie().unwrap().value, "new");
+
+        headers.clear();
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("JSESSIONID=; Max-Age=0"),
+        );
+        session.update(&headers);
+
+        assert_eq!(session.cookie().unwrap().value, "new");
+    }
+}
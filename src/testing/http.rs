@@ -0,0 +1,138 @@
+//! Just enough HTTP/1.1 to serve the client, every connection handles one
+//! request and is closed
+
+// ============================================================================
+// Use
+// ============================================================================
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Result, Write};
+use std::net::TcpStream;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// A request made to the fake
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+
+    /// The path without the query, e.g. `/rest/api/2/issue/TEST-1`
+    pub path: String,
+
+    /// Query parameters, decoded
+    pub query: HashMap<String, String>,
+
+    /// Header names are lower case
+    pub headers: HashMap<String, String>,
+
+    pub body: Vec<u8>,
+}
+
+pub(crate) struct Response {
+    pub status: u16,
+    pub body: Option<Value>,
+}
+
+impl Request {
+    pub(crate) fn read(stream: &mut TcpStream) -> Result<Request> {
+        let mut reader = BufReader::new(stream);
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let target = parts.next().unwrap_or("/").to_string();
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some(colon) = line.find(':') {
+                headers.insert(
+                    line[..colon].trim().to_lowercase(),
+                    line[colon + 1..].trim().to_string(),
+                );
+            }
+        }
+
+        let length = headers
+            .get("content-length")
+            .and_then(|l| l.parse().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+
+        let url = Url::parse(&format!("http://localhost{}", target))
+            .unwrap_or_else(|_| Url::parse("http://localhost/").unwrap());
+
+        Ok(Request {
+            method,
+            path: url.path().to_string(),
+            query: url.query_pairs().into_owned().collect(),
+            headers,
+            body,
+        })
+    }
+
+    /// The body parsed as JSON, `Value::Null` when empty or invalid
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or(Value::Null)
+    }
+}
+
+impl Response {
+    pub(crate) fn json(status: u16, body: Value) -> Response {
+        Response {
+            status,
+            body: Some(body),
+        }
+    }
+
+    pub(crate) fn empty(status: u16) -> Response {
+        Response { status, body: None }
+    }
+
+    /// An error in the shape JIRA sends, see `crate::Errors`
+    pub(crate) fn errors(status: u16, messages: Vec<String>, errors: Value) -> Response {
+        Response::json(
+            status,
+            json!({ "errorMessages": messages, "errors": errors }),
+        )
+    }
+
+    pub(crate) fn write(&self, stream: &mut TcpStream) -> Result<()> {
+        let body = self.body.as_ref().map(Value::to_string).unwrap_or_default();
+
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json;charset=UTF-8\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason(self.status),
+            body.len(),
+            body
+        )?;
+
+        stream.flush()
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Unknown",
+    }
+}
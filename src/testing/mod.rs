@@ -0,0 +1,168 @@
+//! An in-memory fake JIRA for integration tests, enabled with the `testing`
+//! feature.
+//!
+//! Unlike canned responses the fake keeps state: issues created through it
+//! can be fetched, edited, commented on, transitioned and searched for, and
+//! it answers bad requests with errors in the shape JIRA uses so they come
+//! back as `Error::Fault`.
+//!
+//! ```
+//! use jirac::testing::FakeJira;
+//! use serde_json::{json, Value};
+//!
+//! let jira = FakeJira::start();
+//! jira.add_project("TEST", "Test project");
+//!
+//! let client = jira.client();
+//! let created = client
+//!     .post::<_, Value>(
+//!         "api/2/issue",
+//!         json!({"fields": {
+//!             "project": {"key": "TEST"},
+//!             "issuetype": {"name": "Bug"},
+//!             "summary": "It broke"
+//!         }}),
+//!     )
+//!     .unwrap()
+//!     .data;
+//!
+//! assert_eq!(created["key"], "TEST-1");
+//! ```
+//!
+//! It serves:
+//!
+//! * `GET serverInfo`, `myself`, `project`, `project/{key}` and `status`
+//! * `POST issue`, `GET`, `PUT` and `DELETE issue/{key}`
+//! * `GET` and `POST issue/{key}/transitions`, the workflow goes between
+//!   "To Do", "In Progress" and "Done"
+//! * `GET` and `POST issue/{key}/comment`
+//! * `GET` and `POST search`, with a subset of JQL
+//! * `POST version`, `GET` and `PUT version/{id}`
+
+// ============================================================================
+// Use
+// ============================================================================
+pub use crate::testing::http::Request;
+
+use crate::testing::state::State;
+use crate::{Client, Credentials};
+use serde_json::Value;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+// ============================================================================
+// Private Modules
+// ============================================================================
+mod http;
+mod state;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// A fake JIRA listening on a local port, it stops when dropped
+pub struct FakeJira {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl FakeJira {
+    /// Starts the fake on a free port with no projects or issues
+    pub fn start() -> FakeJira {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("could not bind the fake JIRA");
+        let addr = listener.local_addr().expect("fake JIRA has no address");
+        let state = Arc::new(Mutex::new(State::new(format!("http://{}", addr))));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let (s, st) = (state.clone(), stop.clone());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if st.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let state = s.clone();
+                    thread::spawn(move || serve(stream, &state));
+                }
+            }
+        });
+
+        FakeJira { addr, state, stop }
+    }
+
+    /// The base URL to build a client with
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// A client pointing at the fake, credentials are not checked
+    pub fn client(&self) -> Client {
+        Client::new(
+            self.url(),
+            Credentials::Basic("admin".into(), "admin".into()),
+        )
+    }
+
+    /// Adds a project with every issue type and returns it as JIRA would
+    pub fn add_project(&self, key: &str, name: &str) -> Value {
+        self.lock().add_project(key, name)
+    }
+
+    /// The current state of an issue, `None` if there is no such issue
+    pub fn issue(&self, key: &str) -> Option<Value> {
+        self.lock().issues.iter().find(|i| i["key"] == key).cloned()
+    }
+
+    /// Every issue in the order they were created
+    pub fn issues(&self) -> Vec<Value> {
+        self.lock().issues.clone()
+    }
+
+    /// The requests received so far, oldest first
+    pub fn requests(&self) -> Vec<Request> {
+        self.lock().requests.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl Drop for FakeJira {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // wake the listener up so it sees it has to stop
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+impl std::fmt::Debug for FakeJira {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("FakeJira")
+            .field("url", &self.url())
+            .finish()
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+fn serve(mut stream: TcpStream, state: &Mutex<State>) {
+    let request = match Request::read(&mut stream) {
+        Ok(request) if !request.method.is_empty() => request,
+        _ => return,
+    };
+
+    let response = {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        state.requests.push(request.clone());
+        state.handle(&request)
+    };
+
+    let _ = response.write(&mut stream);
+}
@@ -0,0 +1,933 @@
+//! The data held by the fake and the handlers for each endpoint. Resources
+//! are kept as the JSON JIRA would send so the handlers only have to look
+//! things up and patch them.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::testing::http::{Request, Response};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+pub(crate) struct State {
+    pub base: String,
+    pub projects: Vec<Value>,
+    pub issues: Vec<Value>,
+    pub versions: Vec<Value>,
+    pub requests: Vec<Request>,
+    next_id: u64,
+    /// The last issue number handed out in each project, JIRA doesn't reuse
+    /// the keys of deleted issues
+    issue_numbers: HashMap<String, u64>,
+}
+
+impl State {
+    pub(crate) fn new(base: String) -> State {
+        State {
+            base,
+            projects: vec![],
+            issues: vec![],
+            versions: vec![],
+            requests: vec![],
+            next_id: 10000,
+            issue_numbers: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn add_project(&mut self, key: &str, name: &str) -> Value {
+        let id = self.next_id();
+        let project = json!({
+            "self": format!("{}/rest/api/2/project/{}", self.base, id),
+            "id": id.to_string(),
+            "key": key,
+            "name": name,
+            "projectTypeKey": "software",
+            "issueTypes": ISSUE_TYPES
+                .iter()
+                .map(|(id, name, subtask)| issue_type(&self.base, id, name, *subtask))
+                .collect::<Vec<_>>(),
+            "components": [],
+            "versions": [],
+            "archived": false
+        });
+
+        self.projects.push(project.clone());
+        project
+    }
+
+    pub(crate) fn handle(&mut self, req: &Request) -> Response {
+        let path = match req.path.strip_prefix("/rest/api/2/") {
+            Some(path) => path.trim_end_matches('/'),
+            None => return not_found("The requested resource could not be found"),
+        };
+        let segments: Vec<&str> = path.split('/').collect();
+        let method = req.method.as_str();
+
+        match (method, segments.as_slice()) {
+            ("GET", ["serverInfo"]) => Response::json(200, self.server_info()),
+            ("GET", ["myself"]) => Response::json(200, myself(&self.base)),
+            ("GET", ["project"]) => Response::json(200, json!(self.projects)),
+            ("GET", ["project", key]) => match self.project(key) {
+                Some(project) => Response::json(200, project),
+                None => not_found(&format!("No project could be found with key '{}'.", key)),
+            },
+            ("GET", ["status"]) => Response::json(
+                200,
+                json!(STATUSES
+                    .iter()
+                    .map(|s| status(&self.base, s))
+                    .collect::<Vec<_>>()),
+            ),
+            ("POST", ["issue"]) => self.create_issue(&req.json()),
+            ("GET", ["issue", key]) => match self.issue_index(key) {
+                Some(i) => Response::json(200, self.issues[i].clone()),
+                None => issue_not_found(),
+            },
+            ("PUT", ["issue", key]) => self.edit_issue(key, &req.json()),
+            ("DELETE", ["issue", key]) => match self.issue_index(key) {
+                Some(i) => {
+                    self.issues.remove(i);
+                    Response::empty(204)
+                }
+                None => issue_not_found(),
+            },
+            ("GET", ["issue", key, "transitions"]) => match self.issue_index(key) {
+                Some(i) => Response::json(
+                    200,
+                    json!({
+                        "expand": "transitions",
+                        "transitions": self.transitions(i)
+                    }),
+                ),
+                None => issue_not_found(),
+            },
+            ("POST", ["issue", key, "transitions"]) => self.transition(key, &req.json()),
+            ("GET", ["issue", key, "comment"]) => match self.issue_index(key) {
+                Some(i) => {
+                    let comments = self.issues[i]["fields"]["comment"]["comments"].clone();
+                    Response::json(200, page(req, &comments, "comments"))
+                }
+                None => issue_not_found(),
+            },
+            ("POST", ["issue", key, "comment"]) => self.add_comment(key, &req.json()),
+            ("GET", ["search"]) => self.search(req, req.query.get("jql").map(String::as_str)),
+            ("POST", ["search"]) => {
+                let body = req.json();
+                let mut req = req.clone();
+                for param in &["startAt", "maxResults"] {
+                    if let Some(n) = body[*param].as_u64() {
+                        req.query.insert(param.to_string(), n.to_string());
+                    }
+                }
+                self.search(&req, body["jql"].as_str())
+            }
+            ("POST", ["version"]) => self.create_version(&req.json()),
+            ("GET", ["version", id]) => match self.version_index(id) {
+                Some(i) => Response::json(200, self.versions[i].clone()),
+                None => not_found(&format!("Could not find version for id '{}'", id)),
+            },
+            ("PUT", ["version", id]) => self.edit_version(id, &req.json()),
+            (_, ["issue", ..]) | (_, ["version", ..]) | (_, ["project", ..]) => {
+                Response::empty(405)
+            }
+            _ => not_found("null for uri"),
+        }
+    }
+
+    // ========================================================================
+    // Server
+    // ========================================================================
+    fn server_info(&self) -> Value {
+        json!({
+            "baseUrl": self.base,
+            "version": "8.20.0",
+            "versionNumbers": [8, 20, 0],
+            "deploymentType": "Server",
+            "buildNumber": 820000,
+            "serverTitle": "Fake JIRA",
+            "serverTime": timestamp(now()),
+        })
+    }
+
+    // ========================================================================
+    // Projects
+    // ========================================================================
+    fn project(&self, key_or_id: &str) -> Option<Value> {
+        let mut project = self
+            .projects
+            .iter()
+            .find(|p| p["key"] == key_or_id || p["id"] == key_or_id)?
+            .clone();
+
+        let id: u64 = project["id"].as_str()?.parse().ok()?;
+        project["versions"] = json!(self
+            .versions
+            .iter()
+            .filter(|v| v["projectId"] == id)
+            .collect::<Vec<_>>());
+
+        Some(project)
+    }
+
+    // ========================================================================
+    // Issues
+    // ========================================================================
+    fn create_issue(&mut self, body: &Value) -> Response {
+        let fields = body["fields"].as_object().cloned().unwrap_or_default();
+        let mut errors = Map::new();
+
+        let project = match fields.get("project") {
+            None => {
+                errors.insert("project".into(), json!("project is required"));
+                None
+            }
+            Some(p) => {
+                let key = p["key"]
+                    .as_str()
+                    .or_else(|| p["id"].as_str())
+                    .unwrap_or_default();
+                let project = self.project(key);
+                if project.is_none() {
+                    errors.insert("project".into(), json!("valid project is required"));
+                }
+                project
+            }
+        };
+
+        let found_type = match fields.get("issuetype") {
+            None => {
+                errors.insert("issuetype".into(), json!("issue type is required"));
+                None
+            }
+            Some(t) => {
+                let found = ISSUE_TYPES
+                    .iter()
+                    .find(|(id, name, _)| t["id"] == *id || t["name"] == *name);
+                if found.is_none() {
+                    errors.insert("issuetype".into(), json!("valid issue type is required"));
+                }
+                found
+            }
+        };
+
+        if fields
+            .get("summary")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .is_empty()
+        {
+            errors.insert(
+                "summary".into(),
+                json!("You must specify a summary of the issue."),
+            );
+        }
+
+        let (project, (type_id, type_name, subtask)) = match (project, found_type) {
+            (Some(p), Some(t)) if errors.is_empty() => (p, t),
+            _ => return Response::errors(400, vec![], Value::Object(errors)),
+        };
+
+        let id = self.next_id().to_string();
+        let project_key = project["key"].as_str().unwrap_or_default().to_string();
+        let number = self.issue_numbers.entry(project_key.clone()).or_insert(0);
+        *number += 1;
+        let key = format!("{}-{}", project_key, number);
+        let link = format!("{}/rest/api/2/issue/{}", self.base, id);
+        let now = timestamp(now());
+
+        let mut issue_fields = fields;
+        issue_fields.insert(
+            "project".into(),
+            json!({
+                "self": project["self"],
+                "id": project["id"],
+                "key": project["key"],
+                "name": project["name"],
+                "projectTypeKey": project["projectTypeKey"]
+            }),
+        );
+        issue_fields.insert(
+            "issuetype".into(),
+            issue_type(&self.base, type_id, type_name, *subtask),
+        );
+        issue_fields.insert("status".into(), status(&self.base, &STATUSES[0]));
+        issue_fields.insert("resolution".into(), Value::Null);
+        issue_fields.insert("resolutiondate".into(), Value::Null);
+        issue_fields.insert("created".into(), json!(now));
+        issue_fields.insert("updated".into(), json!(now));
+        issue_fields.insert("reporter".into(), myself(&self.base));
+        issue_fields.entry("labels").or_insert_with(|| json!([]));
+        issue_fields.entry("description").or_insert(Value::Null);
+        issue_fields.insert(
+            "comment".into(),
+            json!({"comments": [], "startAt": 0, "maxResults": 0, "total": 0}),
+        );
+
+        self.issues.push(json!({
+            "expand": "renderedFields,names,schema,operations,editmeta,changelog,versionedRepresentations",
+            "id": id,
+            "self": link,
+            "key": key,
+            "fields": issue_fields,
+        }));
+
+        Response::json(201, json!({"id": id, "key": key, "self": link}))
+    }
+
+    fn edit_issue(&mut self, key: &str, body: &Value) -> Response {
+        let i = match self.issue_index(key) {
+            Some(i) => i,
+            None => return issue_not_found(),
+        };
+
+        let mut errors = Map::new();
+        let fields = body["fields"].as_object().cloned().unwrap_or_default();
+        let updates = body["update"].as_object().cloned().unwrap_or_default();
+
+        for name in fields.keys().chain(updates.keys()) {
+            if READ_ONLY.contains(&name.as_str()) {
+                errors.insert(
+                    name.clone(),
+                    json!(format!(
+                        "Field '{}' cannot be set. It is not on the appropriate screen, or unknown.",
+                        name
+                    )),
+                );
+            }
+        }
+        if fields
+            .get("summary")
+            .is_some_and(|s| s.as_str().unwrap_or_default().is_empty())
+        {
+            errors.insert(
+                "summary".into(),
+                json!("You must specify a summary of the issue."),
+            );
+        }
+        if !errors.is_empty() {
+            return Response::errors(400, vec![], Value::Object(errors));
+        }
+
+        let issue_fields = &mut self.issues[i]["fields"];
+        for (name, value) in fields {
+            issue_fields[name] = value;
+        }
+
+        // each field in `update` holds a list of set, add and remove operations
+        for (name, operations) in updates {
+            for op in operations.as_array().cloned().unwrap_or_default() {
+                let current = &mut issue_fields[name.as_str()];
+                if let Some(value) = op.get("set") {
+                    *current = value.clone();
+                }
+                if let Some(value) = op.get("add") {
+                    match current.as_array_mut() {
+                        Some(values) => values.push(value.clone()),
+                        None => *current = json!([value]),
+                    }
+                }
+                if let Some(value) = op.get("remove") {
+                    if let Some(values) = current.as_array_mut() {
+                        values.retain(|v| v != value);
+                    }
+                }
+            }
+        }
+
+        issue_fields["updated"] = json!(timestamp(now()));
+
+        Response::empty(204)
+    }
+
+    fn transitions(&self, i: usize) -> Vec<Value> {
+        let current = &self.issues[i]["fields"]["status"]["id"];
+
+        TRANSITIONS
+            .iter()
+            .filter(|(_, _, to)| *current != STATUSES[*to].0)
+            .map(|(id, name, to)| {
+                json!({
+                    "id": id,
+                    "name": name,
+                    "to": status(&self.base, &STATUSES[*to]),
+                    "hasScreen": false,
+                    "isGlobal": true,
+                    "isInitial": false,
+                    "isConditional": false
+                })
+            })
+            .collect()
+    }
+
+    fn transition(&mut self, key: &str, body: &Value) -> Response {
+        let i = match self.issue_index(key) {
+            Some(i) => i,
+            None => return issue_not_found(),
+        };
+
+        let id = match &body["transition"]["id"] {
+            Value::String(id) => id.clone(),
+            Value::Number(id) => id.to_string(),
+            _ => {
+                return Response::errors(
+                    400,
+                    vec!["Missing 'transition' identifier".to_string()],
+                    json!({}),
+                )
+            }
+        };
+
+        let to = match TRANSITIONS.iter().find(|(t, _, _)| *t == id) {
+            Some((_, _, to)) if self.transitions(i).iter().any(|t| t["id"] == id.as_str()) => {
+                &STATUSES[*to]
+            }
+            _ => {
+                return Response::errors(
+                    400,
+                    vec![format!(
+                        "Transition id '{}' is not valid for this issue.",
+                        id
+                    )],
+                    json!({}),
+                )
+            }
+        };
+
+        let now = timestamp(now());
+        let base = self.base.clone();
+        let fields = &mut self.issues[i]["fields"];
+
+        for (name, value) in body["fields"].as_object().cloned().unwrap_or_default() {
+            fields[name] = value;
+        }
+
+        fields["status"] = status(&base, to);
+        if to.3 == "done" {
+            if fields["resolution"].is_null() {
+                fields["resolution"] = json!({
+                    "self": format!("{}/rest/api/2/resolution/10000", base),
+                    "id": "10000",
+                    "name": "Done",
+                    "description": "Work on this issue is complete."
+                });
+            }
+            fields["resolutiondate"] = json!(now);
+        } else {
+            fields["resolution"] = Value::Null;
+            fields["resolutiondate"] = Value::Null;
+        }
+        fields["updated"] = json!(now);
+
+        Response::empty(204)
+    }
+
+    fn add_comment(&mut self, key: &str, body: &Value) -> Response {
+        let i = match self.issue_index(key) {
+            Some(i) => i,
+            None => return issue_not_found(),
+        };
+
+        let text = body["body"].as_str().unwrap_or_default();
+        if text.is_empty() {
+            return Response::errors(
+                400,
+                vec![],
+                json!({"comment": "Comment body can not be empty!"}),
+            );
+        }
+
+        let id = self.next_id();
+        let now = timestamp(now());
+        let comment = json!({
+            "self": format!("{}/rest/api/2/issue/{}/comment/{}", self.base, self.issues[i]["id"].as_str().unwrap_or_default(), id),
+            "id": id.to_string(),
+            "author": myself(&self.base),
+            "updateAuthor": myself(&self.base),
+            "body": text,
+            "created": now,
+            "updated": now
+        });
+
+        let comments = &mut self.issues[i]["fields"]["comment"];
+        if let Some(list) = comments["comments"].as_array_mut() {
+            list.push(comment.clone());
+        }
+        let total = comments["comments"]
+            .as_array()
+            .map(Vec::len)
+            .unwrap_or_default();
+        comments["total"] = json!(total);
+        comments["maxResults"] = json!(total);
+
+        Response::json(201, comment)
+    }
+
+    fn search(&self, req: &Request, jql: Option<&str>) -> Response {
+        let clauses = match parse_jql(jql.unwrap_or_default()) {
+            Ok(clauses) => clauses,
+            Err(message) => return Response::errors(400, vec![message], json!({})),
+        };
+
+        let issues: Vec<Value> = self
+            .issues
+            .iter()
+            .filter(|issue| clauses.iter().all(|c| c.matches(issue)))
+            .cloned()
+            .collect();
+
+        Response::json(200, page(req, &json!(issues), "issues"))
+    }
+
+    fn issue_index(&self, key_or_id: &str) -> Option<usize> {
+        self.issues
+            .iter()
+            .position(|i| i["key"] == key_or_id || i["id"] == key_or_id)
+    }
+
+    // ========================================================================
+    // Versions
+    // ========================================================================
+    fn create_version(&mut self, body: &Value) -> Response {
+        let name = body["name"].as_str().unwrap_or_default().to_string();
+        if name.is_empty() {
+            return Response::errors(
+                400,
+                vec![],
+                json!({"name": "You must specify a valid version name"}),
+            );
+        }
+
+        let project = match &body["projectId"] {
+            Value::Number(id) => self.project(&id.to_string()),
+            _ => body["project"].as_str().and_then(|key| self.project(key)),
+        };
+        let project_id: u64 = match project
+            .as_ref()
+            .and_then(|p| p["id"].as_str()?.parse().ok())
+        {
+            Some(id) => id,
+            None => {
+                return Response::errors(
+                    400,
+                    vec![],
+                    json!({"project": "Project must be specified to create a version."}),
+                )
+            }
+        };
+
+        if self
+            .versions
+            .iter()
+            .any(|v| v["projectId"] == project_id && v["name"] == name.as_str())
+        {
+            return Response::errors(
+                400,
+                vec![],
+                json!({"name": "A version with this name already exists in this project."}),
+            );
+        }
+
+        let id = self.next_id().to_string();
+        let mut version = json!({
+            "self": format!("{}/rest/api/2/version/{}", self.base, id),
+            "id": id,
+            "name": name,
+            "description": body["description"].as_str().unwrap_or_default(),
+            "archived": body["archived"].as_bool().unwrap_or_default(),
+            "released": body["released"].as_bool().unwrap_or_default(),
+            "overdue": false,
+            "projectId": project_id
+        });
+        if let Some(date) = body["releaseDate"].as_str().filter(|d| !d.is_empty()) {
+            version["releaseDate"] = json!(date);
+            version["userReleaseDate"] = json!(date);
+        }
+
+        self.versions.push(version.clone());
+
+        Response::json(201, version)
+    }
+
+    fn edit_version(&mut self, id: &str, body: &Value) -> Response {
+        let i = match self.version_index(id) {
+            Some(i) => i,
+            None => return not_found(&format!("Could not find version for id '{}'", id)),
+        };
+
+        for field in &["name", "description", "archived", "released", "releaseDate"] {
+            match body.get(*field) {
+                Some(Value::String(s)) if s.is_empty() => {}
+                Some(value) => self.versions[i][*field] = value.clone(),
+                None => {}
+            }
+        }
+
+        Response::json(200, self.versions[i].clone())
+    }
+
+    fn version_index(&self, id: &str) -> Option<usize> {
+        self.versions.iter().position(|v| v["id"] == id)
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+/// id, name and whether it is a sub-task
+const ISSUE_TYPES: [(&str, &str, bool); 4] = [
+    ("1", "Bug", false),
+    ("3", "Task", false),
+    ("10001", "Story", false),
+    ("5", "Sub-task", true),
+];
+
+/// id, name, category id and category key of the workflow's statuses
+const STATUSES: [(&str, &str, i64, &str); 3] = [
+    ("10000", "To Do", 2, "new"),
+    ("3", "In Progress", 4, "indeterminate"),
+    ("10001", "Done", 3, "done"),
+];
+
+/// id, name and index of the status the transition leads to, every status
+/// can be reached from every other one
+const TRANSITIONS: [(&str, &str, usize); 3] = [
+    ("11", "To Do", 0),
+    ("21", "In Progress", 1),
+    ("31", "Done", 2),
+];
+
+/// Fields that are only changed through the workflow or not at all
+const READ_ONLY: [&str; 7] = [
+    "project",
+    "issuetype",
+    "status",
+    "resolutiondate",
+    "created",
+    "updated",
+    "comment",
+];
+
+struct Clause {
+    field: String,
+    values: Vec<String>,
+    negated: bool,
+}
+
+impl Clause {
+    fn matches(&self, issue: &Value) -> bool {
+        let fields = &issue["fields"];
+        let found = self.values.iter().any(|v| {
+            let v = v.to_lowercase();
+            let eq = |value: &Value| value.as_str().is_some_and(|s| s.to_lowercase() == v);
+
+            match self.field.as_str() {
+                "project" => eq(&fields["project"]["key"]) || eq(&fields["project"]["id"]),
+                "key" | "issuekey" | "id" => eq(&issue["key"]) || eq(&issue["id"]),
+                "status" => eq(&fields["status"]["name"]) || eq(&fields["status"]["id"]),
+                "statuscategory" => {
+                    eq(&fields["status"]["statusCategory"]["name"])
+                        || eq(&fields["status"]["statusCategory"]["key"])
+                }
+                "issuetype" | "type" => eq(&fields["issuetype"]["name"]),
+                "labels" => fields["labels"]
+                    .as_array()
+                    .is_some_and(|l| l.iter().any(eq)),
+                "summary" => fields["summary"]
+                    .as_str()
+                    .is_some_and(|s| s.to_lowercase().contains(&v)),
+                _ => false,
+            }
+        });
+
+        found != self.negated
+    }
+}
+
+/// Understands `AND`ed clauses using `=`, `!=`, `~`, `in` and `not in` on a
+/// few fields, `ORDER BY` is ignored and issues are returned in the order
+/// they were created
+fn parse_jql(jql: &str) -> Result<Vec<Clause>, String> {
+    let jql = match find_token(jql, &["order by"]) {
+        Some((i, _)) => &jql[..i],
+        None => jql,
+    };
+
+    let mut clauses = vec![];
+    for clause in split_and(jql) {
+        let clause = clause.trim();
+        if clause.is_empty() {
+            continue;
+        }
+
+        let invalid = || format!("Error in the JQL Query: unable to parse '{}'.", clause);
+
+        let (field, op, rest) = find_token(clause, &[" not in ", " in ", "!=", "=", "~"])
+            .map(|(i, op)| (clause[..i].trim(), op.trim(), clause[i + op.len()..].trim()))
+            .ok_or_else(invalid)?;
+
+        let field = field.to_lowercase();
+        if !FIELDS.contains(&field.as_str()) {
+            return Err(format!(
+                "Field '{}' does not exist or you do not have permission to view it.",
+                field
+            ));
+        }
+
+        let values = if op == "in" || op == "not in" {
+            rest.strip_prefix('(')
+                .and_then(|r| r.strip_suffix(')'))
+                .ok_or_else(invalid)?
+                .split(',')
+                .map(unquote)
+                .collect()
+        } else {
+            vec![unquote(rest)]
+        };
+
+        clauses.push(Clause {
+            field,
+            values,
+            negated: op == "!=" || op == "not in",
+        });
+    }
+
+    Ok(clauses)
+}
+
+const FIELDS: [&str; 10] = [
+    "project",
+    "key",
+    "issuekey",
+    "id",
+    "status",
+    "statuscategory",
+    "issuetype",
+    "type",
+    "labels",
+    "summary",
+];
+
+fn split_and(jql: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut rest = jql;
+
+    while let Some((i, and)) = find_token(rest, &[" and "]) {
+        parts.push(&rest[..i]);
+        rest = &rest[i + and.len()..];
+    }
+    parts.push(rest);
+
+    parts
+}
+
+/// Finds the first of `tokens` outside quoted values, ignoring ASCII case.
+/// The tokens are ASCII so the offset is always a char boundary in `jql`,
+/// which lowercasing the whole query wouldn't guarantee
+fn find_token(jql: &str, tokens: &[&'static str]) -> Option<(usize, &'static str)> {
+    let bytes = jql.as_bytes();
+    let mut quote = None;
+
+    for i in 0..bytes.len() {
+        match (quote, bytes[i]) {
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"') | (None, b'\'') => quote = Some(bytes[i]),
+            (None, _) => {
+                let rest = &bytes[i..];
+                let found = tokens.iter().find(|t| {
+                    rest.len() >= t.len() && rest[..t.len()].eq_ignore_ascii_case(t.as_bytes())
+                });
+
+                if let Some(token) = found {
+                    return Some((i, token));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
+}
+
+/// Slices `items` by the `startAt` and `maxResults` query parameters
+fn page(req: &Request, items: &Value, name: &str) -> Value {
+    let items = items.as_array().cloned().unwrap_or_default();
+    let param = |name: &str, default: usize| {
+        req.query
+            .get(name)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    };
+    let start = param("startAt", 0);
+    let max = param("maxResults", 50).min(1000);
+
+    let mut page = json!({
+        "startAt": start,
+        "maxResults": max,
+        "total": items.len(),
+    });
+    page[name] = json!(items.into_iter().skip(start).take(max).collect::<Vec<_>>());
+
+    page
+}
+
+fn issue_type(base: &str, id: &str, name: &str, subtask: bool) -> Value {
+    json!({
+        "self": format!("{}/rest/api/2/issuetype/{}", base, id),
+        "id": id,
+        "name": name,
+        "description": "",
+        "iconUrl": "",
+        "subtask": subtask
+    })
+}
+
+fn status(base: &str, status: &(&str, &str, i64, &str)) -> Value {
+    let (id, name, category_id, category_key) = *status;
+    let category_name = match category_key {
+        "new" => "To Do",
+        "indeterminate" => "In Progress",
+        _ => "Done",
+    };
+    let colour = match category_key {
+        "new" => "blue-gray",
+        "indeterminate" => "yellow",
+        _ => "green",
+    };
+
+    json!({
+        "self": format!("{}/rest/api/2/status/{}", base, id),
+        "id": id,
+        "name": name,
+        "description": "",
+        "iconUrl": "",
+        "statusCategory": {
+            "self": format!("{}/rest/api/2/statuscategory/{}", base, category_id),
+            "id": category_id,
+            "key": category_key,
+            "colorName": colour,
+            "name": category_name
+        }
+    })
+}
+
+fn myself(base: &str) -> Value {
+    json!({
+        "self": format!("{}/rest/api/2/user?username=admin", base),
+        "key": "admin",
+        "name": "admin",
+        "emailAddress": "admin@example.com",
+        "displayName": "Administrator",
+        "active": true,
+        "timeZone": "UTC"
+    })
+}
+
+fn not_found(message: &str) -> Response {
+    Response::errors(404, vec![message.to_string()], json!({}))
+}
+
+fn issue_not_found() -> Response {
+    not_found("Issue Does Not Exist")
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Formats milliseconds since the epoch the way JIRA does,
+/// "2020-03-08T14:49:58.599+0000"
+fn timestamp(millis: u64) -> String {
+    let secs = millis / 1000;
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}+0000",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        millis % 1000
+    )
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0), "1970-01-01T00:00:00.000+0000");
+        assert_eq!(timestamp(1_583_678_998_599), "2020-03-08T14:49:58.599+0000");
+        assert_eq!(timestamp(951_782_400_000), "2000-02-29T00:00:00.000+0000");
+    }
+
+    #[test]
+    fn test_parse_jql() {
+        let clauses = parse_jql(
+            "project = TEST AND status in (\"To Do\", Done) and labels != x ORDER BY key",
+        )
+        .unwrap();
+
+        assert_eq!(clauses.len(), 3);
+        assert_eq!(clauses[1].field, "status");
+        assert_eq!(clauses[1].values, vec!["To Do", "Done"]);
+        assert!(clauses[2].negated);
+
+        assert!(parse_jql("").unwrap().is_empty());
+        assert!(parse_jql("reporter = admin").is_err());
+        assert!(parse_jql("project").is_err());
+    }
+
+    #[test]
+    fn test_parse_jql_non_ascii() {
+        let clauses = parse_jql("summary ~ \"İstanbul\" and project = TEST").unwrap();
+
+        assert_eq!(clauses.len(), 2);
+        assert_eq!(clauses[0].values, vec!["İstanbul"]);
+        assert_eq!(clauses[1].field, "project");
+        assert_eq!(clauses[1].values, vec!["TEST"]);
+
+        let clauses = parse_jql("summary ~ 'Ærø and Fanø' AND project = TEST").unwrap();
+        assert_eq!(clauses[0].values, vec!["Ærø and Fanø"]);
+        assert_eq!(clauses[1].values, vec!["TEST"]);
+
+        let clauses = parse_jql("summary ~ \"order by İ\" order by key").unwrap();
+        assert_eq!(clauses[0].values, vec!["order by İ"]);
+    }
+}
@@ -6,6 +6,7 @@
 use crate::v2::User;
 use crate::Client;
 use crate::Response;
+use crate::OptionalTimestamp;
 use crate::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
@@ -27,8 +28,8 @@ pub struct Attachment {
     pub author: Option<User>,
 
     /// Date created in the following format: "2020-03-09T20:01:55.575+0000"
-    #[serde(default)]
-    pub created: String,
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub created: OptionalTimestamp,
 
     /// Size of the attachment in bytes
     #[serde(default)]
@@ -82,6 +83,7 @@ impl std::fmt::Display for Attachment {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::format_optional_timestamp;
     use std::fs;
 
     #[test]
@@ -93,7 +95,7 @@ mod tests {
         assert_eq!(a.self_link, "http://www.example.com/jira/rest/api/2.0/attachments/10000");
         assert_eq!(a.filename, "picture.jpg");
         assert_eq!(a.author.unwrap().name, "fred");
-        assert_eq!(a.created, "2019-11-05T17:14:26.121+0000");
+        assert_eq!(format_optional_timestamp(&a.created), "2019-11-05T17:14:26.121+0000");
         assert_eq!(a.size, 23123);
         assert_eq!(a.mime_type, "image/jpeg");
         assert_eq!(a.content, "http://www.example.com/jira/attachments/10000");
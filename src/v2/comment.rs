@@ -4,6 +4,7 @@
 // Use
 // ============================================================================
 use crate::v2::{Pagination, User};
+use crate::OptionalTimestamp;
 use crate::{Deserialize, Serialize};
 
 // ============================================================================
@@ -32,13 +33,12 @@ pub struct Comment {
     pub update_author: Option<User>,
 
     /// Date created in format "2020-03-10T16:14:17.856+0000",
-    #[serde(default)]
-    pub created: String,
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub created: OptionalTimestamp,
 
     /// Date last updated in format "2020-03-10T16:14:17.856+0000",
-    #[serde(default)]
-    pub updated: String,
-
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub updated: OptionalTimestamp,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,7 +49,7 @@ pub struct PaginatedComments {
 
     /// Pagination
     #[serde(flatten, default)]
-    pub pagination: Option<Pagination>
+    pub pagination: Option<Pagination>,
 }
 
 // ============================================================================
@@ -67,4 +67,4 @@ impl std::fmt::Display for PaginatedComments {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
     }
-}
\ No newline at end of file
+}
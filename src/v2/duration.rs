@@ -0,0 +1,315 @@
+//! Durations written in JIRA's "2w 3d 4h 30m" notation. How long a day and a
+//! week are depends on the time tracking configuration of the instance, so
+//! parsing and formatting both take a `TimeTrackingConfiguration`.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::Client;
+use crate::Response;
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeTrackingConfiguration {
+    /// Number of hours in a working day, for example: 8
+    #[serde(rename = "workingHoursPerDay", default = "default_hours_per_day")]
+    pub working_hours_per_day: f64,
+
+    /// Number of days in a working week, for example: 5
+    #[serde(rename = "workingDaysPerWeek", default = "default_days_per_week")]
+    pub working_days_per_week: f64,
+
+    /// How durations are shown in the UI, one of "pretty", "days" or "hours"
+    #[serde(rename = "timeFormat", default)]
+    pub time_format: String,
+
+    /// Unit used when none is given, one of "minute", "hour", "day" or "week"
+    #[serde(rename = "defaultUnit", default)]
+    pub default_unit: String,
+}
+
+/// A length of time with second precision
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JiraDuration {
+    seconds: i64,
+}
+
+/// Returned when a string is not in JIRA's duration notation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseDurationError {
+    input: String,
+}
+
+impl TimeTrackingConfiguration {
+    /// Fetches the time tracking settings of the instance. For more info
+    /// consult the api docs:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v2/api-group-time-tracking/#api-rest-api-2-configuration-timetracking-options-get
+    pub fn fetch(c: &Client) -> Response<TimeTrackingConfiguration> {
+        c.get("api/2/configuration/timetracking/options")
+    }
+
+    /// Length of a working day in seconds
+    pub fn seconds_per_day(&self) -> i64 {
+        (self.working_hours_per_day * 3600.0).round() as i64
+    }
+
+    /// Length of a working week in seconds
+    pub fn seconds_per_week(&self) -> i64 {
+        (self.working_days_per_week * self.working_hours_per_day * 3600.0).round() as i64
+    }
+
+    /// Seconds in one of the unit suffixes "w", "d", "h" or "m"
+    fn unit_seconds(&self, unit: char) -> Option<i64> {
+        match unit {
+            'w' => Some(self.seconds_per_week()),
+            'd' => Some(self.seconds_per_day()),
+            'h' => Some(3600),
+            'm' => Some(60),
+            _ => None,
+        }
+    }
+
+    /// The suffix of the default unit, minutes if it is not recognised
+    fn default_suffix(&self) -> char {
+        match self.default_unit.as_str() {
+            "week" => 'w',
+            "day" => 'd',
+            "hour" => 'h',
+            _ => 'm',
+        }
+    }
+}
+
+impl JiraDuration {
+    /// Creates a duration from a number of seconds
+    pub fn from_seconds(seconds: i64) -> Self {
+        JiraDuration { seconds }
+    }
+
+    /// The duration in seconds, as in the `timeSpentSeconds` fields
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Parses a duration such as "2d 4h" or "1.5h". A bare number is taken to
+    /// be in the configured default unit.
+    pub fn parse(s: &str, config: &TimeTrackingConfiguration) -> Result<Self, ParseDurationError> {
+        let err = || ParseDurationError {
+            input: s.to_string(),
+        };
+
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Err(err());
+        }
+
+        if let Ok(n) = trimmed.parse::<f64>() {
+            let unit = config.unit_seconds(config.default_suffix()).unwrap_or(60);
+            return Ok(JiraDuration::from_seconds((n * unit as f64).round() as i64));
+        }
+
+        let mut seconds = 0.0;
+        let mut number = String::new();
+
+        for ch in trimmed.chars() {
+            if ch.is_ascii_digit() || ch == '.' {
+                number.push(ch);
+            } else if ch.is_whitespace() {
+                if !number.is_empty() {
+                    return Err(err());
+                }
+            } else {
+                let unit = config
+                    .unit_seconds(ch.to_ascii_lowercase())
+                    .ok_or_else(err)?;
+                let n: f64 = number.parse().map_err(|_| err())?;
+                seconds += n * unit as f64;
+                number.clear();
+            }
+        }
+
+        if !number.is_empty() {
+            return Err(err());
+        }
+
+        Ok(JiraDuration::from_seconds(seconds.round() as i64))
+    }
+
+    /// Formats the duration the way JIRA does, e.g. "1w 2d 4h 30m". Anything
+    /// below a minute is dropped and an empty duration is written as "0m".
+    pub fn format(&self, config: &TimeTrackingConfiguration) -> String {
+        let mut remaining = self.seconds.abs();
+        let mut parts = Vec::new();
+
+        for unit in &['w', 'd', 'h', 'm'] {
+            let size = config.unit_seconds(*unit).unwrap_or(60);
+            if size <= 0 {
+                continue;
+            }
+
+            let n = remaining / size;
+            remaining %= size;
+
+            if n > 0 {
+                parts.push(format!("{}{}", n, unit));
+            }
+        }
+
+        if parts.is_empty() {
+            return "0m".to_string();
+        }
+
+        let formatted = parts.join(" ");
+        if self.seconds < 0 {
+            format!("-{}", formatted)
+        } else {
+            formatted
+        }
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl Default for TimeTrackingConfiguration {
+    /// JIRA's out of the box settings, 8 hour days and 5 day weeks
+    fn default() -> Self {
+        TimeTrackingConfiguration {
+            working_hours_per_day: default_hours_per_day(),
+            working_days_per_week: default_days_per_week(),
+            time_format: "pretty".to_string(),
+            default_unit: "minute".to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for TimeTrackingConfiguration {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+impl std::str::FromStr for JiraDuration {
+    type Err = ParseDurationError;
+
+    /// Parses using JIRA's default 8 hour days and 5 day weeks, use
+    /// `JiraDuration::parse` for instances configured differently.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        JiraDuration::parse(s, &TimeTrackingConfiguration::default())
+    }
+}
+
+impl From<JiraDuration> for std::time::Duration {
+    fn from(d: JiraDuration) -> Self {
+        std::time::Duration::from_secs(d.seconds.max(0) as u64)
+    }
+}
+
+impl From<std::time::Duration> for JiraDuration {
+    fn from(d: std::time::Duration) -> Self {
+        JiraDuration::from_seconds(d.as_secs() as i64)
+    }
+}
+
+impl std::fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "invalid duration: {:?}", self.input)
+    }
+}
+
+impl std::error::Error for ParseDurationError {}
+
+// ============================================================================
+// Private
+// ============================================================================
+fn default_hours_per_day() -> f64 {
+    8.0
+}
+
+fn default_days_per_week() -> f64 {
+    5.0
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(hours: f64, days: f64) -> TimeTrackingConfiguration {
+        TimeTrackingConfiguration {
+            working_hours_per_day: hours,
+            working_days_per_week: days,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_default_configuration() {
+        let d: JiraDuration = "1w 2d 4h 30m".parse().unwrap();
+
+        assert_eq!(
+            d.seconds(),
+            5 * 8 * 3600 + 2 * 8 * 3600 + 4 * 3600 + 30 * 60
+        );
+        assert_eq!("2d4h".parse::<JiraDuration>().unwrap().seconds(), 20 * 3600);
+        assert_eq!("1.5h".parse::<JiraDuration>().unwrap().seconds(), 5400);
+        assert_eq!("90".parse::<JiraDuration>().unwrap().seconds(), 5400);
+    }
+
+    #[test]
+    fn test_parse_custom_configuration() {
+        let c = config(7.5, 4.0);
+
+        assert_eq!(JiraDuration::parse("1d", &c).unwrap().seconds(), 27000);
+        assert_eq!(JiraDuration::parse("1w", &c).unwrap().seconds(), 4 * 27000);
+    }
+
+    #[test]
+    fn test_parse_rejects_garbage() {
+        assert!("".parse::<JiraDuration>().is_err());
+        assert!("4x".parse::<JiraDuration>().is_err());
+        assert!("4h 3".parse::<JiraDuration>().is_err());
+        assert!("4 h".parse::<JiraDuration>().is_err());
+        assert!("h".parse::<JiraDuration>().is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        let c = TimeTrackingConfiguration::default();
+
+        assert_eq!(JiraDuration::from_seconds(0).format(&c), "0m");
+        assert_eq!(JiraDuration::from_seconds(72000).format(&c), "2d 4h");
+        assert_eq!(JiraDuration::from_seconds(183600).format(&c), "1w 1d 3h");
+        assert_eq!(
+            JiraDuration::from_seconds(27000).format(&config(7.5, 5.0)),
+            "1d"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let c = config(6.0, 4.0);
+
+        for s in &["3w 2d 5h 15m", "1d", "45m", "2w 1h"] {
+            assert_eq!(&JiraDuration::parse(s, &c).unwrap().format(&c), s);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_configuration() {
+        let c: TimeTrackingConfiguration = serde_json::from_str(
+            r#"{"workingHoursPerDay": 7.5, "workingDaysPerWeek": 5.0,
+                "timeFormat": "pretty", "defaultUnit": "hour"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(c.seconds_per_day(), 27000);
+        assert_eq!(JiraDuration::parse("2", &c).unwrap().seconds(), 7200);
+    }
+}
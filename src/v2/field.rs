@@ -0,0 +1,124 @@
+//! Represents the system and custom fields issues can have
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::Client;
+use crate::Response;
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Field {
+    /// ID of the field, such as `summary` or `customfield_10402`
+    #[serde(default)]
+    pub id: String,
+
+    /// Key of the field, the same as the id on most instances
+    #[serde(default)]
+    pub key: String,
+
+    /// Name of the field as shown to users
+    #[serde(default)]
+    pub name: String,
+
+    /// Whether the field is a custom field
+    #[serde(default)]
+    pub custom: bool,
+
+    /// Whether the field can be used to order search results
+    #[serde(default)]
+    pub orderable: bool,
+
+    /// Whether the field can be shown as a column in issue navigator
+    #[serde(default)]
+    pub navigable: bool,
+
+    /// Whether the field can be searched for
+    #[serde(default)]
+    pub searchable: bool,
+
+    /// The names the field can be referred to by in JQL
+    #[serde(rename = "clauseNames", default)]
+    pub clause_names: Vec<String>,
+
+    /// The type of data the field holds, missing for some system fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<FieldSchema>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldSchema {
+    /// Type of the value, such as `string`, `number` or `array`
+    #[serde(rename = "type", default)]
+    pub field_type: String,
+
+    /// Type of the items when the value is an array
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<String>,
+
+    /// The system field this is, for system fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    /// The plugin type of a custom field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom: Option<String>,
+
+    /// The numeric id of a custom field
+    #[serde(rename = "customId", default, skip_serializing_if = "Option::is_none")]
+    pub custom_id: Option<u64>,
+}
+
+impl Field {
+    /// Fetches every system and custom field. For more info consult the api
+    /// docs:
+    /// https://docs.atlassian.com/software/jira/docs/api/REST/8.2.6/#api/2/field-getFields
+    pub fn all(c: &Client) -> Response<Vec<Field>> {
+        c.get("api/2/field")
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for Field {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_results() {
+        let results =
+            fs::read_to_string("tests/assets/v2/field.json").expect("Unable to read in JSON file");
+        let fields: Vec<Field> = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[2].id, "customfield_10402");
+        assert_eq!(fields[2].name, "Story Points");
+        assert!(fields[2].custom);
+        assert_eq!(fields[2].clause_names, vec!["cf[10402]", "Story Points"]);
+
+        let schema = fields[2].schema.as_ref().unwrap();
+        assert_eq!(schema.field_type, "number");
+        assert_eq!(schema.custom_id, Some(10402));
+
+        assert_eq!(
+            fields[1].schema.as_ref().unwrap().items.as_deref(),
+            Some("version")
+        );
+        assert!(fields[3].schema.is_none());
+    }
+}
@@ -4,6 +4,7 @@
 // Use
 // ============================================================================
 use crate::v2::User;
+use crate::OptionalTimestamp;
 use crate::{Deserialize, Serialize};
 
 // ============================================================================
@@ -11,12 +12,19 @@ use crate::{Deserialize, Serialize};
 // ============================================================================
 #[derive(Deserialize, Serialize, Debug)]
 pub struct History {
-    /// The author of a change
-    pub author: User,
+    /// ID of the change
+    #[serde(default)]
+    pub id: String,
 
-    /// When the change was made
+    /// The author of a change, webhooks leave it out and send the user that
+    /// caused the event instead
     #[serde(default)]
-    pub created: String,
+    pub author: User,
+
+    /// When the change was made in format: "2020-03-10T16:27:20.772+0000",
+    /// webhooks leave it out
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub created: OptionalTimestamp,
 
     /// A list of items changed including previous and new values
     #[serde(default)]
@@ -37,7 +45,7 @@ pub struct HistoryItem {
     pub from: ::serde_json::Value,
 
     /// What the field was changed from in string form.
-    #[serde(rename = "fromString", default)]
+    #[serde(rename = "fromString", default, deserialize_with = "crate::de::null_as_default")]
     pub from_string: String,
 
     /// What the field was changed to in its object form.
@@ -48,7 +56,7 @@ pub struct HistoryItem {
     pub to: ::serde_json::Value,
 
     /// What the field was changed to in string form.
-    #[serde(rename = "toString", default)]
+    #[serde(rename = "toString", default, deserialize_with = "crate::de::null_as_default")]
     pub to_string: String,
 }
 
@@ -14,6 +14,7 @@ use crate::v2::{Progress, Project, Resolution, Status, TimeTracking, User};
 use crate::v2::{Version, Vote, Watches};
 use crate::Client;
 use crate::Response;
+use crate::{Date, OptionalTimestamp, Timestamp};
 use crate::{Deserialize, Serialize};
 use crate::QueryOptions;
 use std::collections::BTreeMap;
@@ -141,7 +142,7 @@ pub struct IssueFields {
     pub time_original_estimate: Option<i64>,
 
     /// Description of the issue
-    #[serde(default)]
+    #[serde(default, deserialize_with = "crate::de::null_as_default")]
     pub description: String,
 
     /// Project issue is part of
@@ -174,8 +175,12 @@ pub struct IssueFields {
 
     /// Date the issue was resolved (put into a resolution status)
     /// in the format: "2020-03-09T20:40:15.922+0000"
-    #[serde(rename = "resolutiondate", default)]
-    pub resolution_date: Option<String>,
+    #[serde(
+        rename = "resolutiondate",
+        default,
+        with = "crate::datetime::option_timestamp"
+    )]
+    pub resolution_date: Option<Timestamp>,
 
     /// Work ratio
     #[serde(rename = "workratio", default)]
@@ -187,8 +192,12 @@ pub struct IssueFields {
 
     /// Date the issue was last viewed in the format:
     /// "2020-03-09T20:40:15.922+0000"
-    #[serde(rename = "lastViewed", default)]
-    pub last_viewed: Option<String>,
+    #[serde(
+        rename = "lastViewed",
+        default,
+        with = "crate::datetime::option_timestamp"
+    )]
+    pub last_viewed: Option<Timestamp>,
 
     /// Watcher details (how many people are watching this issue etc.)
     #[serde(default)]
@@ -203,8 +212,8 @@ pub struct IssueFields {
     pub subtasks: Vec<Issue>,
 
     /// Date the issue was created in format: "2020-03-08T14:49:58.599+0000"
-    #[serde(default)]
-    pub created: String,
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub created: OptionalTimestamp,
 
     /// User who reported the issue
     #[serde(default)]
@@ -238,9 +247,9 @@ pub struct IssueFields {
     #[serde(default)]
     pub versions: Vec<Version>,
 
-    /// Due date of the issue
-    #[serde(rename = "duedate", default)]
-    pub due_date: Option<String>,
+    /// Due date of the issue in the format: "2020-03-20"
+    #[serde(rename = "duedate", default, with = "crate::datetime::option_date")]
+    pub due_date: Option<Date>,
 
     /// Progress on an issue
     #[serde(default)]
@@ -266,10 +275,10 @@ pub struct IssueFields {
     #[serde(default)]
     pub assignee: Option<User>,
 
-    /// Date time the issue was last updated in the format: 
+    /// Date time the issue was last updated in the format:
     /// "2020-03-10T16:27:20.772+0000"
-    #[serde(default)]
-    pub updated: String,
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub updated: OptionalTimestamp,
 
     /// Status of the issue
     #[serde(default)]
@@ -277,7 +286,7 @@ pub struct IssueFields {
 
     /// Flatten
     #[serde(default, flatten)]
-    pub others: BTreeMap<String, ::serde_json::Value>
+    pub others: BTreeMap<String, ::serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -308,6 +317,17 @@ pub struct Issue {
     pub changelog: Option<Changelog>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaginatedIssues {
+    /// A list of issues
+    #[serde(default)]
+    pub issues: Vec<Issue>,
+
+    /// Pagination fields
+    #[serde(flatten, default)]
+    pub pagination: Pagination,
+}
+
 impl Issue {
     /// Fetches a paginated list of issues given an issue key (XXX-123). For more
     /// info consult the api docs:
@@ -327,9 +347,18 @@ impl Issue {
         let mut c = c.clone();
         let mut query = Client::unpack_options(vec![&page.unwrap_or_default()]);
 
-        query.insert("fields".to_string(), field_opts.unwrap_or_default().to_string());
-        query.insert("properties".to_string(), property_opts.unwrap_or_default().to_string());
-        query.insert("updateHistory".to_string(), update_history.unwrap_or_default().to_string());
+        query.insert(
+            "fields".to_string(),
+            field_opts.unwrap_or_default().to_string(),
+        );
+        query.insert(
+            "properties".to_string(),
+            property_opts.unwrap_or_default().to_string(),
+        );
+        query.insert(
+            "updateHistory".to_string(),
+            update_history.unwrap_or_default().to_string(),
+        );
         query.extend(expand_to_hashmap(expand_opts.unwrap_or_default()));
         c = c.add_query(query);
 
@@ -349,6 +378,13 @@ impl std::fmt::Display for Issue {
     }
 }
 
+impl std::fmt::Display for PaginatedIssues {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
 impl std::fmt::Display for IssueFields {
     // This trait requires fmt with this signature
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
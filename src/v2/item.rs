@@ -9,7 +9,7 @@ use crate::{Deserialize, Serialize};
 // ============================================================================
 // Public Structures
 // ============================================================================
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Item {
     /// Number of items in the list
     #[serde(default)]
@@ -6,6 +6,8 @@ pub use crate::v2::attachment::*;
 pub use crate::v2::changelog::*;
 pub use crate::v2::comment::*;
 pub use crate::v2::component::*;
+pub use crate::v2::duration::*;
+pub use crate::v2::field::*;
 pub use crate::v2::group::*;
 pub use crate::v2::history::*;
 pub use crate::v2::issue::*;
@@ -19,6 +21,7 @@ pub use crate::v2::priority::*;
 pub use crate::v2::progress::*;
 pub use crate::v2::project::*;
 pub use crate::v2::resolution::*;
+pub use crate::v2::server_info::*;
 pub use crate::v2::status::*;
 pub use crate::v2::status_category::*;
 pub use crate::v2::time_tracking::*;
@@ -46,6 +49,8 @@ mod watches;
 pub mod application_role;
 pub mod attachment;
 pub mod component;
+pub mod duration;
+pub mod field;
 pub mod group;
 pub mod issue;
 pub mod issue_link;
@@ -56,6 +61,7 @@ pub mod permission;
 pub mod priority;
 pub mod project;
 pub mod resolution;
+pub mod server_info;
 pub mod status;
 pub mod status_category;
 pub mod user;
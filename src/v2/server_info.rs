@@ -0,0 +1,107 @@
+//! General information about the JIRA instance, available without
+//! authentication on both Cloud and Server
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::Client;
+use crate::Response;
+use crate::Timestamp;
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerInfo {
+    /// Base URL of the instance
+    #[serde(rename = "baseUrl", default)]
+    pub base_url: String,
+
+    /// Version in the format "8.5.4"
+    #[serde(default)]
+    pub version: String,
+
+    /// Major, minor and patch version numbers
+    #[serde(rename = "versionNumbers", default)]
+    pub version_numbers: Vec<u32>,
+
+    /// Either "Cloud" or "Server", recent Data Center releases report
+    /// "DataCenter"
+    #[serde(rename = "deploymentType", default)]
+    pub deployment_type: String,
+
+    /// Build number of the release
+    #[serde(rename = "buildNumber", default)]
+    pub build_number: i64,
+
+    /// Date of the build in the format: "2020-02-12T00:00:00.000+0000"
+    #[serde(
+        rename = "buildDate",
+        default,
+        with = "crate::datetime::option_timestamp"
+    )]
+    pub build_date: Option<Timestamp>,
+
+    /// Current time on the server in the format:
+    /// "2020-03-10T16:27:20.772+0000"
+    #[serde(
+        rename = "serverTime",
+        default,
+        with = "crate::datetime::option_timestamp"
+    )]
+    pub server_time: Option<Timestamp>,
+
+    /// Revision of the build
+    #[serde(rename = "scmInfo", default)]
+    pub scm_info: String,
+
+    /// Title of the instance
+    #[serde(rename = "serverTitle", default)]
+    pub server_title: String,
+}
+
+impl ServerInfo {
+    /// Fetches information about the instance, for more information see:
+    /// https://docs.atlassian.com/software/jira/docs/api/REST/8.5.4/#api/2/serverInfo-getServerInfo
+    pub fn get(c: &Client) -> Response<ServerInfo> {
+        c.get("api/2/serverInfo")
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for ServerInfo {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_timestamp;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_results() {
+        let results = fs::read_to_string("tests/assets/v2/server_info.json")
+            .expect("Unable to read in JSON file");
+        let info: ServerInfo = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(info.version, "8.5.4");
+        assert_eq!(info.version_numbers, vec![8, 5, 4]);
+        assert_eq!(info.deployment_type, "Server");
+        assert_eq!(info.build_number, 805004);
+        assert_eq!(
+            info.build_date.as_ref().map(format_timestamp).as_deref(),
+            Some("2020-02-12T00:00:00.000+0000")
+        );
+        assert!(info.server_time.is_some());
+    }
+}
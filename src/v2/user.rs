@@ -3,8 +3,10 @@
 // ============================================================================
 // Use
 // ============================================================================
+use crate::oauth1::encode;
 use crate::v2::{ApplicationRole, Group, Item, Pagination};
 use crate::Client;
+use crate::Feature;
 use crate::Options;
 use crate::Response;
 use crate::{Deserialize, Serialize};
@@ -72,8 +74,17 @@ impl Default for UserOptions {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct User {
+    /// The account id of the user, on Cloud this replaces `name` and `key`
+    /// which are no longer returned
+    #[serde(rename = "accountId", default)]
+    pub account_id: String,
+
+    /// The type of account on Cloud, one of "atlassian", "app" or "customer"
+    #[serde(rename = "accountType", default)]
+    pub account_type: String,
+
     /// Returns true if the user is active in Jira
     #[serde(default)]
     pub active: bool,
@@ -115,7 +126,37 @@ pub struct User {
     pub application_roles: Option<Item>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginatedUsers {
+    /// A list of users
+    #[serde(rename = "values", default)]
+    pub users: Vec<User>,
+
+    /// Pagination fields
+    #[serde(flatten, default)]
+    pub pagination: Pagination,
+}
+
+/// The account id a username or key maps to on Cloud
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserMigration {
+    /// The username that was looked up
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// The key that was looked up
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// The account id of the user
+    #[serde(rename = "accountId", default)]
+    pub account_id: String,
+}
+
 impl User {
+    /// Searches for users by username, this is not supported by Cloud where
+    /// `search_by_query` should be used instead. When the instance is known
+    /// to be Cloud the search is sent as a free text query.
     pub fn search<S>(
         c: &Client,
         search: S,
@@ -128,16 +169,37 @@ impl User {
         let mut query =
             Client::unpack_options(vec![&opts.unwrap_or_default(), &page.unwrap_or_default()]);
 
-        query.insert("username".to_string(), search.into());
+        let param = search_param(c, "username");
+        query.insert(param.to_string(), encode(&search.into()));
 
         c.clone().add_query(query).get("api/2/user/search")
     }
 
+    /// Searches for users whose display name or email address match the
+    /// query, for more information see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v2/api-group-user-search/#api-rest-api-2-user-search-get
+    /// When the instance is known to be Server or Data Center the query is
+    /// sent as a username, which there matches display names and email
+    /// addresses as well.
+    pub fn search_by_query<S>(c: &Client, query: S, page: Option<Pagination>) -> Response<Vec<User>>
+    where
+        S: Into<String>,
+    {
+        let mut query_map = Client::unpack_options(vec![&page.unwrap_or_default()]);
+
+        let param = search_param(c, "query");
+        query_map.insert(param.to_string(), encode(&query.into()));
+
+        c.clone().add_query(query_map).get("api/2/user/search")
+    }
+
     /// Fetches a user by username
     pub fn from_username<U>(c: &Client, username: U, expand: Vec<UserExpand>) -> Response<User>
     where
         U: Into<String>,
     {
+        c.ensure(Feature::Usernames)?;
+
         let mut query: HashMap<String, String> = HashMap::new();
 
         query.insert("username".to_string(), username.into());
@@ -151,6 +213,8 @@ impl User {
     where
         K: Into<String>,
     {
+        c.ensure(Feature::Usernames)?;
+
         let mut query: HashMap<String, String> = HashMap::new();
 
         query.insert("key".to_string(), key.into());
@@ -159,6 +223,44 @@ impl User {
         c.clone().add_query(query).get("api/2/user")
     }
 
+    /// Fetches a user by account id, this is the only lookup supported by
+    /// Cloud
+    pub fn from_account_id<A>(c: &Client, account_id: A, expand: Vec<UserExpand>) -> Response<User>
+    where
+        A: Into<String>,
+    {
+        c.ensure(Feature::AccountIds)?;
+
+        let mut query: HashMap<String, String> = HashMap::new();
+
+        query.insert("accountId".to_string(), encode(&account_id.into()));
+        query.extend(expand_to_hashmap(expand));
+
+        c.clone().add_query(query).get("api/2/user")
+    }
+
+    /// Fetches a page of users given a list of account ids, for more
+    /// information see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v2/api-group-users/#api-rest-api-2-user-bulk-get
+    pub fn from_account_ids<A>(
+        c: &Client,
+        account_ids: Vec<A>,
+        page: Option<Pagination>,
+    ) -> Response<PaginatedUsers>
+    where
+        A: Into<String>,
+    {
+        c.ensure(Feature::AccountIds)?;
+
+        let query = Client::unpack_options(vec![&page.unwrap_or_default()]);
+        let url = format!(
+            "api/2/user/bulk?{}",
+            repeated_query("accountId", account_ids)
+        );
+
+        c.clone().add_query(query).get(&url)
+    }
+
     pub fn groups(&self) -> Vec<Group> {
         if let Some(i) = &self.groups {
             serde_json::value::from_value(i.items.clone()).unwrap()
@@ -176,6 +278,50 @@ impl User {
     }
 }
 
+impl UserMigration {
+    /// Looks up the account ids for a list of usernames, for more information
+    /// see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v2/api-group-users/#api-rest-api-2-user-bulk-migration-get
+    pub fn from_usernames<U>(c: &Client, usernames: Vec<U>) -> Response<Vec<UserMigration>>
+    where
+        U: Into<String>,
+    {
+        c.ensure(Feature::AccountIds)?;
+
+        let url = format!(
+            "api/2/user/bulk/migration?{}",
+            repeated_query("username", usernames)
+        );
+
+        c.get(&url)
+    }
+
+    /// Looks up the account ids for a list of user keys
+    pub fn from_keys<K>(c: &Client, keys: Vec<K>) -> Response<Vec<UserMigration>>
+    where
+        K: Into<String>,
+    {
+        c.ensure(Feature::AccountIds)?;
+
+        let url = format!("api/2/user/bulk/migration?{}", repeated_query("key", keys));
+
+        c.get(&url)
+    }
+
+    /// Maps each username or key that was looked up to its account id
+    pub fn to_map(migrations: &[UserMigration]) -> HashMap<String, String> {
+        migrations
+            .iter()
+            .filter_map(|m| {
+                m.username
+                    .as_ref()
+                    .or(m.key.as_ref())
+                    .map(|id| (id.clone(), m.account_id.clone()))
+            })
+            .collect()
+    }
+}
+
 // ============================================================================
 // Trait Implementations
 // ============================================================================
@@ -186,9 +332,34 @@ impl std::fmt::Display for User {
     }
 }
 
+impl std::fmt::Display for PaginatedUsers {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+impl std::fmt::Display for UserMigration {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
 // ============================================================================
 // Private
 // ============================================================================
+/// The parameter user search takes on the instance, Cloud removed `username`
+/// in favour of `query` which Server does not have. Like `Client::ensure`
+/// the caller's choice is kept until the deployment is known.
+fn search_param<'a>(c: &Client, requested: &'a str) -> &'a str {
+    match c.capabilities() {
+        Some(capabilities) if capabilities.is_cloud() => "query",
+        Some(_) => "username",
+        None => requested,
+    }
+}
+
 fn expand_to_hashmap(e: Vec<UserExpand>) -> HashMap<String, String> {
     let mut res: HashMap<String, String> = HashMap::new();
     let mut value = e.iter().fold(String::from(""), |acc, e| {
@@ -201,6 +372,20 @@ fn expand_to_hashmap(e: Vec<UserExpand>) -> HashMap<String, String> {
     res
 }
 
+/// Bulk endpoints take a parameter once per value which a query map can't
+/// represent, e.g. "accountId=a&accountId=b". Values are percent encoded as
+/// usernames are often email addresses.
+fn repeated_query<V>(name: &str, values: Vec<V>) -> String
+where
+    V: Into<String>,
+{
+    values
+        .into_iter()
+        .map(|v| format!("{}={}", name, encode(&v.into())))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -218,6 +403,31 @@ mod tests {
         assert!(h.get("expand").unwrap().contains("applicationRoles"));
     }
 
+    #[test]
+    fn test_repeated_query() {
+        assert_eq!(
+            repeated_query("accountId", vec!["a", "b"]),
+            "accountId=a&accountId=b"
+        );
+        assert_eq!(
+            repeated_query("username", vec!["a+b@example.com", "c&d #1"]),
+            "username=a%2Bb%40example.com&username=c%26d%20%231"
+        );
+    }
+
+    #[test]
+    fn test_migration_map() {
+        let migrations: Vec<UserMigration> = serde_json::from_str(
+            r#"[{"username": "fred", "accountId": "5b10a2844c20165700ede21g"},
+                {"key": "jira-user-1", "accountId": "5b10ac8d82e05b22cc7d4ef5"}]"#,
+        )
+        .unwrap();
+        let map = UserMigration::to_map(&migrations);
+
+        assert_eq!(map.get("fred").unwrap(), "5b10a2844c20165700ede21g");
+        assert_eq!(map.get("jira-user-1").unwrap(), "5b10ac8d82e05b22cc7d4ef5");
+    }
+
     #[test]
     fn test_deserialize_results() {
         let results =
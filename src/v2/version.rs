@@ -4,6 +4,7 @@
 // Use
 // ============================================================================
 use crate::Client;
+use crate::OptionalDate;
 use crate::Response;
 use crate::{Deserialize, Serialize};
 
@@ -30,8 +31,13 @@ pub struct Version {
 
     /// Release date is the ACTUAL release date your software was released.
     /// You should only really set this field when the release is complete.
-    #[serde(rename = "releaseDate", default)]
-    pub release_date: String,
+    /// In the format: "2010-07-06"
+    #[serde(
+        rename = "releaseDate",
+        default,
+        with = "crate::datetime::optional_date"
+    )]
+    pub release_date: OptionalDate,
 
     /// User release date is the proposed date of release given on version
     /// creation. This field and release_date will hold the same value once a
@@ -66,7 +72,7 @@ impl Default for Version {
             self_link: String::new(),
             description: String::new(),
             name: String::new(),
-            release_date: String::new(),
+            release_date: OptionalDate::default(),
             user_release_date: String::new(),
             archived: false,
             released: false,
@@ -109,6 +115,7 @@ impl Version {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::format_optional_date;
     use std::fs;
 
     #[test]
@@ -119,7 +126,7 @@ mod tests {
         assert_eq!(version.project_id, 10000);
         assert_eq!(version.user_release_date, "6/Jul/2010");
         assert!(version.overdue);
-        assert_eq!(version.release_date, "2010-07-06");
+        assert_eq!(format_optional_date(&version.release_date), "2010-07-06");
         assert!(version.released);
         assert!(!version.archived);
         assert_eq!(version.name, "New Version 1");
@@ -7,6 +7,7 @@ use crate::v2::{Pagination, User};
 use crate::Client;
 use crate::Response;
 use crate::{Deserialize, Serialize};
+use crate::{OptionalTimestamp, Timestamp};
 
 // ============================================================================
 // Public Structures
@@ -30,16 +31,16 @@ pub struct Worklog {
     pub comment: String,
 
     /// Date the worklog was created in format: "2020-03-08T16:40:18.010+0000"
-    #[serde(default)]
-    pub created: String,
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub created: OptionalTimestamp,
 
     /// Date the worklog was updated in format: "2020-03-08T16:40:18.010+0000"
-    #[serde(default)]
-    pub updated: String,
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub updated: OptionalTimestamp,
 
     /// Date the worklog was started in format: "2020-03-08T16:40:18.010+0000"
-    #[serde(default)]
-    pub started: Option<String>,
+    #[serde(default, with = "crate::datetime::option_timestamp")]
+    pub started: Option<Timestamp>,
 
     /// Time spent on the issue associated with this worklog in format: "2d 4h"
     #[serde(rename = "timeSpent", default)]
@@ -104,6 +105,7 @@ impl std::fmt::Display for Worklog {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{format_optional_timestamp, format_timestamp};
     use std::fs;
 
     #[test]
@@ -116,9 +118,9 @@ mod tests {
         assert_eq!(w.author.unwrap().name, "xxx");
         assert_eq!(w.update_author.unwrap().name, "xxx");
         assert_eq!(w.comment, "");
-        assert_eq!(w.created, "2020-03-08T16:40:18.010+0000");
-        assert_eq!(w.updated, "2020-03-08T16:40:18.010+0000");
-        assert_eq!(w.started.unwrap(), "2020-03-08T16:40:00.000+0000");
+        assert_eq!(format_optional_timestamp(&w.created), "2020-03-08T16:40:18.010+0000");
+        assert_eq!(format_optional_timestamp(&w.updated), "2020-03-08T16:40:18.010+0000");
+        assert_eq!(format_timestamp(&w.started.unwrap()), "2020-03-08T16:40:00.000+0000");
         assert_eq!(w.time_spent.unwrap(), "2d 4h");
         assert_eq!(w.time_spent_seconds.unwrap(), 72000);
         assert_eq!(w.id, "10000");
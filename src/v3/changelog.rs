@@ -0,0 +1,196 @@
+//! A representation of JIRA's changelog format along with the Cloud only bulk
+//! changelog endpoint
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::v2::{HistoryItem, Pagination};
+use crate::v3::{Page, User};
+use crate::Client;
+use crate::Feature;
+use crate::Response;
+use crate::OptionalTimestamp;
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct Changelog {
+    /// A list of changes
+    #[serde(default)]
+    pub histories: Vec<History>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct History {
+    /// ID of the change
+    #[serde(default)]
+    pub id: String,
+
+    /// The author of a change
+    #[serde(default)]
+    pub author: Option<User>,
+
+    /// When the change was made in format: "2020-03-10T16:27:20.772+0000"
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub created: OptionalTimestamp,
+
+    /// A list of items changed including previous and new values
+    #[serde(default)]
+    pub items: Vec<HistoryItem>,
+}
+
+/// Request body for `Changelog::bulk_fetch`
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct BulkChangelogRequest {
+    /// Issue ids or keys to fetch changelogs for, up to 1000
+    #[serde(rename = "issueIdsOrKeys")]
+    pub issue_ids_or_keys: Vec<String>,
+
+    /// Only include changes to these fields
+    #[serde(rename = "fieldIds", skip_serializing_if = "Vec::is_empty", default)]
+    pub field_ids: Vec<String>,
+
+    /// Maximum number of changelogs in a page
+    #[serde(
+        rename = "maxResults",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub max_results: Option<i64>,
+
+    /// Token returned by the previous page
+    #[serde(
+        rename = "nextPageToken",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct IssueChangelog {
+    /// ID of the issue the changes belong to
+    #[serde(rename = "issueId", default)]
+    pub issue_id: String,
+
+    /// Changes made to the issue
+    #[serde(rename = "changeHistories", default)]
+    pub change_histories: Vec<History>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BulkChangelog {
+    /// Changelogs grouped by issue
+    #[serde(rename = "issueChangeLogs", default)]
+    pub issue_changelogs: Vec<IssueChangelog>,
+
+    /// Token to fetch the next page with, missing on the last page
+    #[serde(rename = "nextPageToken", default)]
+    pub next_page_token: Option<String>,
+}
+
+impl Changelog {
+    /// Fetches a page of changes made to an issue, for more information see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v3/api-group-issues/#api-rest-api-3-issue-issueidorkey-changelog-get
+    pub fn from_issue<I>(c: &Client, issue: I, page: Option<Pagination>) -> Response<Page<History>>
+    where
+        I: Into<String>,
+    {
+        c.ensure(Feature::RestV3)?;
+
+        let url = format!("api/3/issue/{}/changelog", issue.into());
+        let query = Client::unpack_options(vec![&page.unwrap_or_default()]);
+
+        c.clone().add_query(query).get(&url)
+    }
+
+    /// Fetches changelogs for many issues at once, for more information see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v3/api-group-issues/#api-rest-api-3-changelog-bulkfetch-post
+    pub fn bulk_fetch(c: &Client, request: &BulkChangelogRequest) -> Response<BulkChangelog> {
+        c.ensure(Feature::RestV3)?;
+
+        c.post("api/3/changelog/bulkfetch", request)
+    }
+}
+
+impl BulkChangelogRequest {
+    pub fn new<I, S>(issues: I) -> BulkChangelogRequest
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        BulkChangelogRequest {
+            issue_ids_or_keys: issues.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl BulkChangelog {
+    /// The request for the next page, if there is one
+    pub fn next(&self, request: &BulkChangelogRequest) -> Option<BulkChangelogRequest> {
+        self.next_page_token
+            .as_ref()
+            .map(|token| BulkChangelogRequest {
+                issue_ids_or_keys: request.issue_ids_or_keys.clone(),
+                field_ids: request.field_ids.clone(),
+                max_results: request.max_results,
+                next_page_token: Some(token.clone()),
+            })
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for Changelog {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+impl std::fmt::Display for History {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+impl std::fmt::Display for BulkChangelog {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_bulk_results() {
+        let results = fs::read_to_string("tests/assets/v3/changelog_bulk.json")
+            .expect("Unable to read in JSON file");
+        let bulk: BulkChangelog = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(bulk.issue_changelogs.len(), 1);
+        assert_eq!(bulk.issue_changelogs[0].issue_id, "10100");
+
+        let history = &bulk.issue_changelogs[0].change_histories[0];
+        assert_eq!(history.author.as_ref().unwrap().display_name, "Mia Krystof");
+        assert_eq!(history.items[0].field, "status");
+        assert_eq!(history.items[0].to_string, "In Progress");
+
+        let request = BulkChangelogRequest::new(vec!["EX-1"]);
+        let next = bulk.next(&request).unwrap();
+        assert_eq!(next.issue_ids_or_keys, vec!["EX-1"]);
+        assert_eq!(next.next_page_token.unwrap(), "UxAQBFRF");
+    }
+}
@@ -0,0 +1,117 @@
+//! A structure representing a comment on a ticket, the body is an ADF
+//! document rather than wiki markup
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::adf::Document;
+use crate::v2::Pagination;
+use crate::v3::User;
+use crate::Client;
+use crate::Feature;
+use crate::Response;
+use crate::OptionalTimestamp;
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Comment {
+    /// REST API link to comment
+    #[serde(rename = "self", default)]
+    pub self_link: String,
+
+    /// ID of comment
+    #[serde(default)]
+    pub id: String,
+
+    /// Author of comment
+    #[serde(default)]
+    pub author: Option<User>,
+
+    /// Body of comment
+    #[serde(default)]
+    pub body: Option<Document>,
+
+    /// Author of last update to the comment
+    #[serde(rename = "updateAuthor", default)]
+    pub update_author: Option<User>,
+
+    /// Date created in format "2020-03-10T16:14:17.856+0000",
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub created: OptionalTimestamp,
+
+    /// Date last updated in format "2020-03-10T16:14:17.856+0000",
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub updated: OptionalTimestamp,
+
+    /// Is the comment visible to customers in Jira Service Management
+    #[serde(rename = "jsdPublic", default)]
+    pub jsd_public: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginatedComments {
+    /// A list of comments
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+
+    /// Pagination
+    #[serde(flatten, default)]
+    pub pagination: Option<Pagination>,
+}
+
+impl Comment {
+    /// Fetches a page of comments on an issue, for more information see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v3/api-group-issue-comments/#api-rest-api-3-issue-issueidorkey-comment-get
+    pub fn list<I>(c: &Client, issue: I, page: Option<Pagination>) -> Response<PaginatedComments>
+    where
+        I: Into<String>,
+    {
+        c.ensure(Feature::RestV3)?;
+
+        let url = format!("api/3/issue/{}/comment", issue.into());
+        let query = Client::unpack_options(vec![&page.unwrap_or_default()]);
+
+        c.clone().add_query(query).get(&url)
+    }
+
+    /// Adds a comment to an issue, use `Document::from_markdown` to write the
+    /// body in Markdown
+    pub fn add<I>(c: &Client, issue: I, body: Document) -> Response<Comment>
+    where
+        I: Into<String>,
+    {
+        c.ensure(Feature::RestV3)?;
+
+        let url = format!("api/3/issue/{}/comment", issue.into());
+
+        c.post(&url, CommentBody { body })
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for Comment {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+impl std::fmt::Display for PaginatedComments {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+#[derive(Serialize)]
+struct CommentBody {
+    body: Document,
+}
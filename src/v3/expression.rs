@@ -0,0 +1,147 @@
+//! Evaluates Jira expressions, a Cloud only API for querying and computing
+//! values over issues, projects, sprints and boards in a single request.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::Client;
+use crate::Feature;
+use crate::Response;
+use crate::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// An issue or project referenced from an expression context, either by id
+/// or key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextReference {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub id: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub key: Option<String>,
+}
+
+/// The context variables an expression is evaluated with, e.g. setting
+/// `issue` makes the `issue` variable available to the expression.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExpressionContext {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub issue: Option<ContextReference>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub project: Option<ContextReference>,
+
+    /// ID of a sprint
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub sprint: Option<i64>,
+
+    /// ID of a board
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub board: Option<i64>,
+
+    /// Custom context variables
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub custom: Option<Value>,
+
+    /// Any other context variables, such as a JQL query for `issues`
+    #[serde(flatten, default)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpressionResult {
+    /// The value the expression evaluated to
+    #[serde(default)]
+    pub value: Value,
+
+    /// Complexity of the expression and, when a JQL context was used, the
+    /// pagination of the issues it was evaluated against
+    #[serde(default)]
+    pub meta: Option<Value>,
+}
+
+impl ContextReference {
+    pub fn id(id: i64) -> ContextReference {
+        ContextReference {
+            id: Some(id),
+            key: None,
+        }
+    }
+
+    pub fn key<K>(key: K) -> ContextReference
+    where
+        K: Into<String>,
+    {
+        ContextReference {
+            id: None,
+            key: Some(key.into()),
+        }
+    }
+}
+
+impl ExpressionResult {
+    /// Evaluates an expression, for more information see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v3/api-group-jira-expressions/#api-rest-api-3-expression-eval-post
+    pub fn evaluate<E>(
+        c: &Client,
+        expression: E,
+        context: ExpressionContext,
+    ) -> Response<ExpressionResult>
+    where
+        E: Into<String>,
+    {
+        c.ensure(Feature::RestV3)?;
+
+        let body = EvaluateBody {
+            expression: expression.into(),
+            context,
+        };
+
+        c.post("api/3/expression/eval", body)
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for ExpressionResult {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+#[derive(Serialize)]
+struct EvaluateBody {
+    expression: String,
+    context: ExpressionContext,
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_context() {
+        let context = ExpressionContext {
+            issue: Some(ContextReference::key("EX-1")),
+            sprint: Some(7),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&context).unwrap(),
+            serde_json::json!({"issue": {"key": "EX-1"}, "sprint": 7})
+        );
+    }
+}
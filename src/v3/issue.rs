@@ -0,0 +1,270 @@
+//! Represents an issue in JIRA Cloud, the rich text fields are ADF documents
+//! and people are identified by account id. As with v2 anything not covered
+//! here can be found in `others`.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::adf::Document;
+use crate::v2::{Component, IssueExpand, IssueFieldOptions, IssuePropertyOptions, IssueType};
+use crate::v2::{IssueUpdateHistory, Pagination, Priority, Progress, Resolution, Status};
+use crate::v2::{TimeTracking, Version, Vote, Watches};
+use crate::v3::{Changelog, PaginatedComments, Project, User};
+use crate::Client;
+use crate::Feature;
+use crate::QueryOptions;
+use crate::Response;
+use crate::{Date, OptionalTimestamp, Timestamp};
+use crate::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Deserialize, Serialize, Debug)]
+pub struct IssueFields {
+    /// The issue type
+    #[serde(default, rename = "issuetype")]
+    pub issue_type: Option<IssueType>,
+
+    /// Components belonging to the issue
+    #[serde(default)]
+    pub components: Vec<Component>,
+
+    /// Description of the issue
+    #[serde(default)]
+    pub description: Option<Document>,
+
+    /// Project issue is part of
+    #[serde(default)]
+    pub project: Option<Project>,
+
+    /// Fix versions assigned to the issue
+    #[serde(rename = "fixVersions", default)]
+    pub fix_versions: Vec<Version>,
+
+    /// Resolution
+    #[serde(default)]
+    pub resolution: Option<Resolution>,
+
+    /// Time tracking information (time spent, overall estimate etc.)
+    #[serde(default)]
+    pub timetracking: Option<TimeTracking>,
+
+    /// Date the issue was resolved (put into a resolution status)
+    /// in the format: "2020-03-09T20:40:15.922+0000"
+    #[serde(
+        rename = "resolutiondate",
+        default,
+        with = "crate::datetime::option_timestamp"
+    )]
+    pub resolution_date: Option<Timestamp>,
+
+    /// Summary of the issue
+    #[serde(default)]
+    pub summary: String,
+
+    /// Watcher details (how many people are watching this issue etc.)
+    #[serde(default)]
+    pub watches: Option<Watches>,
+
+    /// Creator of the issue
+    #[serde(default)]
+    pub creator: Option<User>,
+
+    /// Subtasks underneath the issue
+    #[serde(default)]
+    pub subtasks: Vec<Issue>,
+
+    /// Date the issue was created in format: "2020-03-08T14:49:58.599+0000"
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub created: OptionalTimestamp,
+
+    /// User who reported the issue
+    #[serde(default)]
+    pub reporter: Option<User>,
+
+    /// Progress based on time estimation in the ticket
+    #[serde(default)]
+    pub progress: Option<Progress>,
+
+    /// Priority assigned to the issue
+    #[serde(default)]
+    pub priority: Option<Priority>,
+
+    /// A list of labels assigned to the issue
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    /// Environment description
+    #[serde(default)]
+    pub environment: Option<Document>,
+
+    /// A list of versions attributed to the issue
+    #[serde(default)]
+    pub versions: Vec<Version>,
+
+    /// Due date of the issue in the format: "2020-03-20"
+    #[serde(rename = "duedate", default, with = "crate::datetime::option_date")]
+    pub due_date: Option<Date>,
+
+    /// Paginated comments
+    #[serde(default)]
+    pub comment: Option<PaginatedComments>,
+
+    /// Votes on the issue.
+    #[serde(default)]
+    pub votes: Option<Vote>,
+
+    /// Person assigned to the issue.
+    #[serde(default)]
+    pub assignee: Option<User>,
+
+    /// Date time the issue was last updated in the format:
+    /// "2020-03-10T16:27:20.772+0000"
+    #[serde(default, with = "crate::datetime::optional_timestamp")]
+    pub updated: OptionalTimestamp,
+
+    /// Status of the issue
+    #[serde(default)]
+    pub status: Option<Status>,
+
+    /// Flatten
+    #[serde(default, flatten)]
+    pub others: BTreeMap<String, ::serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Issue {
+    /// A link to the issue
+    #[serde(rename = "self", default)]
+    pub self_link: String,
+
+    /// The issue key in the format XXX-123
+    #[serde(default)]
+    pub key: String,
+
+    /// The internal id of the ticket
+    #[serde(default)]
+    pub id: String,
+
+    /// Fields inside the issue supported by this library, if you are looking
+    /// for custom data or otherwise can't find what you need check the others
+    /// key
+    pub fields: IssueFields,
+
+    /// Anything not covered will be flattened for access here
+    #[serde(flatten, default)]
+    pub others: BTreeMap<String, ::serde_json::Value>,
+
+    /// A chronical of the changes made to the issue.
+    #[serde(default)]
+    pub changelog: Option<Changelog>,
+}
+
+impl Issue {
+    /// Fetches an issue given an issue key (XXX-123). For more info consult
+    /// the api docs:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v3/api-group-issues/#api-rest-api-3-issue-issueidorkey-get
+    pub fn from_key<I>(
+        c: &Client,
+        key: I,
+        expand_opts: Option<Vec<IssueExpand>>,
+        field_opts: Option<IssueFieldOptions>,
+        property_opts: Option<IssuePropertyOptions>,
+        update_history: Option<IssueUpdateHistory>,
+        page: Option<Pagination>,
+    ) -> Response<Issue>
+    where
+        I: Into<String>,
+    {
+        c.ensure(Feature::RestV3)?;
+
+        let mut query = Client::unpack_options(vec![&page.unwrap_or_default()]);
+
+        query.insert(
+            "fields".to_string(),
+            field_opts.unwrap_or_default().to_string(),
+        );
+        query.insert(
+            "properties".to_string(),
+            property_opts.unwrap_or_default().to_string(),
+        );
+        query.insert(
+            "updateHistory".to_string(),
+            update_history.unwrap_or_default().to_string(),
+        );
+        query.extend(expand_to_hashmap(expand_opts.unwrap_or_default()));
+
+        let endpoint = format!("api/3/issue/{}", key.into());
+
+        c.clone().add_query(query).get(&endpoint)
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for Issue {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+impl std::fmt::Display for IssueFields {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+fn expand_to_hashmap(e: Vec<IssueExpand>) -> HashMap<String, String> {
+    let mut res: HashMap<String, String> = HashMap::new();
+    let mut value = e.iter().fold(String::from(""), |acc, e| {
+        format!("{}{},", acc, e.to_string())
+    });
+
+    value.pop();
+
+    res.insert("expand".to_string(), value);
+    res
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_results() {
+        let results =
+            fs::read_to_string("tests/assets/v3/issue.json").expect("Unable to read in JSON file");
+        let issue: Issue = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(issue.key, "EX-1");
+        assert_eq!(
+            issue.fields.description.unwrap().to_markdown(),
+            "Order **entered** by [@Mia Krystof](mention:5b10a2844c20165700ede21g)"
+        );
+        assert_eq!(
+            issue.fields.assignee.unwrap().account_id,
+            "5b10a2844c20165700ede21g"
+        );
+
+        let comments = issue.fields.comment.unwrap().comments;
+        assert_eq!(comments.len(), 1);
+        assert_eq!(
+            comments[0].body.as_ref().unwrap().to_markdown(),
+            "Looks good"
+        );
+        assert_eq!(issue.fields.status.unwrap().name, "In Progress");
+    }
+}
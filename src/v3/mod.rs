@@ -0,0 +1,34 @@
+//! Resources served by the JIRA Cloud REST API v3. Users are identified by
+//! account id and rich text fields are ADF documents, resources that are the
+//! same in both versions are shared with `v2`.
+
+// ============================================================================
+// Use
+// ============================================================================
+pub use crate::v2::{ApplicationRole, Component, Group, HistoryItem, IssueType, Item};
+pub use crate::v2::{Pagination, Priority, Progress, Resolution, Status, StatusCategory};
+pub use crate::v2::{TimeTracking, Version, Vote, Watches};
+pub use crate::v3::changelog::*;
+pub use crate::v3::comment::*;
+pub use crate::v3::expression::*;
+pub use crate::v3::issue::*;
+pub use crate::v3::page::*;
+pub use crate::v3::permission::*;
+pub use crate::v3::project::*;
+pub use crate::v3::user::*;
+
+// ============================================================================
+// Private Modules
+// ============================================================================
+mod changelog;
+mod comment;
+mod page;
+
+// ============================================================================
+// Public Modules
+// ============================================================================
+pub mod expression;
+pub mod issue;
+pub mod permission;
+pub mod project;
+pub mod user;
@@ -0,0 +1,102 @@
+//! Paged responses in the v3 API wrap their results in a page bean with a
+//! `values` list, unlike v2 where each resource names its own list.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::v2::Pagination;
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// REST API link to this page
+    #[serde(rename = "self", default)]
+    pub self_link: String,
+
+    /// URL to next page, empty on the last page
+    #[serde(rename = "nextPage", default)]
+    pub next_link: String,
+
+    /// Index of the first item in this page
+    #[serde(rename = "startAt", default)]
+    pub start_at: i64,
+
+    /// Maximum number of items in a page
+    #[serde(rename = "maxResults", default)]
+    pub max_results: i64,
+
+    /// Total number of items across all pages
+    #[serde(default)]
+    pub total: i64,
+
+    /// Is the last page
+    #[serde(rename = "isLast", default)]
+    pub is_last: bool,
+
+    /// Items in this page
+    #[serde(default = "Vec::new")]
+    pub values: Vec<T>,
+}
+
+impl<T> Page<T> {
+    /// Pagination options for the page after this one, if there is one
+    pub fn next(&self) -> Option<Pagination> {
+        let start = self.start_at + self.values.len() as i64;
+
+        if self.is_last || self.values.is_empty() || (self.total > 0 && start >= self.total) {
+            None
+        } else {
+            Some(Pagination::new(start, self.max_results))
+        }
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl<T> std::fmt::Display for Page<T>
+where
+    T: Serialize,
+{
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(start_at: i64, total: i64, is_last: bool) -> Page<i64> {
+        Page {
+            self_link: String::new(),
+            next_link: String::new(),
+            start_at,
+            max_results: 2,
+            total,
+            is_last,
+            values: vec![1, 2],
+        }
+    }
+
+    #[test]
+    fn test_next_page() {
+        let next = page(0, 5, false).next().unwrap();
+
+        assert_eq!(next.start_at, 2);
+        assert_eq!(next.max_results, 2);
+    }
+
+    #[test]
+    fn test_no_more_pages() {
+        assert!(page(0, 5, true).next().is_none());
+        assert!(page(3, 5, false).next().is_none());
+    }
+}
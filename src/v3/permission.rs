@@ -0,0 +1,134 @@
+//! Bulk permission checks, a Cloud only API for finding which of a set of
+//! permissions a user holds across projects and issues in one request
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::Client;
+use crate::Feature;
+use crate::Response;
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// Project permissions to check against a set of projects and issues
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectPermissionsCheck {
+    /// Permission keys such as "EDIT_ISSUES"
+    #[serde(default)]
+    pub permissions: Vec<String>,
+
+    /// IDs of projects to check
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub projects: Vec<i64>,
+
+    /// IDs of issues to check
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub issues: Vec<i64>,
+}
+
+/// Request body for `PermissionCheck::check`, when no account id is given
+/// the permissions of the current user are checked
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PermissionCheckRequest {
+    #[serde(rename = "accountId", skip_serializing_if = "Option::is_none", default)]
+    pub account_id: Option<String>,
+
+    /// Global permission keys such as "ADMINISTER"
+    #[serde(
+        rename = "globalPermissions",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub global_permissions: Vec<String>,
+
+    #[serde(
+        rename = "projectPermissions",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub project_permissions: Vec<ProjectPermissionsCheck>,
+}
+
+/// The projects and issues a project permission is granted for
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectPermissionGrant {
+    #[serde(default)]
+    pub permission: String,
+
+    /// IDs of projects the permission is granted for
+    #[serde(default)]
+    pub projects: Vec<i64>,
+
+    /// IDs of issues the permission is granted for
+    #[serde(default)]
+    pub issues: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PermissionCheck {
+    /// Global permissions the user holds
+    #[serde(rename = "globalPermissions", default)]
+    pub global_permissions: Vec<String>,
+
+    /// Project permissions the user holds
+    #[serde(rename = "projectPermissions", default)]
+    pub project_permissions: Vec<ProjectPermissionGrant>,
+}
+
+impl PermissionCheck {
+    /// Checks global and project permissions in bulk, for more information
+    /// see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v3/api-group-permissions/#api-rest-api-3-permissions-check-post
+    pub fn check(c: &Client, request: &PermissionCheckRequest) -> Response<PermissionCheck> {
+        c.ensure(Feature::RestV3)?;
+
+        c.post("api/3/permissions/check", request)
+    }
+
+    /// Returns true if the permission is granted for the given project
+    pub fn has_project_permission(&self, permission: &str, project: i64) -> bool {
+        self.project_permissions
+            .iter()
+            .any(|p| p.permission == permission && p.projects.contains(&project))
+    }
+
+    /// Returns true if the permission is granted for the given issue
+    pub fn has_issue_permission(&self, permission: &str, issue: i64) -> bool {
+        self.project_permissions
+            .iter()
+            .any(|p| p.permission == permission && p.issues.contains(&issue))
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for PermissionCheck {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_results() {
+        let results = fs::read_to_string("tests/assets/v3/permissions_check.json")
+            .expect("Unable to read in JSON file");
+        let check: PermissionCheck = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(check.global_permissions, vec!["ADMINISTER"]);
+        assert!(check.has_project_permission("EDIT_ISSUES", 10001));
+        assert!(!check.has_project_permission("EDIT_ISSUES", 10002));
+        assert!(check.has_issue_permission("EDIT_ISSUES", 10010));
+    }
+}
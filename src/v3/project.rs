@@ -0,0 +1,210 @@
+//! Represents a project in JIRA Cloud
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::v2::{Component, IssueType, Pagination, ProjectExpand, Version};
+use crate::v3::{Page, User};
+use crate::Client;
+use crate::Feature;
+use crate::QueryOptions;
+use crate::Response;
+use crate::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+// ============================================================================
+// Public Enums
+// ============================================================================
+pub enum ProjectOrderBy {
+    Category,
+    Key,
+    Name,
+    Owner,
+    IssueCount,
+    LastIssueUpdatedTime,
+}
+
+impl QueryOptions for ProjectOrderBy {
+    fn to_string(&self) -> String {
+        match &self {
+            ProjectOrderBy::Category => "category".to_string(),
+            ProjectOrderBy::Key => "key".to_string(),
+            ProjectOrderBy::Name => "name".to_string(),
+            ProjectOrderBy::Owner => "owner".to_string(),
+            ProjectOrderBy::IssueCount => "issueCount".to_string(),
+            ProjectOrderBy::LastIssueUpdatedTime => "lastIssueUpdatedTime".to_string(),
+        }
+    }
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Project {
+    /// REST API link to project
+    #[serde(rename = "self", default)]
+    pub self_link: String,
+
+    /// ID of project
+    #[serde(default)]
+    pub id: String,
+
+    /// Key of project
+    #[serde(default)]
+    pub key: String,
+
+    /// Project description
+    #[serde(default)]
+    pub description: String,
+
+    /// The project lead
+    #[serde(default)]
+    pub lead: Option<User>,
+
+    /// Components inside the project
+    #[serde(default)]
+    pub components: Vec<Component>,
+
+    /// Issue types connected to the project
+    #[serde(rename = "issueTypes", default)]
+    pub issue_types: Vec<IssueType>,
+
+    /// Assignee type
+    #[serde(default, rename = "assigneeType")]
+    pub assignee_type: String,
+
+    /// A list of versions inside the project
+    #[serde(default)]
+    pub versions: Vec<Version>,
+
+    /// Name of the project
+    #[serde(default)]
+    pub name: String,
+
+    /// A list of roles assigned to the project
+    #[serde(default)]
+    pub roles: BTreeMap<String, String>,
+
+    /// A list of avatar urls associated with the project
+    #[serde(rename = "avatarUrls", default)]
+    pub avatar_urls: BTreeMap<String, String>,
+
+    /// Project type
+    #[serde(rename = "projectTypeKey", default)]
+    pub project_type_key: String,
+
+    /// Is the project simplified (team-managed)
+    #[serde(default)]
+    pub simplified: bool,
+
+    /// Project style, either "classic" or "next-gen"
+    #[serde(default)]
+    pub style: String,
+
+    /// Is the project archived
+    #[serde(default)]
+    pub archived: bool,
+}
+
+impl Project {
+    /// Fetches a project from key or id, for more information see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v3/api-group-projects/#api-rest-api-3-project-projectidorkey-get
+    pub fn from_key_or_id<K>(
+        c: &Client,
+        key: K,
+        expand_opts: Option<Vec<ProjectExpand>>,
+    ) -> Response<Project>
+    where
+        K: Into<String>,
+    {
+        c.ensure(Feature::RestV3)?;
+
+        let url = format!("api/3/project/{}", key.into());
+        let query = expand_to_hashmap(expand_opts.unwrap_or_default());
+
+        c.clone().add_query(query).get(&url)
+    }
+
+    /// Fetches a page of projects visible to the user, optionally filtered by
+    /// a query matching the project key or name. For more information see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v3/api-group-projects/#api-rest-api-3-project-search-get
+    pub fn search(
+        c: &Client,
+        query: Option<&str>,
+        order_by: Option<ProjectOrderBy>,
+        expand_opts: Option<Vec<ProjectExpand>>,
+        page: Option<Pagination>,
+    ) -> Response<Page<Project>> {
+        c.ensure(Feature::RestV3)?;
+
+        let mut query_map = Client::unpack_options(vec![&page.unwrap_or_default()]);
+
+        if let Some(query) = query {
+            query_map.insert("query".to_string(), query.to_string());
+        }
+
+        if let Some(order_by) = order_by {
+            query_map.insert("orderBy".to_string(), order_by.to_string());
+        }
+
+        if let Some(expand) = expand_opts {
+            query_map.extend(expand_to_hashmap(expand));
+        }
+
+        c.clone().add_query(query_map).get("api/3/project/search")
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for Project {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+fn expand_to_hashmap(e: Vec<ProjectExpand>) -> HashMap<String, String> {
+    let mut res: HashMap<String, String> = HashMap::new();
+    let mut value = e.iter().fold(String::from(""), |acc, e| {
+        format!("{}{},", acc, e.to_string())
+    });
+
+    value.pop();
+
+    res.insert("expand".to_string(), value);
+    res
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_search_results() {
+        let results = fs::read_to_string("tests/assets/v3/project_search.json")
+            .expect("Unable to read in JSON file");
+        let page: Page<Project> = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(page.total, 2);
+        assert!(page.is_last);
+        assert_eq!(page.values.len(), 2);
+        assert_eq!(page.values[0].key, "EX");
+        assert!(!page.values[1].simplified);
+
+        match &page.values[1].lead {
+            Some(u) => assert_eq!(u.account_id, "5b10a2844c20165700ede21g"),
+            None => panic!("expected a project lead"),
+        }
+    }
+}
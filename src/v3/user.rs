@@ -0,0 +1,161 @@
+//! Interface for users in JIRA Cloud, users are identified by account id as
+//! usernames and keys are no longer available
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::v2::{ApplicationRole, Group, Item, Pagination, UserExpand};
+use crate::Client;
+use crate::Feature;
+use crate::Response;
+use crate::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct User {
+    /// The account id, this is the only way to identify a user in Cloud
+    #[serde(rename = "accountId", default)]
+    pub account_id: String,
+
+    /// The type of account, one of "atlassian", "app" or "customer"
+    #[serde(rename = "accountType", default)]
+    pub account_type: String,
+
+    /// Returns true if the user is active in Jira
+    #[serde(default)]
+    pub active: bool,
+
+    /// Returns avatar urls for the user
+    #[serde(rename = "avatarUrls", default)]
+    pub avatar_urls: BTreeMap<String, String>,
+
+    /// Returns the display name of the user
+    #[serde(rename = "displayName", default)]
+    pub display_name: String,
+
+    /// The email address associated with the account, this may be empty
+    /// depending on the user's privacy settings
+    #[serde(rename = "emailAddress", default)]
+    pub email_address: String,
+
+    /// A link to the user object
+    #[serde(rename = "self", default)]
+    pub self_link: String,
+
+    /// The timezone set for the user account
+    #[serde(rename = "timeZone", default)]
+    pub timezone: String,
+
+    /// The locale set for the user account
+    #[serde(default)]
+    pub locale: String,
+
+    /// List of groups the user belongs to
+    #[serde()]
+    pub groups: Option<Item>,
+
+    /// List of application roles the user has
+    #[serde(rename = "applicationRoles")]
+    pub application_roles: Option<Item>,
+}
+
+impl User {
+    /// Searches for users whose display name or email address match the
+    /// query, for more information see:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v3/api-group-user-search/#api-rest-api-3-user-search-get
+    pub fn search<S>(c: &Client, query: S, page: Option<Pagination>) -> Response<Vec<User>>
+    where
+        S: Into<String>,
+    {
+        c.ensure(Feature::RestV3)?;
+
+        let mut query_map = Client::unpack_options(vec![&page.unwrap_or_default()]);
+
+        query_map.insert("query".to_string(), query.into());
+
+        c.clone().add_query(query_map).get("api/3/user/search")
+    }
+
+    /// Fetches a user by account id
+    pub fn from_account_id<A>(c: &Client, account_id: A, expand: Vec<UserExpand>) -> Response<User>
+    where
+        A: Into<String>,
+    {
+        c.ensure(Feature::RestV3)?;
+
+        let mut query: HashMap<String, String> = HashMap::new();
+
+        query.insert("accountId".to_string(), account_id.into());
+        query.extend(expand_to_hashmap(expand));
+
+        c.clone().add_query(query).get("api/3/user")
+    }
+
+    pub fn groups(&self) -> Vec<Group> {
+        if let Some(i) = &self.groups {
+            serde_json::value::from_value(i.items.clone()).unwrap()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn application_roles(&self) -> Vec<ApplicationRole> {
+        if let Some(i) = &self.application_roles {
+            serde_json::value::from_value(i.items.clone()).unwrap()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for User {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+fn expand_to_hashmap(e: Vec<UserExpand>) -> HashMap<String, String> {
+    let mut res: HashMap<String, String> = HashMap::new();
+    let mut value = e.iter().fold(String::from(""), |acc, e| {
+        format!("{}{},", acc, e.to_string())
+    });
+
+    value.pop();
+
+    res.insert("expand".to_string(), value);
+    res
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_results() {
+        let results =
+            fs::read_to_string("tests/assets/v3/user.json").expect("Unable to read in JSON file");
+        let user: User = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(user.account_id, "5b10a2844c20165700ede21g");
+        assert_eq!(user.account_type, "atlassian");
+        assert_eq!(user.display_name, "Mia Krystof");
+        assert_eq!(user.groups().len(), 1);
+        assert_eq!(user.timezone, "Australia/Sydney");
+        assert!(user.active);
+    }
+}
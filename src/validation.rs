@@ -0,0 +1,283 @@
+//! Typed field validation errors, built from the `errors` JIRA sends back
+//! when it rejects an issue's fields.
+//!
+//! JIRA keys its messages by field id and words them for whoever wrote the
+//! field configuration, so `customfield_10402: Field 'customfield_10402'
+//! cannot be set` is common. `ValidationError` resolves the id to the name
+//! users see, classifies the failure and explains it:
+//!
+//! ```no_run
+//! use jirac::{Client, Credentials};
+//! use serde_json::{json, Value};
+//!
+//! let client = Client::new("https://jira.example.com", Credentials::Pat("token".into()));
+//!
+//! if let Err(e) = client.post::<_, Value>("api/2/issue", json!({"fields": {}})) {
+//!     for problem in e.validation_errors(&client).unwrap() {
+//!         // "Story Points" cannot be set, it is not on the screen for this
+//!         // project and issue type, or it does not exist
+//!         println!("{}", problem);
+//!     }
+//! }
+//! ```
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::v2::Field;
+use crate::Client;
+use crate::Result;
+use crate::{Error, Errors};
+
+// ============================================================================
+// Public Enums
+// ============================================================================
+/// Why JIRA rejected a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationKind {
+    /// The field needs a value and none was given
+    Required,
+    /// The value is not one the field accepts
+    InvalidValue,
+    /// The field is not on the create or edit screen, or does not exist
+    NotOnScreen,
+    /// The user may not set the field
+    Permission,
+    /// Anything JIRA says that is none of the above
+    Other,
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// A field JIRA rejected and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// ID of the field, such as `customfield_10402`
+    pub field_id: String,
+
+    /// Name of the field as shown to users, `None` when it is not in the
+    /// field metadata
+    pub field_name: Option<String>,
+
+    pub kind: ValidationKind,
+
+    /// The message JIRA sent
+    pub message: String,
+}
+
+impl ValidationError {
+    /// Classifies JIRA's message for a field, looking its name up in
+    /// `fields`
+    pub fn new(field_id: &str, message: &str, fields: &[Field]) -> ValidationError {
+        let field_name = fields
+            .iter()
+            .find(|f| f.id == field_id || f.key == field_id)
+            .map(|f| f.name.clone());
+
+        ValidationError {
+            field_id: field_id.to_string(),
+            field_name,
+            kind: ValidationKind::classify(message),
+            message: message.to_string(),
+        }
+    }
+
+    /// The name of the field if it is known, its id otherwise
+    pub fn field(&self) -> &str {
+        self.field_name.as_deref().unwrap_or(&self.field_id)
+    }
+}
+
+// ============================================================================
+// impl ValidationKind
+// ============================================================================
+impl ValidationKind {
+    /// Works out the kind of failure from JIRA's English message
+    pub fn classify(message: &str) -> ValidationKind {
+        let message = message.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| message.contains(n));
+
+        if has(&["appropriate screen", "cannot be set"]) {
+            ValidationKind::NotOnScreen
+        } else if has(&["permission", "not allowed to", "not authorized"]) {
+            ValidationKind::Permission
+        } else if has(&[
+            "is required",
+            "must specify",
+            "must be provided",
+            "cannot be empty",
+        ]) {
+            ValidationKind::Required
+        } else if has(&[
+            "invalid",
+            "not valid",
+            "specify a valid",
+            "could not find",
+            "does not exist",
+            "is not one of",
+            "must be",
+            "too long",
+            "exceeds",
+        ]) {
+            ValidationKind::InvalidValue
+        } else {
+            ValidationKind::Other
+        }
+    }
+}
+
+// ============================================================================
+// impl Errors
+// ============================================================================
+impl Errors {
+    /// The field errors as `ValidationError`s, in field id order, with names
+    /// looked up in `fields`
+    pub fn validation_errors(&self, fields: &[Field]) -> Vec<ValidationError> {
+        self.errors
+            .iter()
+            .map(|(id, message)| ValidationError::new(id, message, fields))
+            .collect()
+    }
+}
+
+// ============================================================================
+// impl Error
+// ============================================================================
+impl Error {
+    /// The field errors JIRA sent with the response, named using the field
+    /// metadata fetched from `client`. Empty when the error is not about
+    /// fields, in which case nothing is fetched.
+    pub fn validation_errors(&self, client: &Client) -> Result<Vec<ValidationError>> {
+        let errors = match self.context().and_then(|c| c.errors.as_ref()) {
+            Some(errors) if !errors.errors.is_empty() => errors,
+            _ => return Ok(vec![]),
+        };
+
+        let fields = Field::all(client)?.data;
+
+        Ok(errors.validation_errors(&fields))
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let field = self.field();
+
+        match self.kind {
+            ValidationKind::Required => write!(f, "\"{}\" is required", field),
+            ValidationKind::InvalidValue => {
+                write!(f, "\"{}\" has an invalid value: {}", field, self.message)
+            }
+            ValidationKind::NotOnScreen => write!(
+                f,
+                "\"{}\" cannot be set, it is not on the screen for this project and \
+                 issue type, or it does not exist",
+                field
+            ),
+            ValidationKind::Permission => {
+                write!(f, "You do not have permission to set \"{}\"", field)
+            }
+            ValidationKind::Other => write!(f, "\"{}\": {}", field, self.message),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let kind = match self {
+            ValidationKind::Required => "required",
+            ValidationKind::InvalidValue => "invalid value",
+            ValidationKind::NotOnScreen => "not on screen",
+            ValidationKind::Permission => "permission",
+            ValidationKind::Other => "other",
+        };
+
+        write!(f, "{}", kind)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::fs;
+
+    fn fields() -> Vec<Field> {
+        let results =
+            fs::read_to_string("tests/assets/v2/field.json").expect("Unable to read in JSON file");
+
+        serde_json::from_str(&results).unwrap()
+    }
+
+    #[test]
+    fn test_classify() {
+        let cases = vec![
+            (
+                "Field 'customfield_10402' cannot be set. It is not on the appropriate screen, or unknown.",
+                ValidationKind::NotOnScreen,
+            ),
+            ("You must specify a summary of the issue.", ValidationKind::Required),
+            ("Component/s is required.", ValidationKind::Required),
+            ("Version id '99' is not valid", ValidationKind::InvalidValue),
+            ("Specify a valid value for Priority", ValidationKind::InvalidValue),
+            (
+                "Summary must be less than 255 characters.",
+                ValidationKind::InvalidValue,
+            ),
+            (
+                "You do not have permission to set the fix version.",
+                ValidationKind::Permission,
+            ),
+            ("Something odd happened", ValidationKind::Other),
+        ];
+
+        for (message, kind) in cases {
+            assert_eq!(ValidationKind::classify(message), kind, "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let mut map = BTreeMap::new();
+        map.insert(
+            "customfield_10402".to_string(),
+            "Field 'customfield_10402' cannot be set. It is not on the appropriate screen, or unknown."
+                .to_string(),
+        );
+        map.insert(
+            "summary".to_string(),
+            "You must specify a summary of the issue.".to_string(),
+        );
+        map.insert(
+            "customfield_99999".to_string(),
+            "Option value 'Blue' is not valid".to_string(),
+        );
+        let errors = Errors {
+            error_messages: vec![],
+            errors: map,
+        };
+
+        let messages: Vec<String> = errors
+            .validation_errors(&fields())
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "\"Story Points\" cannot be set, it is not on the screen for this project and \
+                 issue type, or it does not exist",
+                "\"customfield_99999\" has an invalid value: Option value 'Blue' is not valid",
+                "\"Summary\" is required",
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Routes raw webhook bodies to typed handlers. Implement `WebhookHandler`
+//! for the events you care about, every method defaults to doing nothing.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::webhook::{CommentEvent, IssueEvent, ProjectEvent, SprintEvent};
+use crate::webhook::{VersionEvent, WebhookEvent, WorklogEvent};
+use crate::Result;
+use serde_json::Value;
+
+// ============================================================================
+// Traits
+// ============================================================================
+pub trait WebhookHandler {
+    fn issue_created(&mut self, _event: &IssueEvent) {}
+    fn issue_updated(&mut self, _event: &IssueEvent) {}
+    fn issue_deleted(&mut self, _event: &IssueEvent) {}
+    fn comment_created(&mut self, _event: &CommentEvent) {}
+    fn comment_updated(&mut self, _event: &CommentEvent) {}
+    fn comment_deleted(&mut self, _event: &CommentEvent) {}
+    fn worklog_created(&mut self, _event: &WorklogEvent) {}
+    fn worklog_updated(&mut self, _event: &WorklogEvent) {}
+    fn worklog_deleted(&mut self, _event: &WorklogEvent) {}
+    fn sprint_created(&mut self, _event: &SprintEvent) {}
+    fn sprint_updated(&mut self, _event: &SprintEvent) {}
+    fn sprint_deleted(&mut self, _event: &SprintEvent) {}
+    fn sprint_started(&mut self, _event: &SprintEvent) {}
+    fn sprint_closed(&mut self, _event: &SprintEvent) {}
+    fn version_created(&mut self, _event: &VersionEvent) {}
+    fn version_updated(&mut self, _event: &VersionEvent) {}
+    fn version_deleted(&mut self, _event: &VersionEvent) {}
+    fn version_released(&mut self, _event: &VersionEvent) {}
+    fn version_unreleased(&mut self, _event: &VersionEvent) {}
+    fn version_moved(&mut self, _event: &VersionEvent) {}
+    fn project_created(&mut self, _event: &ProjectEvent) {}
+    fn project_updated(&mut self, _event: &ProjectEvent) {}
+    fn project_deleted(&mut self, _event: &ProjectEvent) {}
+
+    /// Called with the untyped body for any event this library does not know
+    /// about, `name` is the value of `webhookEvent`.
+    fn unknown(&mut self, _name: &str, _body: &Value) {}
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+pub struct Dispatcher<H> {
+    handler: H,
+}
+
+impl<H> Dispatcher<H>
+where
+    H: WebhookHandler,
+{
+    /// Creates a dispatcher that routes events to the given handler
+    pub fn new(handler: H) -> Self {
+        Dispatcher { handler }
+    }
+
+    /// Returns the handler
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    /// Consumes the dispatcher returning the handler
+    pub fn into_inner(self) -> H {
+        self.handler
+    }
+
+    /// Parses a raw webhook body and calls the matching handler method.
+    /// Bodies that are not valid JSON, or a known event that does not match
+    /// its expected shape, result in an error.
+    pub fn dispatch(&mut self, body: &str) -> Result<()> {
+        let value: Value = serde_json::from_str(body)?;
+        let name = value["webhookEvent"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        if !WebhookEvent::NAMES.contains(&name.as_str()) {
+            self.handler.unknown(&name, &value);
+            return Ok(());
+        }
+
+        let event: WebhookEvent = serde_json::from_value(value)?;
+        self.route(&event);
+
+        Ok(())
+    }
+
+    /// Calls the handler method matching an already parsed event
+    pub fn route(&mut self, event: &WebhookEvent) {
+        use WebhookEvent::*;
+
+        let h = &mut self.handler;
+
+        match event {
+            IssueCreated(e) => h.issue_created(e),
+            IssueUpdated(e) => h.issue_updated(e),
+            IssueDeleted(e) => h.issue_deleted(e),
+            CommentCreated(e) => h.comment_created(e),
+            CommentUpdated(e) => h.comment_updated(e),
+            CommentDeleted(e) => h.comment_deleted(e),
+            WorklogCreated(e) => h.worklog_created(e),
+            WorklogUpdated(e) => h.worklog_updated(e),
+            WorklogDeleted(e) => h.worklog_deleted(e),
+            SprintCreated(e) => h.sprint_created(e),
+            SprintUpdated(e) => h.sprint_updated(e),
+            SprintDeleted(e) => h.sprint_deleted(e),
+            SprintStarted(e) => h.sprint_started(e),
+            SprintClosed(e) => h.sprint_closed(e),
+            VersionCreated(e) => h.version_created(e),
+            VersionUpdated(e) => h.version_updated(e),
+            VersionDeleted(e) => h.version_deleted(e),
+            VersionReleased(e) => h.version_released(e),
+            VersionUnreleased(e) => h.version_unreleased(e),
+            VersionMoved(e) => h.version_moved(e),
+            ProjectCreated(e) => h.project_created(e),
+            ProjectUpdated(e) => h.project_updated(e),
+            ProjectDeleted(e) => h.project_deleted(e),
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[derive(Default)]
+    struct Recorder {
+        calls: Vec<String>,
+    }
+
+    impl WebhookHandler for Recorder {
+        fn issue_updated(&mut self, e: &IssueEvent) {
+            self.calls.push(format!("issue_updated {}", e.issue.key));
+        }
+
+        fn comment_created(&mut self, e: &CommentEvent) {
+            self.calls.push(format!("comment_created {}", e.comment.id));
+        }
+
+        fn unknown(&mut self, name: &str, _body: &Value) {
+            self.calls.push(format!("unknown {}", name));
+        }
+    }
+
+    #[test]
+    fn test_dispatch_routes_to_handlers() {
+        let mut d = Dispatcher::new(Recorder::default());
+
+        for asset in &["issue_updated", "comment_created", "sprint_started"] {
+            let body = fs::read_to_string(format!("tests/assets/webhook/{}.json", asset))
+                .expect("Unable to read in JSON file");
+            d.dispatch(&body).unwrap();
+        }
+
+        d.dispatch(r#"{"webhookEvent": "board_created", "timestamp": 1}"#)
+            .unwrap();
+
+        assert_eq!(
+            d.into_inner().calls,
+            vec![
+                "issue_updated TEST-1",
+                "comment_created 10100",
+                "unknown board_created"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dispatch_rejects_malformed_events() {
+        let mut d = Dispatcher::new(Recorder::default());
+
+        assert!(d.dispatch("<html>").is_err());
+        assert!(d
+            .dispatch(r#"{"webhookEvent": "comment_created", "timestamp": 1}"#)
+            .is_err());
+        assert!(d.handler().calls.is_empty());
+    }
+}
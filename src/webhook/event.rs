@@ -0,0 +1,314 @@
+//! Typed payloads for the events JIRA sends to registered webhooks. The
+//! `webhookEvent` field of the payload decides which variant is used.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::agile::Sprint;
+use crate::v2::{Comment, History, Issue, Project, User, Version, Worklog};
+use crate::Result;
+use crate::{Deserialize, Serialize};
+
+// ============================================================================
+// Public Enums
+// ============================================================================
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "webhookEvent")]
+pub enum WebhookEvent {
+    #[serde(rename = "jira:issue_created")]
+    IssueCreated(IssueEvent),
+    #[serde(rename = "jira:issue_updated")]
+    IssueUpdated(IssueEvent),
+    #[serde(rename = "jira:issue_deleted")]
+    IssueDeleted(IssueEvent),
+    #[serde(rename = "comment_created")]
+    CommentCreated(CommentEvent),
+    #[serde(rename = "comment_updated")]
+    CommentUpdated(CommentEvent),
+    #[serde(rename = "comment_deleted")]
+    CommentDeleted(CommentEvent),
+    #[serde(rename = "worklog_created")]
+    WorklogCreated(WorklogEvent),
+    #[serde(rename = "worklog_updated")]
+    WorklogUpdated(WorklogEvent),
+    #[serde(rename = "worklog_deleted")]
+    WorklogDeleted(WorklogEvent),
+    #[serde(rename = "sprint_created")]
+    SprintCreated(SprintEvent),
+    #[serde(rename = "sprint_updated")]
+    SprintUpdated(SprintEvent),
+    #[serde(rename = "sprint_deleted")]
+    SprintDeleted(SprintEvent),
+    #[serde(rename = "sprint_started")]
+    SprintStarted(SprintEvent),
+    #[serde(rename = "sprint_closed")]
+    SprintClosed(SprintEvent),
+    #[serde(rename = "jira:version_created")]
+    VersionCreated(VersionEvent),
+    #[serde(rename = "jira:version_updated")]
+    VersionUpdated(VersionEvent),
+    #[serde(rename = "jira:version_deleted")]
+    VersionDeleted(VersionEvent),
+    #[serde(rename = "jira:version_released")]
+    VersionReleased(VersionEvent),
+    #[serde(rename = "jira:version_unreleased")]
+    VersionUnreleased(VersionEvent),
+    #[serde(rename = "jira:version_moved")]
+    VersionMoved(VersionEvent),
+    #[serde(rename = "project_created")]
+    ProjectCreated(ProjectEvent),
+    #[serde(rename = "project_updated")]
+    ProjectUpdated(ProjectEvent),
+    #[serde(rename = "project_deleted")]
+    ProjectDeleted(ProjectEvent),
+}
+
+impl WebhookEvent {
+    /// Every `webhookEvent` value that can be parsed into a `WebhookEvent`
+    pub const NAMES: &'static [&'static str] = &[
+        "jira:issue_created",
+        "jira:issue_updated",
+        "jira:issue_deleted",
+        "comment_created",
+        "comment_updated",
+        "comment_deleted",
+        "worklog_created",
+        "worklog_updated",
+        "worklog_deleted",
+        "sprint_created",
+        "sprint_updated",
+        "sprint_deleted",
+        "sprint_started",
+        "sprint_closed",
+        "jira:version_created",
+        "jira:version_updated",
+        "jira:version_deleted",
+        "jira:version_released",
+        "jira:version_unreleased",
+        "jira:version_moved",
+        "project_created",
+        "project_updated",
+        "project_deleted",
+    ];
+
+    /// Parses a raw webhook body
+    pub fn from_json(body: &str) -> Result<WebhookEvent> {
+        Ok(serde_json::from_str(body)?)
+    }
+
+    /// The `webhookEvent` value this event was sent with
+    pub fn name(&self) -> &'static str {
+        use WebhookEvent::*;
+
+        match self {
+            IssueCreated(_) => "jira:issue_created",
+            IssueUpdated(_) => "jira:issue_updated",
+            IssueDeleted(_) => "jira:issue_deleted",
+            CommentCreated(_) => "comment_created",
+            CommentUpdated(_) => "comment_updated",
+            CommentDeleted(_) => "comment_deleted",
+            WorklogCreated(_) => "worklog_created",
+            WorklogUpdated(_) => "worklog_updated",
+            WorklogDeleted(_) => "worklog_deleted",
+            SprintCreated(_) => "sprint_created",
+            SprintUpdated(_) => "sprint_updated",
+            SprintDeleted(_) => "sprint_deleted",
+            SprintStarted(_) => "sprint_started",
+            SprintClosed(_) => "sprint_closed",
+            VersionCreated(_) => "jira:version_created",
+            VersionUpdated(_) => "jira:version_updated",
+            VersionDeleted(_) => "jira:version_deleted",
+            VersionReleased(_) => "jira:version_released",
+            VersionUnreleased(_) => "jira:version_unreleased",
+            VersionMoved(_) => "jira:version_moved",
+            ProjectCreated(_) => "project_created",
+            ProjectUpdated(_) => "project_updated",
+            ProjectDeleted(_) => "project_deleted",
+        }
+    }
+
+    /// The time the event was fired in milliseconds since the epoch
+    pub fn timestamp(&self) -> i64 {
+        use WebhookEvent::*;
+
+        match self {
+            IssueCreated(e) | IssueUpdated(e) | IssueDeleted(e) => e.timestamp,
+            CommentCreated(e) | CommentUpdated(e) | CommentDeleted(e) => e.timestamp,
+            WorklogCreated(e) | WorklogUpdated(e) | WorklogDeleted(e) => e.timestamp,
+            SprintCreated(e) | SprintUpdated(e) | SprintDeleted(e) | SprintStarted(e)
+            | SprintClosed(e) => e.timestamp,
+            VersionCreated(e) | VersionUpdated(e) | VersionDeleted(e) | VersionReleased(e)
+            | VersionUnreleased(e) | VersionMoved(e) => e.timestamp,
+            ProjectCreated(e) | ProjectUpdated(e) | ProjectDeleted(e) => e.timestamp,
+        }
+    }
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueEvent {
+    /// When the event was fired in milliseconds since the epoch
+    #[serde(default)]
+    pub timestamp: i64,
+
+    /// The kind of issue event, e.g. "issue_generic" or "issue_commented"
+    #[serde(default)]
+    pub issue_event_type_name: Option<String>,
+
+    /// The user that caused the event
+    #[serde(default)]
+    pub user: Option<User>,
+
+    /// The issue the event is about
+    pub issue: Issue,
+
+    /// The changes made to the issue, only sent on updates. JIRA leaves out
+    /// the author and creation time, those are the event's `user` and
+    /// `timestamp`
+    #[serde(default)]
+    pub changelog: Option<History>,
+
+    /// The comment added alongside the change, if any
+    #[serde(default)]
+    pub comment: Option<Comment>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentEvent {
+    /// When the event was fired in milliseconds since the epoch
+    #[serde(default)]
+    pub timestamp: i64,
+
+    /// The comment the event is about
+    pub comment: Comment,
+
+    /// The issue the comment belongs to, JIRA only sends a subset of the
+    /// issue fields here
+    #[serde(default)]
+    pub issue: Option<Issue>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorklogEvent {
+    /// When the event was fired in milliseconds since the epoch
+    #[serde(default)]
+    pub timestamp: i64,
+
+    /// The worklog the event is about
+    pub worklog: Worklog,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SprintEvent {
+    /// When the event was fired in milliseconds since the epoch
+    #[serde(default)]
+    pub timestamp: i64,
+
+    /// The sprint the event is about
+    pub sprint: Sprint,
+
+    /// The sprint as it was before the change, only sent on updates
+    #[serde(rename = "oldValue", default)]
+    pub old_value: Option<Sprint>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionEvent {
+    /// When the event was fired in milliseconds since the epoch
+    #[serde(default)]
+    pub timestamp: i64,
+
+    /// The version the event is about
+    pub version: Version,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectEvent {
+    /// When the event was fired in milliseconds since the epoch
+    #[serde(default)]
+    pub timestamp: i64,
+
+    /// The project the event is about
+    pub project: Project,
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl std::fmt::Display for WebhookEvent {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_issue_updated() {
+        let results = fs::read_to_string("tests/assets/webhook/issue_updated.json")
+            .expect("Unable to read in JSON file");
+        let e = WebhookEvent::from_json(&results).unwrap();
+
+        assert_eq!(e.name(), "jira:issue_updated");
+        assert_eq!(e.timestamp(), 1583857640772);
+
+        match e {
+            WebhookEvent::IssueUpdated(e) => {
+                let changelog = e.changelog.unwrap();
+
+                assert_eq!(e.issue.key, "TEST-1");
+                assert_eq!(e.user.unwrap().name, "xxx");
+                assert_eq!(changelog.id, "10100");
+                assert_eq!(changelog.items[0].field, "assignee");
+                assert_eq!(changelog.items[0].from_string, "");
+                assert_eq!(changelog.items[0].to_string, "xxx");
+            }
+            _ => panic!("expected an issue updated event"),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_sprint_started() {
+        let results = fs::read_to_string("tests/assets/webhook/sprint_started.json")
+            .expect("Unable to read in JSON file");
+
+        match WebhookEvent::from_json(&results).unwrap() {
+            WebhookEvent::SprintStarted(e) => {
+                assert_eq!(e.sprint.state, "active");
+                assert!(e.old_value.is_none());
+            }
+            _ => panic!("expected a sprint started event"),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_version_released() {
+        let results = fs::read_to_string("tests/assets/webhook/version_released.json")
+            .expect("Unable to read in JSON file");
+
+        match WebhookEvent::from_json(&results).unwrap() {
+            WebhookEvent::VersionReleased(e) => assert!(e.version.released),
+            _ => panic!("expected a version released event"),
+        }
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        let results = fs::read_to_string("tests/assets/webhook/version_released.json")
+            .expect("Unable to read in JSON file");
+        let e = WebhookEvent::from_json(&results).unwrap();
+        let v = serde_json::to_value(&e).unwrap();
+
+        assert_eq!(v["webhookEvent"], e.name());
+        assert!(WebhookEvent::NAMES.contains(&e.name()));
+    }
+}
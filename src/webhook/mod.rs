@@ -0,0 +1,15 @@
+//! Types for registering and receiving JIRA webhooks
+
+// ============================================================================
+// Use
+// ============================================================================
+pub use crate::webhook::dispatcher::*;
+pub use crate::webhook::event::*;
+pub use crate::webhook::registration::*;
+
+// ============================================================================
+// Public Modules
+// ============================================================================
+pub mod dispatcher;
+pub mod event;
+pub mod registration;
@@ -0,0 +1,518 @@
+//! An interface for registering webhooks with JIRA.
+//!
+//! JIRA Server and Data Center manage webhooks as an admin resource (see
+//! `Webhook`), JIRA Cloud apps register them dynamically instead (see
+//! `DynamicWebhook`) and those registrations expire after 30 days unless they
+//! are refreshed.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::v2::Pagination;
+use crate::Client;
+use crate::Error;
+use crate::Feature;
+use crate::Response;
+use crate::Result;
+use crate::{Deserialize, Serialize};
+use reqwest::Method;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io::{Error as IoError, ErrorKind};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Constants
+// ============================================================================
+/// How long a dynamically registered webhook lives before JIRA removes it
+pub const DYNAMIC_WEBHOOK_LIFETIME: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// The filter key JIRA Server uses for the JQL applied to issue events
+const JQL_FILTER: &str = "issue-related-events-section";
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+/// A webhook registered on JIRA Server or Data Center
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Webhook {
+    /// REST API link to the webhook
+    #[serde(rename = "self", default, skip_serializing)]
+    pub self_link: String,
+
+    /// Name of the webhook
+    #[serde(default)]
+    pub name: String,
+
+    /// URL JIRA will post events to
+    #[serde(default)]
+    pub url: String,
+
+    /// Events that trigger the webhook e.g. "jira:issue_created"
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Filters applied to the events, see `jql` for the issue filter
+    #[serde(default)]
+    pub filters: BTreeMap<String, String>,
+
+    /// Send the event without a body
+    #[serde(rename = "excludeBody", default)]
+    pub exclude_body: bool,
+
+    /// Is the webhook enabled
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+impl Webhook {
+    /// Returns an enabled webhook posting the given events to the url
+    pub fn new<N, U>(name: N, url: U, events: Vec<String>) -> Self
+    where
+        N: Into<String>,
+        U: Into<String>,
+    {
+        Webhook {
+            name: name.into(),
+            url: url.into(),
+            events,
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    /// The id of the webhook, taken from its REST API link
+    pub fn id(&self) -> Option<i64> {
+        self.self_link.rsplit('/').next()?.parse().ok()
+    }
+
+    /// The JQL used to filter issue events
+    pub fn jql(&self) -> Option<&str> {
+        self.filters.get(JQL_FILTER).map(|s| s.as_str())
+    }
+
+    /// Sets the JQL used to filter issue events
+    pub fn set_jql<J>(&mut self, jql: J)
+    where
+        J: Into<String>,
+    {
+        self.filters.insert(JQL_FILTER.to_string(), jql.into());
+    }
+
+    /// Fetches all webhooks. For more info consult the api docs:
+    /// https://developer.atlassian.com/server/jira/platform/webhooks/
+    pub fn all(c: &Client) -> Response<Vec<Webhook>> {
+        c.get("webhooks/1.0/webhook")
+    }
+
+    /// Fetches a webhook by id
+    pub fn from_id(c: &Client, id: i64) -> Response<Webhook> {
+        let endpoint = format!("webhooks/1.0/webhook/{}", id);
+        c.get(&endpoint)
+    }
+
+    /// Registers a new webhook
+    pub fn create(self, c: &Client) -> Response<Webhook> {
+        c.post("webhooks/1.0/webhook", self)
+    }
+
+    /// Updates the webhook with the information currently in the struct, the
+    /// webhook must have been fetched or created first so its id is known.
+    pub fn update(self, c: &Client) -> Response<Webhook> {
+        let id = self.id().ok_or_else(|| {
+            IoError::new(
+                ErrorKind::InvalidInput,
+                "webhook has no id, fetch or create it before updating",
+            )
+        })?;
+
+        let endpoint = format!("webhooks/1.0/webhook/{}", id);
+        c.put(&endpoint, self)
+    }
+
+    /// Deletes a webhook by id
+    pub fn delete(c: &Client, id: i64) -> Response<()> {
+        let endpoint = format!("webhooks/1.0/webhook/{}", id);
+        c.delete(&endpoint)
+    }
+}
+
+/// The events and filter for a single dynamic webhook registration
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WebhookDetails {
+    /// Events that trigger the webhook e.g. "jira:issue_created"
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// JQL used to filter issue events
+    #[serde(rename = "jqlFilter", default)]
+    pub jql_filter: String,
+}
+
+/// A webhook registered by a JIRA Cloud app
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DynamicWebhook {
+    /// ID of the webhook
+    #[serde(default)]
+    pub id: i64,
+
+    /// JQL used to filter issue events
+    #[serde(rename = "jqlFilter", default)]
+    pub jql_filter: String,
+
+    /// Events that trigger the webhook
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// When the webhook expires in milliseconds since the epoch
+    #[serde(rename = "expirationDate", default)]
+    pub expiration_date: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginatedDynamicWebhooks {
+    /// A list of webhooks
+    #[serde(default)]
+    pub values: Vec<DynamicWebhook>,
+
+    /// Pagination fields
+    #[serde(flatten, default)]
+    pub pagination: Pagination,
+}
+
+/// The outcome of one registration, in the same order they were requested
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookRegistrationResult {
+    /// ID of the created webhook
+    #[serde(rename = "createdWebhookId", default)]
+    pub created_webhook_id: Option<i64>,
+
+    /// Why the webhook could not be created
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookExpiration {
+    /// The new expiry of the refreshed webhooks in milliseconds since the epoch
+    #[serde(rename = "expirationDate", default)]
+    pub expiration_date: i64,
+}
+
+/// A webhook JIRA failed to deliver
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FailedWebhook {
+    /// ID of the webhook
+    #[serde(default)]
+    pub id: String,
+
+    /// The body that could not be delivered, if JIRA kept it
+    #[serde(default)]
+    pub body: Option<String>,
+
+    /// URL the webhook was sent to
+    #[serde(default)]
+    pub url: String,
+
+    /// When delivery failed in milliseconds since the epoch
+    #[serde(rename = "failureTime", default)]
+    pub failure_time: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FailedWebhooks {
+    /// A list of failed webhooks
+    #[serde(default)]
+    pub values: Vec<FailedWebhook>,
+
+    /// The maximum number of results returned
+    #[serde(rename = "maxResults", default)]
+    pub max_results: i64,
+
+    /// A link to the next page of failures, if any
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+impl DynamicWebhook {
+    /// Registers webhooks posting to the given url. For more info consult the
+    /// api docs:
+    /// https://developer.atlassian.com/cloud/jira/platform/rest/v2/api-group-webhooks/#api-rest-api-2-webhook-post
+    pub fn register<U>(
+        c: &Client,
+        url: U,
+        webhooks: Vec<WebhookDetails>,
+    ) -> Result<Vec<WebhookRegistrationResult>>
+    where
+        U: Into<String>,
+    {
+        c.ensure(Feature::DynamicWebhooks)?;
+
+        #[derive(Serialize)]
+        struct Request {
+            url: String,
+            webhooks: Vec<WebhookDetails>,
+        }
+
+        #[derive(Deserialize)]
+        struct Results {
+            #[serde(rename = "webhookRegistrationResult", default)]
+            results: Vec<WebhookRegistrationResult>,
+        }
+
+        let res = c.post::<_, Results>(
+            "api/2/webhook",
+            Request {
+                url: url.into(),
+                webhooks,
+            },
+        )?;
+
+        Ok(res.data.results)
+    }
+
+    /// Fetches a paginated list of the webhooks registered by the calling app
+    pub fn all(c: &Client, page: Option<Pagination>) -> Response<PaginatedDynamicWebhooks> {
+        c.ensure(Feature::DynamicWebhooks)?;
+
+        let query = Client::unpack_options(vec![&page.unwrap_or_default()]);
+        c.clone().add_query(query).get("api/2/webhook")
+    }
+
+    /// Removes webhooks by id
+    pub fn delete(c: &Client, ids: &[i64]) -> Response<()> {
+        c.ensure(Feature::DynamicWebhooks)?;
+
+        let body = serde_json::to_vec(&WebhookIds { webhook_ids: ids })?;
+        c.request(Method::DELETE, "api/2/webhook", Some(body))
+    }
+
+    /// Extends the life of webhooks by id, JIRA removes webhooks that have
+    /// not been refreshed for 30 days.
+    pub fn refresh(c: &Client, ids: &[i64]) -> Response<WebhookExpiration> {
+        c.ensure(Feature::DynamicWebhooks)?;
+
+        c.put("api/2/webhook/refresh", WebhookIds { webhook_ids: ids })
+    }
+
+    /// Fetches webhooks that JIRA failed to deliver, `after` is a time in
+    /// milliseconds since the epoch to page from.
+    pub fn failed(c: &Client, max_results: i64, after: Option<i64>) -> Response<FailedWebhooks> {
+        c.ensure(Feature::DynamicWebhooks)?;
+
+        let mut query: HashMap<String, String> = HashMap::new();
+
+        query.insert("maxResults".to_string(), max_results.to_string());
+
+        if let Some(after) = after {
+            query.insert("after".to_string(), after.to_string());
+        }
+
+        c.clone().add_query(query).get("api/2/webhook/failed")
+    }
+
+    /// Refreshes every webhook registered by the app that expires within the
+    /// given duration, returning the ids that were refreshed.
+    pub fn refresh_expiring(c: &Client, within: Duration) -> Result<Vec<i64>> {
+        let mut webhooks = Vec::new();
+        let mut page = Pagination::default();
+
+        loop {
+            let res =
+                DynamicWebhook::all(c, Some(Pagination::new(page.start_at, page.max_results)))?;
+            let count = res.data.values.len() as i64;
+
+            webhooks.extend(res.data.values);
+            page.start_at += count;
+
+            if count == 0 || page.start_at >= res.data.pagination.total {
+                break;
+            }
+        }
+
+        let ids = expiring(&webhooks, now_millis(), within);
+
+        if !ids.is_empty() {
+            DynamicWebhook::refresh(c, &ids)?;
+        }
+
+        Ok(ids)
+    }
+
+    /// Spawns a thread that calls `refresh_expiring` every `interval` until
+    /// the returned refresher is stopped or dropped. Failed refreshes are
+    /// retried on the next interval and logged as warnings when the `tracing`
+    /// feature is enabled, see `spawn_refresher_with` to handle them.
+    pub fn spawn_refresher(c: &Client, interval: Duration, within: Duration) -> WebhookRefresher {
+        DynamicWebhook::spawn_refresher_with(c, interval, within, |_e| {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %_e, "refreshing dynamic webhooks failed");
+        })
+    }
+
+    /// Like `spawn_refresher`, passing the error of each failed refresh to
+    /// `on_error`
+    pub fn spawn_refresher_with<F>(
+        c: &Client,
+        interval: Duration,
+        within: Duration,
+        mut on_error: F,
+    ) -> WebhookRefresher
+    where
+        F: FnMut(Error) + Send + 'static,
+    {
+        let c = c.clone();
+        let (stop, stopped) = channel::<()>();
+
+        let handle = std::thread::spawn(move || loop {
+            if let Err(e) = DynamicWebhook::refresh_expiring(&c, within) {
+                on_error(e);
+            }
+
+            match stopped.recv_timeout(interval) {
+                Err(RecvTimeoutError::Timeout) => continue,
+                _ => break,
+            }
+        });
+
+        WebhookRefresher {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+/// A running background refresh of dynamic webhooks, see
+/// `DynamicWebhook::spawn_refresher`.
+pub struct WebhookRefresher {
+    stop: Sender<()>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl WebhookRefresher {
+    /// Stops refreshing and waits for the thread to finish
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let _ = self.stop.send(());
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl Drop for WebhookRefresher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl std::fmt::Display for Webhook {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+impl std::fmt::Display for DynamicWebhook {
+    // This trait requires fmt with this signature
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+// ============================================================================
+// Private
+// ============================================================================
+#[derive(Serialize)]
+struct WebhookIds<'a> {
+    #[serde(rename = "webhookIds")]
+    webhook_ids: &'a [i64],
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn expiring(webhooks: &[DynamicWebhook], now: i64, within: Duration) -> Vec<i64> {
+    let deadline = now + within.as_millis() as i64;
+
+    webhooks
+        .iter()
+        .filter(|w| w.expiration_date <= deadline)
+        .map(|w| w.id)
+        .collect()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_deserialize_webhook() {
+        let results = fs::read_to_string("tests/assets/webhook/webhook.json")
+            .expect("Unable to read in JSON file");
+        let w: Webhook = serde_json::from_str(&results).unwrap();
+
+        assert_eq!(w.id(), Some(1));
+        assert_eq!(w.name, "my first webhook via rest");
+        assert_eq!(w.events.len(), 2);
+        assert_eq!(w.jql(), Some("Project = JRA AND resolution = Fixed"));
+        assert!(!w.exclude_body);
+        assert!(w.enabled);
+    }
+
+    #[test]
+    fn test_serialize_webhook_jql() {
+        let mut w = Webhook::new("hook", "http://localhost", vec![]);
+        w.set_jql("project = TEST");
+
+        let v = serde_json::to_value(&w).unwrap();
+
+        assert_eq!(
+            v["filters"]["issue-related-events-section"],
+            "project = TEST"
+        );
+        assert!(v.get("self").is_none());
+    }
+
+    #[test]
+    fn test_update_without_id() {
+        let c = Client::new("http://localhost", crate::Credentials::Pat("token".into()));
+        let w = Webhook::new("hook", "http://localhost", vec![]);
+
+        match w.update(&c) {
+            Err(Error::IO(e)) => assert_eq!(e.kind(), ErrorKind::InvalidInput),
+            other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+        }
+    }
+
+    #[test]
+    fn test_expiring() {
+        let results = fs::read_to_string("tests/assets/webhook/dynamic_webhooks.json")
+            .expect("Unable to read in JSON file");
+        let w: PaginatedDynamicWebhooks = serde_json::from_str(&results).unwrap();
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        assert_eq!(expiring(&w.values, 1589000000000, day), vec![10000]);
+        assert_eq!(
+            expiring(&w.values, 1589000000000, DYNAMIC_WEBHOOK_LIFETIME),
+            vec![10000, 10001]
+        );
+    }
+}
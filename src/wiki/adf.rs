@@ -0,0 +1,577 @@
+//! Conversion between wiki documents and ADF, Markdown goes through ADF in
+//! both directions
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::adf::{self as a, Mark, Node};
+use crate::wiki::{Block, Document, Inline, ListItem, PanelKind, TableCell};
+use std::collections::BTreeMap;
+
+// ============================================================================
+// Wiki to ADF
+// ============================================================================
+pub(crate) fn to_adf(doc: &Document) -> a::Document {
+    a::Document::new(doc.blocks.iter().flat_map(block_to_adf).collect())
+}
+
+fn block_to_adf(block: &Block) -> Vec<Node> {
+    let node = match block {
+        Block::Heading { level, content } => Node::Heading(a::Heading {
+            attrs: a::HeadingAttrs {
+                level: *level,
+                other: BTreeMap::new(),
+            },
+            content: inline_to_adf(content, &[]),
+            other: BTreeMap::new(),
+        }),
+        Block::Paragraph(content) => return paragraph_to_adf(content),
+        Block::List { ordered, items } => {
+            let items = items.iter().map(item_to_adf).collect();
+
+            if *ordered {
+                Node::OrderedList(a::OrderedList {
+                    attrs: None,
+                    content: items,
+                    other: BTreeMap::new(),
+                })
+            } else {
+                Node::BulletList(a::Container::new(items))
+            }
+        }
+        Block::Code { language, code } => code_block(language.clone(), code),
+        Block::NoFormat(text) => code_block(None, text),
+        Block::Quote(blocks) => Node::Blockquote(a::Container::new(
+            blocks.iter().flat_map(block_to_adf).collect(),
+        )),
+        Block::Panel {
+            kind,
+            title,
+            content,
+        } => {
+            let panel_type = match kind {
+                PanelKind::Panel | PanelKind::Info => a::PanelType::Info,
+                PanelKind::Note => a::PanelType::Note,
+                PanelKind::Warning => a::PanelType::Warning,
+                PanelKind::Tip => a::PanelType::Success,
+            };
+
+            let mut blocks = vec![];
+            if let Some(title) = title {
+                blocks.push(Node::paragraph(vec![Node::Text(a::Text {
+                    text: title.clone(),
+                    marks: vec![Mark::Strong],
+                    ..Default::default()
+                })]));
+            }
+            blocks.extend(content.iter().flat_map(block_to_adf));
+
+            Node::Panel(a::Panel {
+                attrs: a::PanelAttrs {
+                    panel_type,
+                    other: BTreeMap::new(),
+                },
+                content: blocks,
+                other: BTreeMap::new(),
+            })
+        }
+        Block::Table(rows) => Node::Table(a::Table {
+            attrs: None,
+            content: rows
+                .iter()
+                .map(|row| Node::TableRow(a::Container::new(row.iter().map(cell_to_adf).collect())))
+                .collect(),
+            other: BTreeMap::new(),
+        }),
+        Block::Rule => Node::rule(),
+    };
+
+    vec![node]
+}
+
+/// A paragraph of nothing but images becomes media, otherwise images are
+/// kept as links to the attachment
+fn paragraph_to_adf(content: &[Inline]) -> Vec<Node> {
+    let images: Vec<&String> = content
+        .iter()
+        .filter_map(|i| match i {
+            Inline::Image { src, .. } => Some(src),
+            _ => None,
+        })
+        .collect();
+
+    let only_images = content.iter().all(|i| match i {
+        Inline::Image { .. } | Inline::LineBreak => true,
+        Inline::Text(t) => t.trim().is_empty(),
+        _ => false,
+    });
+
+    if images.is_empty() || !only_images {
+        return vec![Node::paragraph(inline_to_adf(content, &[]))];
+    }
+
+    images
+        .into_iter()
+        .map(|src| {
+            Node::MediaSingle(a::MediaSingle {
+                attrs: None,
+                content: vec![Node::Media(a::Media {
+                    attrs: a::MediaAttrs {
+                        media_type: a::MediaType::External,
+                        id: None,
+                        collection: None,
+                        url: Some(src.clone()),
+                        alt: None,
+                        width: None,
+                        height: None,
+                        other: BTreeMap::new(),
+                    },
+                    other: BTreeMap::new(),
+                })],
+                other: BTreeMap::new(),
+            })
+        })
+        .collect()
+}
+
+fn item_to_adf(item: &ListItem) -> Node {
+    let mut content = vec![Node::paragraph(inline_to_adf(&item.content, &[]))];
+    content.extend(item.children.iter().flat_map(block_to_adf));
+
+    Node::ListItem(a::Container::new(content))
+}
+
+fn cell_to_adf(cell: &TableCell) -> Node {
+    let cell_node = a::TableCell {
+        attrs: None,
+        content: vec![Node::paragraph(inline_to_adf(&cell.content, &[]))],
+        other: BTreeMap::new(),
+    };
+
+    if cell.header {
+        Node::TableHeader(cell_node)
+    } else {
+        Node::TableCell(cell_node)
+    }
+}
+
+fn code_block(language: Option<String>, code: &str) -> Node {
+    Node::CodeBlock(a::CodeBlock {
+        attrs: language.map(|language| a::CodeBlockAttrs {
+            language: Some(language),
+            other: BTreeMap::new(),
+        }),
+        content: if code.is_empty() {
+            vec![]
+        } else {
+            vec![Node::text(code)]
+        },
+        other: BTreeMap::new(),
+    })
+}
+
+/// Flattens nested formatting into text nodes carrying marks
+fn inline_to_adf(nodes: &[Inline], marks: &[Mark]) -> Vec<Node> {
+    let text = |text: &str, marks: Vec<Mark>| {
+        Node::Text(a::Text {
+            text: text.to_string(),
+            marks,
+            ..Default::default()
+        })
+    };
+
+    let with = |mark: Mark| {
+        let mut marks = marks.to_vec();
+        marks.push(mark);
+        marks
+    };
+
+    let mut out = vec![];
+
+    for node in nodes {
+        match node {
+            Inline::Text(t) => out.push(text(t, marks.to_vec())),
+            Inline::Strong(c) => out.extend(inline_to_adf(c, &with(Mark::Strong))),
+            Inline::Emphasis(c) | Inline::Citation(c) => {
+                out.extend(inline_to_adf(c, &with(Mark::Em)))
+            }
+            Inline::Strike(c) => out.extend(inline_to_adf(c, &with(Mark::Strike))),
+            Inline::Underline(c) => out.extend(inline_to_adf(c, &with(Mark::Underline))),
+            Inline::Superscript(c) => {
+                out.extend(inline_to_adf(c, &with(subsup(a::SubsupType::Sup))))
+            }
+            Inline::Subscript(c) => out.extend(inline_to_adf(c, &with(subsup(a::SubsupType::Sub)))),
+            Inline::Monospace(t) => out.push(text(t, with(Mark::Code))),
+            Inline::Color { color, content } => out.extend(inline_to_adf(
+                content,
+                &with(Mark::TextColor {
+                    attrs: a::TextColorAttrs {
+                        color: color.clone(),
+                        other: BTreeMap::new(),
+                    },
+                }),
+            )),
+            Inline::Link { url, content } => {
+                let link = with(link(url));
+                if content.is_empty() {
+                    out.push(text(url, link));
+                } else {
+                    out.extend(inline_to_adf(content, &link));
+                }
+            }
+            Inline::Mention(user) => out.push(Node::Mention(a::Mention {
+                attrs: a::MentionAttrs {
+                    id: user.trim_start_matches("accountid:").to_string(),
+                    text: Some(format!("@{}", user.trim_start_matches("accountid:"))),
+                    ..Default::default()
+                },
+                other: BTreeMap::new(),
+            })),
+            Inline::Image { src, .. } => out.push(text(src, with(link(src)))),
+            Inline::LineBreak => out.push(Node::hard_break()),
+        }
+    }
+
+    out
+}
+
+fn link(href: &str) -> Mark {
+    Mark::Link {
+        attrs: a::LinkAttrs {
+            href: href.to_string(),
+            ..Default::default()
+        },
+    }
+}
+
+fn subsup(kind: a::SubsupType) -> Mark {
+    Mark::Subsup {
+        attrs: a::SubsupAttrs {
+            kind,
+            other: BTreeMap::new(),
+        },
+    }
+}
+
+// ============================================================================
+// ADF to Wiki
+// ============================================================================
+pub(crate) fn from_adf(doc: &a::Document) -> Document {
+    Document {
+        blocks: blocks_from_adf(&doc.content),
+    }
+}
+
+fn blocks_from_adf(nodes: &[Node]) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut inline = vec![];
+
+    for node in nodes {
+        if node.is_inline() {
+            inline.push(node.clone());
+            continue;
+        }
+
+        if !inline.is_empty() {
+            blocks.push(Block::Paragraph(inline_from_adf(&inline)));
+            inline.clear();
+        }
+
+        blocks.extend(block_from_adf(node));
+    }
+
+    if !inline.is_empty() {
+        blocks.push(Block::Paragraph(inline_from_adf(&inline)));
+    }
+
+    blocks
+}
+
+fn block_from_adf(node: &Node) -> Vec<Block> {
+    let block = match node {
+        Node::Paragraph(p) => Block::Paragraph(inline_from_adf(&p.content)),
+        Node::Heading(h) => Block::Heading {
+            level: h.attrs.level,
+            content: inline_from_adf(&h.content),
+        },
+        Node::BulletList(l) => list_from_adf(false, &l.content),
+        Node::OrderedList(l) => list_from_adf(true, &l.content),
+        Node::CodeBlock(c) => {
+            let code = c
+                .content
+                .iter()
+                .filter_map(|n| match n {
+                    Node::Text(t) => Some(t.text.as_str()),
+                    _ => None,
+                })
+                .collect();
+
+            match c.attrs.as_ref().and_then(|a| a.language.clone()) {
+                Some(language) => Block::Code {
+                    language: Some(language),
+                    code,
+                },
+                None => Block::NoFormat(code),
+            }
+        }
+        Node::Blockquote(q) => Block::Quote(blocks_from_adf(&q.content)),
+        Node::Panel(p) => Block::Panel {
+            kind: match p.attrs.panel_type {
+                a::PanelType::Note => PanelKind::Note,
+                a::PanelType::Warning | a::PanelType::Error => PanelKind::Warning,
+                a::PanelType::Success => PanelKind::Tip,
+                a::PanelType::Info => PanelKind::Info,
+                a::PanelType::Custom => PanelKind::Panel,
+            },
+            title: None,
+            content: blocks_from_adf(&p.content),
+        },
+        Node::Rule(_) => Block::Rule,
+        Node::Table(t) => Block::Table(
+            t.content
+                .iter()
+                .map(|row| {
+                    row.content()
+                        .iter()
+                        .map(|cell| TableCell {
+                            header: matches!(cell, Node::TableHeader(_)),
+                            content: join_paragraphs(cell.content()),
+                        })
+                        .collect()
+                })
+                .collect(),
+        ),
+        Node::MediaSingle(_) | Node::MediaGroup(_) => {
+            Block::Paragraph(inline_from_adf(node.content()))
+        }
+        Node::Unknown(v) => {
+            return serde_json::from_value::<Vec<Node>>(v["content"].clone())
+                .map(|content| blocks_from_adf(&content))
+                .unwrap_or_default()
+        }
+        n => return blocks_from_adf(n.content()),
+    };
+
+    vec![block]
+}
+
+fn list_from_adf(ordered: bool, items: &[Node]) -> Block {
+    let items = items
+        .iter()
+        .map(|item| {
+            let mut content = vec![];
+            let mut children = vec![];
+
+            for block in item.content() {
+                match block {
+                    Node::BulletList(_) | Node::OrderedList(_) => {
+                        children.extend(block_from_adf(block))
+                    }
+                    block => {
+                        if !content.is_empty() {
+                            content.push(Inline::LineBreak);
+                        }
+                        content.extend(join_paragraphs(std::slice::from_ref(block)));
+                    }
+                }
+            }
+
+            ListItem { content, children }
+        })
+        .collect();
+
+    Block::List { ordered, items }
+}
+
+/// The text of a list item or table cell, wiki markup only allows a single
+/// line so paragraphs are joined with line breaks
+fn join_paragraphs(nodes: &[Node]) -> Vec<Inline> {
+    let mut out = vec![];
+
+    for node in nodes {
+        let inline = if node.is_inline() {
+            inline_from_adf(std::slice::from_ref(node))
+        } else if node.content().iter().all(Node::is_inline) {
+            inline_from_adf(node.content())
+        } else {
+            join_paragraphs(node.content())
+        };
+
+        if inline.is_empty() {
+            continue;
+        }
+
+        if !out.is_empty() {
+            out.push(Inline::LineBreak);
+        }
+        out.extend(inline);
+    }
+
+    out
+}
+
+/// Rebuilds nested formatting from the marks on each text node, nodes that
+/// share their outermost mark are grouped together
+fn inline_from_adf(nodes: &[Node]) -> Vec<Inline> {
+    let items: Vec<(Vec<&Mark>, &Node)> = nodes
+        .iter()
+        .map(|node| {
+            let mut marks: Vec<&Mark> = match node {
+                Node::Text(t) => t.marks.iter().filter(|m| rank(m).is_some()).collect(),
+                _ => vec![],
+            };
+            marks.sort_by_key(|m| rank(m));
+            (marks, node)
+        })
+        .collect();
+
+    nest(&items, 0)
+}
+
+fn nest(items: &[(Vec<&Mark>, &Node)], depth: usize) -> Vec<Inline> {
+    let mut out = vec![];
+    let mut i = 0;
+
+    while i < items.len() {
+        let mark = match items[i].0.get(depth) {
+            Some(mark) => *mark,
+            None => {
+                out.extend(leaf(items[i].1));
+                i += 1;
+                continue;
+            }
+        };
+
+        let start = i;
+        while i < items.len() && items[i].0.get(depth) == Some(&mark) {
+            i += 1;
+        }
+
+        out.push(wrap(mark, nest(&items[start..i], depth + 1)));
+    }
+
+    out
+}
+
+fn leaf(node: &Node) -> Option<Inline> {
+    match node {
+        Node::Text(t) if t.marks.contains(&Mark::Code) => Some(Inline::Monospace(t.text.clone())),
+        Node::Text(t) => Some(Inline::Text(t.text.clone())),
+        Node::HardBreak(_) => Some(Inline::LineBreak),
+        Node::Mention(m) if is_account_id(&m.attrs.id) => {
+            Some(Inline::Mention(format!("accountid:{}", m.attrs.id)))
+        }
+        Node::Mention(m) => Some(Inline::Mention(m.attrs.id.clone())),
+        Node::Emoji(e) => Some(Inline::Text(
+            e.attrs
+                .text
+                .clone()
+                .unwrap_or_else(|| e.attrs.short_name.clone()),
+        )),
+        Node::InlineCard(c) => c.attrs.url.as_ref().map(|url| Inline::Link {
+            url: url.clone(),
+            content: vec![],
+        }),
+        Node::Media(m) => m
+            .attrs
+            .url
+            .clone()
+            .or_else(|| m.attrs.alt.clone())
+            .or_else(|| m.attrs.id.clone())
+            .map(|src| Inline::Image { src, params: None }),
+        Node::Unknown(v) => v["text"].as_str().map(|t| Inline::Text(t.to_string())),
+        _ => None,
+    }
+}
+
+fn wrap(mark: &Mark, content: Vec<Inline>) -> Inline {
+    match mark {
+        Mark::Link { attrs } => {
+            let bare = matches!(content.as_slice(), [Inline::Text(t)] if *t == attrs.href);
+            Inline::Link {
+                url: attrs.href.clone(),
+                content: if bare { vec![] } else { content },
+            }
+        }
+        Mark::Strong => Inline::Strong(content),
+        Mark::Em => Inline::Emphasis(content),
+        Mark::Strike => Inline::Strike(content),
+        Mark::Underline => Inline::Underline(content),
+        Mark::TextColor { attrs } => Inline::Color {
+            color: attrs.color.clone(),
+            content,
+        },
+        Mark::Subsup { attrs } if attrs.kind == a::SubsupType::Sup => Inline::Superscript(content),
+        Mark::Subsup { .. } => Inline::Subscript(content),
+        Mark::Code | Mark::Unknown(_) => Inline::Strong(content),
+    }
+}
+
+/// Order marks are nested in, code is written as monospace text instead
+fn rank(mark: &Mark) -> Option<u8> {
+    match mark {
+        Mark::Link { .. } => Some(0),
+        Mark::Strong => Some(1),
+        Mark::Em => Some(2),
+        Mark::Strike => Some(3),
+        Mark::Underline => Some(4),
+        Mark::TextColor { .. } => Some(5),
+        Mark::Subsup { .. } => Some(6),
+        Mark::Code | Mark::Unknown(_) => None,
+    }
+}
+
+/// Cloud account ids are either 24 hex digits or prefixed with a number and
+/// a colon, e.g. "557058:f58131cb-b67d-43c7-b30d-6b58d40bd077"
+fn is_account_id(id: &str) -> bool {
+    id.contains(':') || (id.len() == 24 && id.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use crate::wiki::Document;
+
+    #[test]
+    fn test_to_markdown() {
+        let doc = Document::parse(
+            "h3. Summary\n\nSee *[the docs|https://example.com]* by [~accountid:5b10ac8d82e05b22cc7d4ef5]\n\n* one\n*# two\n\n{code:sh}\nmake\n{code}\n\n{note}\nmind the gap\n{note}\n\n!diagram.png!",
+        );
+
+        assert_eq!(
+            doc.to_markdown(),
+            "### Summary\n\n\
+             See [**the docs**](https://example.com) by [@5b10ac8d82e05b22cc7d4ef5](mention:5b10ac8d82e05b22cc7d4ef5)\n\n\
+             - one\n  1. two\n\n\
+             ```sh\nmake\n```\n\n\
+             > [!NOTE]\n> mind the gap\n\n\
+             ![](diagram.png)"
+        );
+    }
+
+    #[test]
+    fn test_from_markdown() {
+        let doc = Document::from_markdown(
+            "## Title\n\nSome **bold *both*** and `code`, see [docs](https://x.com)\n\n1. first\n   - nested\n\n> quoted\n\n| A | B |\n| --- | --- |\n| 1 | 2 |",
+        );
+
+        assert_eq!(
+            doc.to_wiki(),
+            "h2. Title\n\n\
+             Some *bold _both_* and {{code}}, see [docs|https://x.com]\n\n\
+             # first\n#* nested\n\n\
+             bq. quoted\n\n\
+             ||A||B||\n|1|2|"
+        );
+    }
+
+    #[test]
+    fn test_adf_round_trip() {
+        let markup = "h1. Plan\n\n*bold* _em_ -gone- +under+ H~2~O x^2^ {{mono}} {color:#ff5630}red{color}\n[link|https://x.com] [~accountid:5b10ac8d82e05b22cc7d4ef5]\n\n# a\n## b\n\n||H||\n|C|\n\n{code:rust}\nfn main() {}\n{code}\n\n{noformat}\nraw\n{noformat}\n\nbq. quote\n\n----";
+        let doc = Document::parse(markup);
+
+        assert_eq!(Document::from(&doc.to_adf()), doc);
+    }
+}
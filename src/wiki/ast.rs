@@ -0,0 +1,172 @@
+//! The tree a piece of wiki markup is parsed into
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::wiki::{adf, html, parser, writer};
+
+// ============================================================================
+// Public Enums
+// ============================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// `h1. ` to `h6. `
+    Heading { level: u8, content: Vec<Inline> },
+
+    /// Lines of text, single newlines become `Inline::LineBreak`
+    Paragraph(Vec<Inline>),
+
+    /// Lines starting with `*`, `-` or `#`, nested by repeating the marker
+    List { ordered: bool, items: Vec<ListItem> },
+
+    /// `{code:language}...{code}`
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+
+    /// `{noformat}...{noformat}`
+    NoFormat(String),
+
+    /// `bq. ` or `{quote}...{quote}`
+    Quote(Vec<Block>),
+
+    /// `{panel:title=...}...{panel}` and the `{info}`, `{note}`, `{warning}`
+    /// and `{tip}` macros
+    Panel {
+        kind: PanelKind,
+        title: Option<String>,
+        content: Vec<Block>,
+    },
+
+    /// Rows of `||heading||` and `|cell|`
+    Table(Vec<Vec<TableCell>>),
+
+    /// `----`
+    Rule,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inline {
+    Text(String),
+    /// `*strong*`
+    Strong(Vec<Inline>),
+    /// `_emphasis_`
+    Emphasis(Vec<Inline>),
+    /// `-deleted-`
+    Strike(Vec<Inline>),
+    /// `+inserted+`
+    Underline(Vec<Inline>),
+    /// `^superscript^`
+    Superscript(Vec<Inline>),
+    /// `~subscript~`
+    Subscript(Vec<Inline>),
+    /// `??citation??`
+    Citation(Vec<Inline>),
+    /// `{{monospaced}}`
+    Monospace(String),
+    /// `{color:red}text{color}`
+    Color {
+        color: String,
+        content: Vec<Inline>,
+    },
+    /// `[url]` or `[text|url]`
+    Link {
+        url: String,
+        content: Vec<Inline>,
+    },
+    /// `[~username]` or `[~accountid:5b10ac8d82e05b22cc7d4ef5]`
+    Mention(String),
+    /// `!image.png!` or `!image.png|thumbnail!`
+    Image {
+        src: String,
+        params: Option<String>,
+    },
+    /// `\\` or a newline inside a paragraph
+    LineBreak,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelKind {
+    Panel,
+    Info,
+    Note,
+    Warning,
+    Tip,
+}
+
+// ============================================================================
+// Public Structures
+// ============================================================================
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListItem {
+    /// Text of the item
+    pub content: Vec<Inline>,
+
+    /// Lists nested under the item
+    pub children: Vec<Block>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableCell {
+    /// True for `||heading||` cells
+    pub header: bool,
+
+    pub content: Vec<Inline>,
+}
+
+/// A parsed piece of wiki markup such as a v2 issue description or comment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    pub blocks: Vec<Block>,
+}
+
+impl Document {
+    /// Parses wiki markup, anything that is not valid markup is kept as text
+    pub fn parse(markup: &str) -> Document {
+        Document {
+            blocks: parser::parse_blocks(markup),
+        }
+    }
+
+    /// Converts Markdown to a document, use `to_wiki` to get the markup
+    pub fn from_markdown(markdown: &str) -> Document {
+        adf::from_adf(&crate::adf::Document::from_markdown(markdown))
+    }
+
+    /// Writes the document back out as wiki markup
+    pub fn to_wiki(&self) -> String {
+        writer::write_blocks(&self.blocks)
+    }
+
+    /// Renders the document as Markdown
+    pub fn to_markdown(&self) -> String {
+        self.to_adf().to_markdown()
+    }
+
+    /// Renders the document as an HTML fragment
+    pub fn to_html(&self) -> String {
+        html::render_blocks(&self.blocks)
+    }
+
+    /// Converts the document to the Atlassian Document Format used by the v3
+    /// API. Image attachments become external media pointing at their name.
+    pub fn to_adf(&self) -> crate::adf::Document {
+        adf::to_adf(self)
+    }
+}
+
+// ============================================================================
+// Trait Implementations
+// ============================================================================
+impl From<&crate::adf::Document> for Document {
+    fn from(doc: &crate::adf::Document) -> Self {
+        adf::from_adf(doc)
+    }
+}
+
+impl std::fmt::Display for Document {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_wiki())
+    }
+}
@@ -0,0 +1,261 @@
+//! Renders a wiki document as an HTML fragment using the same elements and
+//! classes JIRA does where there is one.
+//!
+//! Wiki markup comes from JIRA users so the output is safe to embed: text is
+//! escaped, links and images are only rendered for http, https, mailto and
+//! relative URLs and colours are only applied when they are a name or a hex
+//! code. Anything else is rendered as plain text.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::wiki::{Block, Inline, ListItem, PanelKind};
+
+// ============================================================================
+// Blocks
+// ============================================================================
+pub(crate) fn render_blocks(blocks: &[Block]) -> String {
+    blocks
+        .iter()
+        .map(render_block)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_block(block: &Block) -> String {
+    match block {
+        Block::Heading { level, content } => {
+            let level = level.clamp(&1, &6);
+            format!("<h{l}>{}</h{l}>", render_inline(content), l = level)
+        }
+        Block::Paragraph(content) => format!("<p>{}</p>", render_inline(content)),
+        Block::List { ordered, items } => render_list(*ordered, items),
+        Block::Code { language, code } => match language {
+            Some(language) => format!(
+                "<pre><code class=\"language-{}\">{}</code></pre>",
+                escape(language),
+                escape(code)
+            ),
+            None => format!("<pre><code>{}</code></pre>", escape(code)),
+        },
+        Block::NoFormat(text) => format!("<pre>{}</pre>", escape(text)),
+        Block::Quote(blocks) => format!("<blockquote>{}</blockquote>", render_blocks(blocks)),
+        Block::Panel {
+            kind,
+            title,
+            content,
+        } => {
+            let class = match kind {
+                PanelKind::Panel => "panel",
+                PanelKind::Info => "panel information",
+                PanelKind::Note => "panel note",
+                PanelKind::Warning => "panel warning",
+                PanelKind::Tip => "panel tip",
+            };
+
+            let header = title
+                .as_ref()
+                .map(|t| format!("<div class=\"panelHeader\"><b>{}</b></div>", escape(t)))
+                .unwrap_or_default();
+
+            format!(
+                "<div class=\"{}\">{}<div class=\"panelContent\">{}</div></div>",
+                class,
+                header,
+                render_blocks(content)
+            )
+        }
+        Block::Table(rows) => {
+            let rows: String = rows
+                .iter()
+                .map(|row| {
+                    let cells: String = row
+                        .iter()
+                        .map(|cell| {
+                            let tag = if cell.header { "th" } else { "td" };
+                            format!("<{t}>{}</{t}>", render_inline(&cell.content), t = tag)
+                        })
+                        .collect();
+                    format!("<tr>{}</tr>", cells)
+                })
+                .collect();
+
+            format!("<table><tbody>{}</tbody></table>", rows)
+        }
+        Block::Rule => "<hr>".to_string(),
+    }
+}
+
+fn render_list(ordered: bool, items: &[ListItem]) -> String {
+    let tag = if ordered { "ol" } else { "ul" };
+    let items: String = items
+        .iter()
+        .map(|item| {
+            let children: String = item.children.iter().map(render_block).collect();
+            format!("<li>{}{}</li>", render_inline(&item.content), children)
+        })
+        .collect();
+
+    format!("<{t}>{}</{t}>", items, t = tag)
+}
+
+// ============================================================================
+// Inline
+// ============================================================================
+fn render_inline(nodes: &[Inline]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Inline::Text(text) => escape(text),
+            Inline::Strong(c) => wrap("strong", c),
+            Inline::Emphasis(c) => wrap("em", c),
+            Inline::Strike(c) => wrap("del", c),
+            Inline::Underline(c) => wrap("ins", c),
+            Inline::Superscript(c) => wrap("sup", c),
+            Inline::Subscript(c) => wrap("sub", c),
+            Inline::Citation(c) => wrap("cite", c),
+            Inline::Monospace(text) => format!("<code>{}</code>", escape(text)),
+            Inline::Color { color, content } if is_safe_color(color) => format!(
+                "<span style=\"color: {}\">{}</span>",
+                escape(color),
+                render_inline(content)
+            ),
+            Inline::Color { content, .. } => render_inline(content),
+            Inline::Link { url, content } => {
+                let text = if content.is_empty() {
+                    escape(url)
+                } else {
+                    render_inline(content)
+                };
+
+                if is_safe_url(url) {
+                    format!("<a href=\"{}\">{}</a>", escape(url), text)
+                } else {
+                    text
+                }
+            }
+            Inline::Mention(user) => format!(
+                "<a class=\"user-hover\" rel=\"{u}\">@{u}</a>",
+                u = escape(user)
+            ),
+            Inline::Image { src, .. } if is_safe_url(src) => {
+                format!("<img src=\"{}\" alt=\"\">", escape(src))
+            }
+            Inline::Image { src, .. } => escape(src),
+            Inline::LineBreak => "<br>".to_string(),
+        })
+        .collect()
+}
+
+fn wrap(tag: &str, content: &[Inline]) -> String {
+    format!("<{t}>{}</{t}>", render_inline(content), t = tag)
+}
+
+/// True for http, https and mailto URLs and for relative ones. Browsers
+/// ignore whitespace and control characters inside a scheme so any scheme
+/// that is not exactly one of those is refused.
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim();
+    let scheme_end = url.find([':', '/', '?', '#']);
+
+    match scheme_end {
+        Some(end) if url[end..].starts_with(':') => {
+            let scheme = &url[..end];
+            ["http", "https", "mailto"]
+                .iter()
+                .any(|s| scheme.eq_ignore_ascii_case(s))
+        }
+        _ => true,
+    }
+}
+
+/// True for a colour name such as "red" or a hex code such as "#ff5630"
+fn is_safe_color(color: &str) -> bool {
+    match color.strip_prefix('#') {
+        Some(hex) => matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => !color.is_empty() && color.chars().all(|c| c.is_ascii_alphabetic()),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use crate::wiki::Document;
+
+    #[test]
+    fn test_render_html() {
+        let doc = Document::parse(
+            "h1. Title & <more>\n\n*bold* [~jdoe] [docs|https://x.com?a=1&b=2]\n\n# one\n## two\n\n||A||\n|{{x < y}}|\n\n{code:sql}\nSELECT 1;\n{code}",
+        );
+
+        assert_eq!(
+            doc.to_html(),
+            "<h1>Title &amp; &lt;more&gt;</h1>\n\
+             <p><strong>bold</strong> <a class=\"user-hover\" rel=\"jdoe\">@jdoe</a> <a href=\"https://x.com?a=1&amp;b=2\">docs</a></p>\n\
+             <ol><li>one<ol><li>two</li></ol></li></ol>\n\
+             <table><tbody><tr><th>A</th></tr><tr><td><code>x &lt; y</code></td></tr></tbody></table>\n\
+             <pre><code class=\"language-sql\">SELECT 1;</code></pre>"
+        );
+    }
+
+    #[test]
+    fn test_unsafe_links_are_text() {
+        let doc = Document::parse(
+            "[x|javascript:alert(1)] [JavaScript:alert(1)] [y|java\tscript:alert(1)] !data:image/png;base64,AA!",
+        );
+
+        assert_eq!(
+            doc.to_html(),
+            "<p>x JavaScript:alert(1) y data:image/png;base64,AA</p>"
+        );
+
+        let doc = Document::parse("[a|HTTPS://x.com] [b|mailto:a@x.com] [c|/browse/T-1] [d|#top]");
+
+        assert_eq!(
+            doc.to_html(),
+            "<p><a href=\"HTTPS://x.com\">a</a> <a href=\"mailto:a@x.com\">b</a> \
+             <a href=\"/browse/T-1\">c</a> <a href=\"#top\">d</a></p>"
+        );
+    }
+
+    #[test]
+    fn test_unsafe_colors_are_ignored() {
+        let doc = Document::parse(
+            "{color:red;background:url(//evil)}a{color} {color:#FF5630}b{color} {color:blue}c{color} {color:#12345g}d{color}",
+        );
+
+        assert_eq!(
+            doc.to_html(),
+            "<p>a <span style=\"color: #FF5630\">b</span> <span style=\"color: blue\">c</span> d</p>"
+        );
+    }
+
+    #[test]
+    fn test_empty_table_rows() {
+        let doc = Document::parse("||\n|a|");
+
+        assert_eq!(
+            doc.to_html(),
+            "<table><tbody><tr><td>a</td></tr></tbody></table>"
+        );
+    }
+}
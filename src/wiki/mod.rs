@@ -0,0 +1,20 @@
+//! JIRA wiki markup, used for rich text by JIRA Server and the v2 API, with
+//! rendering to Markdown and HTML and conversion from Markdown.
+
+// ============================================================================
+// Use
+// ============================================================================
+pub use crate::wiki::ast::*;
+
+// ============================================================================
+// Private Modules
+// ============================================================================
+mod adf;
+mod html;
+mod parser;
+mod writer;
+
+// ============================================================================
+// Public Modules
+// ============================================================================
+pub mod ast;
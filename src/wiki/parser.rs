@@ -0,0 +1,716 @@
+//! Parses wiki markup into blocks and inline nodes. Markup is forgiving,
+//! anything that does not form a valid construct is kept as text.
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::wiki::{Block, Inline, ListItem, PanelKind, TableCell};
+
+// ============================================================================
+// Blocks
+// ============================================================================
+pub(crate) fn parse_blocks(markup: &str) -> Vec<Block> {
+    let markup = markup.replace("\r\n", "\n");
+    let mut blocks = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut rest = markup.as_str();
+
+    while !rest.is_empty() {
+        if let Some((block, remaining)) = block_macro(rest.trim_start_matches([' ', '\t'])) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(block);
+            rest = remaining;
+            continue;
+        }
+
+        let (line, next) = split_line(rest);
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            rest = next;
+        } else if let Some(block) = single_line_block(trimmed) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(block);
+            rest = next;
+        } else if list_marker(trimmed).is_some() {
+            flush_paragraph(&mut paragraph, &mut blocks);
+
+            let mut entries = vec![];
+            while !rest.is_empty() {
+                let (line, next) = split_line(rest);
+                match list_marker(line.trim()) {
+                    Some(entry) => entries.push(entry),
+                    None => break,
+                }
+                rest = next;
+            }
+
+            blocks.extend(build_lists(&entries));
+        } else if trimmed.starts_with('|') {
+            flush_paragraph(&mut paragraph, &mut blocks);
+
+            let mut rows = vec![];
+            while !rest.is_empty() {
+                let (line, next) = split_line(rest);
+                if !line.trim().starts_with('|') {
+                    break;
+                }
+                let row = parse_row(line.trim());
+                if !row.is_empty() {
+                    rows.push(row);
+                }
+                rest = next;
+            }
+
+            // A row of bare pipes such as "||" has no cells to show
+            if !rows.is_empty() {
+                blocks.push(Block::Table(rows));
+            }
+        } else {
+            paragraph.push(trimmed);
+            rest = next;
+        }
+    }
+
+    flush_paragraph(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn split_line(s: &str) -> (&str, &str) {
+    match s.find('\n') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    }
+}
+
+fn flush_paragraph(lines: &mut Vec<&str>, blocks: &mut Vec<Block>) {
+    if lines.is_empty() {
+        return;
+    }
+
+    let mut content = vec![];
+    for (i, line) in lines.drain(..).enumerate() {
+        if i > 0 {
+            content.push(Inline::LineBreak);
+        }
+        content.extend(parse_inline(line));
+    }
+
+    blocks.push(Block::Paragraph(content));
+}
+
+/// Headings, single line quotes and rules
+fn single_line_block(line: &str) -> Option<Block> {
+    if line == "----" {
+        return Some(Block::Rule);
+    }
+
+    if let Some(text) = line.strip_prefix("bq.") {
+        if text.is_empty() || text.starts_with(' ') {
+            return Some(Block::Quote(vec![Block::Paragraph(parse_inline(
+                text.trim(),
+            ))]));
+        }
+    }
+
+    let mut chars = line.chars();
+    if let (Some('h'), Some(level @ '1'..='6'), Some('.')) =
+        (chars.next(), chars.next(), chars.next())
+    {
+        let text = chars.as_str();
+        if text.is_empty() || text.starts_with(' ') {
+            return Some(Block::Heading {
+                level: level as u8 - b'0',
+                content: parse_inline(text.trim()),
+            });
+        }
+    }
+
+    None
+}
+
+/// Parses `{code:java}...{code}` and the other block macros returning the
+/// block and the markup following the closing tag
+fn block_macro(s: &str) -> Option<(Block, &str)> {
+    let open_end = s.strip_prefix('{')?.find('}')? + 1;
+    let (name, params) = match s[1..open_end].find(':') {
+        Some(i) => (&s[1..i + 1], Some(&s[i + 2..open_end])),
+        None => (&s[1..open_end], None),
+    };
+
+    let kind = match name {
+        "code" | "noformat" | "quote" => None,
+        "panel" => Some(PanelKind::Panel),
+        "info" => Some(PanelKind::Info),
+        "note" => Some(PanelKind::Note),
+        "warning" => Some(PanelKind::Warning),
+        "tip" => Some(PanelKind::Tip),
+        _ => return None,
+    };
+
+    let close = format!("{{{}}}", name);
+    let body_start = open_end + 1;
+    let body_len = s[body_start..].find(&close)?;
+    let body = &s[body_start..body_start + body_len];
+    let mut rest = &s[body_start + body_len + close.len()..];
+
+    // Drop the remainder of the closing line when it is only whitespace
+    let (line, next) = split_line(rest);
+    if line.trim().is_empty() {
+        rest = next;
+    }
+
+    let (positional, named) = parse_params(params.unwrap_or_default());
+
+    let block = match name {
+        "code" => Block::Code {
+            language: named
+                .iter()
+                .find(|(k, _)| *k == "language")
+                .map(|(_, v)| v.to_string())
+                .or(positional),
+            code: trim_newlines(body).to_string(),
+        },
+        "noformat" => Block::NoFormat(trim_newlines(body).to_string()),
+        "quote" => Block::Quote(parse_blocks(body)),
+        _ => Block::Panel {
+            kind: kind.unwrap_or(PanelKind::Panel),
+            title: named
+                .iter()
+                .find(|(k, _)| *k == "title")
+                .map(|(_, v)| v.to_string()),
+            content: parse_blocks(body),
+        },
+    };
+
+    Some((block, rest))
+}
+
+/// Splits "java|title=Example" into the positional value and named values
+fn parse_params(params: &str) -> (Option<String>, Vec<(&str, &str)>) {
+    let mut positional = None;
+    let mut named = vec![];
+
+    for param in params.split('|').filter(|p| !p.is_empty()) {
+        match param.find('=') {
+            Some(i) => named.push((param[..i].trim(), param[i + 1..].trim())),
+            None if positional.is_none() => positional = Some(param.trim().to_string()),
+            None => {}
+        }
+    }
+
+    (positional, named)
+}
+
+/// Code blocks nearly always start and end with a newline inside the tags
+fn trim_newlines(s: &str) -> &str {
+    let s = s.strip_prefix('\n').unwrap_or(s);
+    s.strip_suffix('\n').unwrap_or(s)
+}
+
+/// Returns the markers and text of a list line such as "*# item"
+fn list_marker(line: &str) -> Option<(&str, &str)> {
+    let end = line.find(|c: char| c != '*' && c != '#' && c != '-')?;
+    let (marker, text) = line.split_at(end);
+
+    let valid = !marker.is_empty()
+        && (marker == "-" || !marker.contains('-'))
+        && text.starts_with([' ', '\t']);
+
+    if valid {
+        Some((marker, text.trim()))
+    } else {
+        None
+    }
+}
+
+/// Splits list lines into separate lists wherever the top level marker
+/// changes, e.g. from "*" to "#"
+fn build_lists(entries: &[(&str, &str)]) -> Vec<Block> {
+    let mut lists = vec![];
+    let mut start = 0;
+
+    for i in 1..=entries.len() {
+        let changed = i == entries.len() || entries[i].0[..1] != entries[start].0[..1];
+        if changed {
+            lists.push(build_list(&entries[start..i], 1));
+            start = i;
+        }
+    }
+
+    lists
+}
+
+fn build_list(entries: &[(&str, &str)], depth: usize) -> Block {
+    let ordered = entries[0].0.as_bytes().get(depth - 1) == Some(&b'#');
+    let mut items: Vec<ListItem> = vec![];
+    let mut i = 0;
+
+    while i < entries.len() {
+        let (marker, text) = entries[i];
+
+        if marker.len() <= depth {
+            items.push(ListItem {
+                content: parse_inline(text),
+                children: vec![],
+            });
+            i += 1;
+            continue;
+        }
+
+        // A change of marker at the same depth starts a sibling list
+        let start = i;
+        let kind = entries[i].0.as_bytes()[depth];
+        while i < entries.len()
+            && entries[i].0.len() > depth
+            && entries[i].0.as_bytes()[depth] == kind
+        {
+            i += 1;
+        }
+
+        if items.is_empty() {
+            items.push(ListItem::default());
+        }
+
+        let child = build_list(&entries[start..i], depth + 1);
+        items.last_mut().unwrap().children.push(child);
+    }
+
+    Block::List { ordered, items }
+}
+
+/// Splits "||a||b||" or "|a|b|" into cells, pipes inside links, macros and
+/// images do not end a cell
+fn parse_row(line: &str) -> Vec<TableCell> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut cells = vec![];
+    let mut current = String::new();
+    let mut header = false;
+    let mut started = false;
+    let mut depth = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        match c {
+            '\\' if i + 1 < chars.len() => {
+                current.push(c);
+                current.push(chars[i + 1]);
+                i += 2;
+                continue;
+            }
+            '[' | '{' => depth += 1,
+            ']' | '}' if depth > 0 => depth -= 1,
+            '!' if depth == 0 => {
+                if let Some(end) = image_end(&chars, i) {
+                    current.extend(&chars[i..=end]);
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '|' if depth == 0 => {
+                if started {
+                    cells.push(TableCell {
+                        header,
+                        content: parse_inline(current.trim()),
+                    });
+                    current.clear();
+                }
+
+                started = true;
+                header = chars.get(i + 1) == Some(&'|');
+                i += if header { 2 } else { 1 };
+                continue;
+            }
+            _ => {}
+        }
+
+        current.push(c);
+        i += 1;
+    }
+
+    if !current.trim().is_empty() {
+        cells.push(TableCell {
+            header,
+            content: parse_inline(current.trim()),
+        });
+    }
+
+    cells
+}
+
+// ============================================================================
+// Inline
+// ============================================================================
+const FORMATS: &[&str] = &["??", "*", "_", "-", "+", "^", "~"];
+
+pub(crate) fn parse_inline(text: &str) -> Vec<Inline> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = vec![];
+    let mut buf = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        let parsed = match c {
+            '\\' if next == Some('\\') => Some((Inline::LineBreak, i + 2)),
+            '\\' if next.is_some() => {
+                buf.push(next.unwrap());
+                i += 2;
+                continue;
+            }
+            '{' => brace(&chars, i),
+            '[' => link(&chars, i),
+            '!' => image_end(&chars, i).map(|end| (image(&chars[i + 1..end]), end + 1)),
+            _ => format(&chars, i),
+        };
+
+        match parsed {
+            Some((inline, end)) => {
+                if !buf.is_empty() {
+                    out.push(Inline::Text(std::mem::take(&mut buf)));
+                }
+                out.push(inline);
+                i = end;
+            }
+            None => {
+                buf.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    if !buf.is_empty() {
+        out.push(Inline::Text(buf));
+    }
+
+    out
+}
+
+fn starts_with(chars: &[char], i: usize, s: &str) -> bool {
+    (i..).zip(s.chars()).all(|(j, c)| chars.get(j) == Some(&c))
+}
+
+fn find(chars: &[char], from: usize, s: &str) -> Option<usize> {
+    (from..chars.len()).find(|&j| starts_with(chars, j, s))
+}
+
+fn collect(chars: &[char]) -> String {
+    chars.iter().collect()
+}
+
+/// `{{monospace}}`, `{color:red}text{color}` and `{*}strong{*}` which can
+/// be used in the middle of a word
+fn brace(chars: &[char], i: usize) -> Option<(Inline, usize)> {
+    if starts_with(chars, i, "{{") {
+        let end = find(chars, i + 2, "}}")?;
+        return Some((Inline::Monospace(collect(&chars[i + 2..end])), end + 2));
+    }
+
+    if starts_with(chars, i, "{color:") {
+        let open_end = find(chars, i, "}")?;
+        let close = find(chars, open_end + 1, "{color}")?;
+        return Some((
+            Inline::Color {
+                color: collect(&chars[i + 7..open_end]).trim().to_string(),
+                content: parse_inline(&collect(&chars[open_end + 1..close])),
+            },
+            close + 7,
+        ));
+    }
+
+    for delim in FORMATS {
+        let tag = format!("{{{}}}", delim);
+        if starts_with(chars, i, &tag) {
+            let start = i + tag.chars().count();
+            let end = find(chars, start, &tag)?;
+            if end == start {
+                return None;
+            }
+
+            let content = parse_inline(&collect(&chars[start..end]));
+            return Some((wrap(delim, content), end + tag.chars().count()));
+        }
+    }
+
+    None
+}
+
+/// `[~user]`, `[url]` and `[text|url]`
+fn link(chars: &[char], i: usize) -> Option<(Inline, usize)> {
+    let end = find(chars, i + 1, "]")?;
+    let inner = collect(&chars[i + 1..end]);
+
+    if inner.trim().is_empty() {
+        return None;
+    }
+
+    if let Some(user) = inner.strip_prefix('~') {
+        return Some((Inline::Mention(user.to_string()), end + 1));
+    }
+
+    let inline = match inner.find('|') {
+        Some(p) => Inline::Link {
+            url: inner[p + 1..]
+                .split('|')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            content: parse_inline(&inner[..p]),
+        },
+        None => Inline::Link {
+            url: inner.trim().to_string(),
+            content: vec![],
+        },
+    };
+
+    Some((inline, end + 1))
+}
+
+/// Finds the closing `!` of an image, the name may not contain whitespace
+fn image_end(chars: &[char], i: usize) -> Option<usize> {
+    let end = find(chars, i + 1, "!")?;
+    let name = &chars[i + 1..end];
+
+    if name.is_empty() || name.iter().any(|c| c.is_whitespace()) {
+        None
+    } else {
+        Some(end)
+    }
+}
+
+fn image(inner: &[char]) -> Inline {
+    let inner = collect(inner);
+
+    match inner.find('|') {
+        Some(p) => Inline::Image {
+            src: inner[..p].to_string(),
+            params: Some(inner[p + 1..].to_string()),
+        },
+        None => Inline::Image {
+            src: inner,
+            params: None,
+        },
+    }
+}
+
+/// `*strong*` and friends. An opening delimiter can not follow a letter or be
+/// followed by whitespace, a closing one is the reverse.
+fn format(chars: &[char], i: usize) -> Option<(Inline, usize)> {
+    let delim = FORMATS.iter().find(|d| starts_with(chars, i, d))?;
+    let len = delim.chars().count();
+    let start = i + len;
+
+    let before = if i == 0 { None } else { chars.get(i - 1) };
+    let after = chars.get(start)?;
+
+    if before.is_some_and(|c| c.is_alphanumeric())
+        || after.is_whitespace()
+        || starts_with(chars, start, delim)
+    {
+        return None;
+    }
+
+    let end = (start + 1..chars.len()).find(|&j| {
+        starts_with(chars, j, delim)
+            && !chars[j - 1].is_whitespace()
+            && !chars
+                .get(j + len)
+                .is_some_and(|c| c.is_alphanumeric() || starts_with(chars, j + len, delim))
+    })?;
+
+    let content = parse_inline(&collect(&chars[start..end]));
+    Some((wrap(delim, content), end + len))
+}
+
+fn wrap(delim: &str, content: Vec<Inline>) -> Inline {
+    match delim {
+        "*" => Inline::Strong(content),
+        "_" => Inline::Emphasis(content),
+        "-" => Inline::Strike(content),
+        "+" => Inline::Underline(content),
+        "^" => Inline::Superscript(content),
+        "~" => Inline::Subscript(content),
+        _ => Inline::Citation(content),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Inline {
+        Inline::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_parse_inline_formatting() {
+        assert_eq!(
+            parse_inline("a *bold _both_* and -gone- in foo-bar-baz"),
+            vec![
+                text("a "),
+                Inline::Strong(vec![text("bold "), Inline::Emphasis(vec![text("both")])]),
+                text(" and "),
+                Inline::Strike(vec![text("gone")]),
+                text(" in foo-bar-baz"),
+            ]
+        );
+
+        assert_eq!(
+            parse_inline("x{*}y{*}z H{~}2{~}O ??cite?? 2 * 3 * 4"),
+            vec![
+                text("x"),
+                Inline::Strong(vec![text("y")]),
+                text("z H"),
+                Inline::Subscript(vec![text("2")]),
+                text("O "),
+                Inline::Citation(vec![text("cite")]),
+                text(" 2 * 3 * 4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_inline_links_and_macros() {
+        assert_eq!(
+            parse_inline("[~jdoe] see [the docs|https://example.com] or [https://example.com]"),
+            vec![
+                Inline::Mention("jdoe".to_string()),
+                text(" see "),
+                Inline::Link {
+                    url: "https://example.com".to_string(),
+                    content: vec![text("the docs")],
+                },
+                text(" or "),
+                Inline::Link {
+                    url: "https://example.com".to_string(),
+                    content: vec![],
+                },
+            ]
+        );
+
+        assert_eq!(
+            parse_inline("{{a*b*}} {color:red}hot{color}\\\\!shot.png|thumbnail! wow! \\*"),
+            vec![
+                Inline::Monospace("a*b*".to_string()),
+                text(" "),
+                Inline::Color {
+                    color: "red".to_string(),
+                    content: vec![text("hot")],
+                },
+                Inline::LineBreak,
+                Inline::Image {
+                    src: "shot.png".to_string(),
+                    params: Some("thumbnail".to_string()),
+                },
+                text(" wow! *"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        let blocks = parse_blocks(
+            "h1. Title\n\nfirst line\nsecond line\n\n* one\n** nested\n*# numbered\n* two\n# other\n\n{code:java}\nint x = 1;\n{code}\nbq. quoted\n----",
+        );
+
+        assert_eq!(
+            blocks[0],
+            Block::Heading {
+                level: 1,
+                content: vec![text("Title")],
+            }
+        );
+        assert_eq!(
+            blocks[1],
+            Block::Paragraph(vec![
+                text("first line"),
+                Inline::LineBreak,
+                text("second line")
+            ])
+        );
+
+        match &blocks[2] {
+            Block::List { ordered, items } => {
+                assert!(!ordered);
+                assert_eq!(items.len(), 2);
+                assert_eq!(items[0].children.len(), 2);
+                assert!(matches!(
+                    &items[0].children[0],
+                    Block::List { ordered: false, .. }
+                ));
+                assert!(matches!(
+                    &items[0].children[1],
+                    Block::List { ordered: true, .. }
+                ));
+            }
+            b => panic!("expected a list, got {:?}", b),
+        }
+
+        assert!(matches!(&blocks[3], Block::List { ordered: true, .. }));
+        assert_eq!(
+            blocks[4],
+            Block::Code {
+                language: Some("java".to_string()),
+                code: "int x = 1;".to_string(),
+            }
+        );
+        assert!(matches!(&blocks[5], Block::Quote(_)));
+        assert_eq!(blocks[6], Block::Rule);
+        assert_eq!(blocks.len(), 7);
+    }
+
+    #[test]
+    fn test_parse_macros_and_tables() {
+        let blocks = parse_blocks(
+            "{panel:title=Heads up|borderStyle=dashed}\n*careful*\n{panel}\n{noformat}raw *text*{noformat}\n||Key||Summary||\n|TEST-1|[a|https://x.com/a] !s.png|thumbnail!|",
+        );
+
+        match &blocks[0] {
+            Block::Panel {
+                kind,
+                title,
+                content,
+            } => {
+                assert_eq!(*kind, PanelKind::Panel);
+                assert_eq!(title.as_deref(), Some("Heads up"));
+                assert_eq!(content.len(), 1);
+            }
+            b => panic!("expected a panel, got {:?}", b),
+        }
+
+        assert_eq!(blocks[1], Block::NoFormat("raw *text*".to_string()));
+
+        match &blocks[2] {
+            Block::Table(rows) => {
+                assert!(rows[0].iter().all(|c| c.header));
+                assert_eq!(rows[1].len(), 2);
+                assert!(!rows[1][1].header);
+                assert_eq!(rows[1][1].content.len(), 3);
+            }
+            b => panic!("expected a table, got {:?}", b),
+        }
+    }
+
+    #[test]
+    fn test_unclosed_markup_is_text() {
+        assert_eq!(
+            parse_blocks("{code}\nnever closed\n*half"),
+            vec![Block::Paragraph(vec![
+                text("{code}"),
+                Inline::LineBreak,
+                text("never closed"),
+                Inline::LineBreak,
+                text("*half"),
+            ])]
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Writes a wiki document back out as markup
+
+// ============================================================================
+// Use
+// ============================================================================
+use crate::wiki::{Block, Inline, ListItem, PanelKind};
+
+// ============================================================================
+// Blocks
+// ============================================================================
+pub(crate) fn write_blocks(blocks: &[Block]) -> String {
+    blocks
+        .iter()
+        .map(write_block)
+        .filter(|b| !b.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn write_block(block: &Block) -> String {
+    match block {
+        Block::Heading { level, content } => {
+            format!("h{}. {}", level.clamp(&1, &6), write_inline(content, false))
+        }
+        Block::Paragraph(content) => write_inline(content, false),
+        Block::List { ordered, items } => write_list(*ordered, items, ""),
+        Block::Code { language, code } => match language {
+            Some(language) => format!("{{code:{}}}\n{}\n{{code}}", language, code),
+            None => format!("{{code}}\n{}\n{{code}}", code),
+        },
+        Block::NoFormat(text) => format!("{{noformat}}\n{}\n{{noformat}}", text),
+        Block::Quote(blocks) => match blocks.as_slice() {
+            [Block::Paragraph(content)] if !content.contains(&Inline::LineBreak) => {
+                format!("bq. {}", write_inline(content, false))
+            }
+            blocks => format!("{{quote}}\n{}\n{{quote}}", write_blocks(blocks)),
+        },
+        Block::Panel {
+            kind,
+            title,
+            content,
+        } => {
+            let name = match kind {
+                PanelKind::Panel => "panel",
+                PanelKind::Info => "info",
+                PanelKind::Note => "note",
+                PanelKind::Warning => "warning",
+                PanelKind::Tip => "tip",
+            };
+
+            let open = match title {
+                Some(title) => format!("{{{}:title={}}}", name, title),
+                None => format!("{{{}}}", name),
+            };
+
+            format!("{}\n{}\n{{{}}}", open, write_blocks(content), name)
+        }
+        Block::Table(rows) => rows
+            .iter()
+            .map(|row| {
+                let mut line = String::new();
+                for cell in row {
+                    line.push_str(if cell.header { "||" } else { "|" });
+                    line.push_str(&write_inline(&cell.content, true));
+                }
+                line.push_str(match row.last() {
+                    Some(cell) if cell.header => "||",
+                    _ => "|",
+                });
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Block::Rule => "----".to_string(),
+    }
+}
+
+fn write_list(ordered: bool, items: &[ListItem], prefix: &str) -> String {
+    let marker = format!("{}{}", prefix, if ordered { '#' } else { '*' });
+    let mut lines = vec![];
+
+    for item in items {
+        if !item.content.is_empty() {
+            lines.push(format!("{} {}", marker, write_inline(&item.content, true)));
+        }
+
+        for child in &item.children {
+            match child {
+                Block::List { ordered, items } => lines.push(write_list(*ordered, items, &marker)),
+                block => lines.push(write_block(block)),
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+// ============================================================================
+// Inline
+// ============================================================================
+/// Writes inline nodes, inside tables and lists a line break has to be
+/// written as `\\` as a newline would end the row or item.
+pub(crate) fn write_inline(nodes: &[Inline], single_line: bool) -> String {
+    let mut out = String::new();
+
+    for (i, node) in nodes.iter().enumerate() {
+        let written = match node {
+            Inline::Text(text) => escape(text, out.is_empty() || out.ends_with('\n')),
+            Inline::Strong(c) => delimit("*", c, &out, nodes.get(i + 1)),
+            Inline::Emphasis(c) => delimit("_", c, &out, nodes.get(i + 1)),
+            Inline::Strike(c) => delimit("-", c, &out, nodes.get(i + 1)),
+            Inline::Underline(c) => delimit("+", c, &out, nodes.get(i + 1)),
+            Inline::Superscript(c) => delimit("^", c, &out, nodes.get(i + 1)),
+            Inline::Subscript(c) => delimit("~", c, &out, nodes.get(i + 1)),
+            Inline::Citation(c) => delimit("??", c, &out, nodes.get(i + 1)),
+            Inline::Monospace(text) => format!("{{{{{}}}}}", text),
+            Inline::Color { color, content } => format!(
+                "{{color:{}}}{}{{color}}",
+                color,
+                write_inline(content, single_line)
+            ),
+            Inline::Link { url, content } if content.is_empty() => format!("[{}]", url),
+            Inline::Link { url, content } => {
+                format!("[{}|{}]", write_inline(content, true), url)
+            }
+            Inline::Mention(user) => format!("[~{}]", user),
+            Inline::Image { src, params } => match params {
+                Some(params) => format!("!{}|{}!", src, params),
+                None => format!("!{}!", src),
+            },
+            Inline::LineBreak if single_line => "\\\\".to_string(),
+            Inline::LineBreak => "\n".to_string(),
+        };
+
+        out.push_str(&written);
+    }
+
+    out
+}
+
+/// Wraps formatted content, the `{*}` form is used where the plain form
+/// would not be recognised such as in the middle of a word
+fn delimit(delim: &str, content: &[Inline], before: &str, next: Option<&Inline>) -> String {
+    let inner = write_inline(content, true);
+
+    let after_word = before.chars().last().is_some_and(char::is_alphanumeric);
+    let before_word = match next {
+        Some(Inline::Text(t)) => t.chars().next().is_some_and(char::is_alphanumeric),
+        _ => false,
+    };
+    let padded = inner.starts_with(char::is_whitespace) || inner.ends_with(char::is_whitespace);
+
+    if after_word || before_word || padded || inner.is_empty() {
+        format!("{{{d}}}{}{{{d}}}", inner, d = delim)
+    } else {
+        format!("{d}{}{d}", inner, d = delim)
+    }
+}
+
+/// Escapes characters that would otherwise be read as markup. Formatting
+/// characters are only escaped where the parser would take them to open
+/// formatting, a closing one means nothing without an opening one, so
+/// ordinary text such as "foo-bar" or "é*ü*" is left alone.
+fn escape(text: &str, line_start: bool) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = if i == 0 { None } else { Some(chars[i - 1]) };
+        let next = chars.get(i + 1).copied();
+
+        let opens =
+            !prev.is_some_and(char::is_alphanumeric) && next.is_some_and(|n| !n.is_whitespace());
+
+        let needs_escape = match c {
+            '\\' | '[' | '{' | '}' | '|' => true,
+            '!' => next.is_some_and(|n| !n.is_whitespace()),
+            '?' => next == Some('?'),
+            '*' | '-' | '#' if line_start && i == 0 => true,
+            '*' | '_' | '-' | '+' | '^' | '~' => opens,
+            _ => false,
+        };
+
+        if needs_escape {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[cfg(test)]
+mod tests {
+    use crate::wiki::Document;
+
+    #[test]
+    fn test_round_trip() {
+        let markup = "h2. Steps
+
+Run *all* the _tests_ in foo-bar{*}baz{*}
+then [~jdoe] checks [the docs|https://example.com]
+
+* one
+** nested
+*# numbered
+* two
+
+{code:rust}
+fn main() {}
+{code}
+
+bq. quoted
+
+{warning:title=Careful}
+{{rm -rf}} is {color:red}dangerous{color}
+{warning}
+
+||Key||Summary||
+|TEST-1|line\\\\break|
+
+----";
+
+        assert_eq!(Document::parse(markup).to_wiki(), markup);
+    }
+
+    #[test]
+    fn test_escapes_text() {
+        let doc = Document::parse(r"\*not bold\* 2 * 3 \[x\] \{{y}} a-b wow!");
+
+        assert_eq!(doc.to_wiki(), r"\*not bold* 2 * 3 \[x] \{\{y\}\} a-b wow!");
+        assert_eq!(Document::parse(&doc.to_wiki()), doc);
+
+        for markup in &["é*ü*", "x_y_ z", "a+b+ c"] {
+            assert_eq!(Document::parse(markup).to_wiki(), *markup);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::agile::{Epic, EpicColour, EpicUpdate, RankPosition};
+use jirac::Client;
+use jirac::Credentials;
+use mockito::{mock, Matcher};
+use serde_json::json;
+use std::fs;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_update() {
+    let result =
+        fs::read_to_string("tests/assets/agile/epic.json").expect("Unable to read in JSON file");
+
+    let _m = mock("POST", "/rest/agile/1.0/epic/EPIC-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::Json(json!({
+            "summary": "epic 1 summary",
+            "color": { "key": "color_4" }
+        })))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let update = EpicUpdate {
+        summary: Some("epic 1 summary".to_string()),
+        colour: Some(EpicColour {
+            key: "color_4".to_string(),
+        }),
+        ..Default::default()
+    };
+
+    let e = Epic::update(&client, "EPIC-1", update).unwrap();
+
+    assert_eq!(e.data.id, 37);
+}
+
+#[test]
+fn test_issues_without_epic() {
+    let result = fs::read_to_string("tests/assets/agile/sprint_issues.json")
+        .expect("Unable to read in JSON file");
+
+    let _m = mock("GET", "/rest/agile/1.0/epic/none/issue")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::Any)
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let i = Epic::issues_without_epic(&client, None).unwrap();
+
+    assert_eq!(i.data.issues.len(), 1);
+}
+
+#[test]
+fn test_move_and_remove_issues() {
+    let issues: Vec<String> = (1..=51).map(|i| format!("TEST-{}", i)).collect();
+
+    let moved = mock("POST", "/rest/agile/1.0/epic/EPIC-2/issue")
+        .with_status(204)
+        .expect(2)
+        .create();
+
+    let removed = mock("POST", "/rest/agile/1.0/epic/none/issue")
+        .with_status(204)
+        .expect(2)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    Epic::move_issues(&client, "EPIC-2", &issues).unwrap();
+    Epic::remove_issues(&client, &issues).unwrap();
+
+    moved.assert();
+    removed.assert();
+}
+
+#[test]
+fn test_rank() {
+    let m = mock("PUT", "/rest/agile/1.0/epic/EPIC-3/rank")
+        .with_status(204)
+        .match_body(Matcher::Json(json!({ "rankAfterEpic": "EPIC-4" })))
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    Epic::rank(
+        &client,
+        "EPIC-3",
+        RankPosition::After("EPIC-4".to_string()),
+        None,
+    )
+    .unwrap();
+
+    m.assert();
+}
@@ -0,0 +1,71 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::agile::Rank;
+use jirac::Client;
+use jirac::Credentials;
+use mockito::{mock, Matcher};
+use serde_json::json;
+use std::fs;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_rank_before_and_reorder() {
+    let issues = vec![String::from("PR-1"), String::from("PR-2")];
+
+    let before = mock("PUT", "/rest/agile/1.0/issue/rank")
+        .with_status(204)
+        .match_body(Matcher::Json(json!({
+            "issues": ["PR-1", "PR-2"],
+            "rankBeforeIssue": "PR-3",
+            "rankCustomFieldId": 10521
+        })))
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let r = Rank::before(&client, &issues, "PR-3", Some(10521)).unwrap();
+
+    assert!(r.data.is_none());
+    before.assert();
+
+    let result = fs::read_to_string("tests/assets/agile/rank_partial.json")
+        .expect("Unable to read in JSON file");
+
+    let after = mock("PUT", "/rest/agile/1.0/issue/rank")
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::Json(json!({
+            "issues": ["PR-1"],
+            "rankAfterIssue": "PR-3"
+        })))
+        .with_body(result)
+        .create();
+
+    let current = vec![
+        String::from("PR-1"),
+        String::from("PR-2"),
+        String::from("PR-3"),
+    ];
+    let desired = vec![
+        String::from("PR-2"),
+        String::from("PR-3"),
+        String::from("PR-1"),
+    ];
+
+    let r = Rank::reorder(&client, &current, &desired, None).unwrap();
+
+    assert_eq!(r.len(), 1);
+    assert_eq!(r[0].entries[2].status, 503);
+    after.assert();
+}
@@ -0,0 +1,171 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::agile::Sprint;
+use jirac::parse_timestamp;
+use jirac::Client;
+use jirac::Credentials;
+use mockito::{mock, Matcher};
+use serde_json::json;
+use std::fs;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_get() {
+    let result =
+        fs::read_to_string("tests/assets/agile/sprint.json").expect("Unable to read in JSON file");
+
+    let _m = mock("GET", "/rest/agile/1.0/sprint/37")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let s = Sprint::from_id(&client, 37).unwrap();
+
+    assert_eq!(s.data.name, "sprint 1");
+}
+
+#[test]
+fn test_start() {
+    let result =
+        fs::read_to_string("tests/assets/agile/sprint.json").expect("Unable to read in JSON file");
+
+    let _m = mock("POST", "/rest/agile/1.0/sprint/38")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::Json(json!({
+            "state": "active",
+            "startDate": "2015-04-11T15:22:00.000+10:00",
+            "endDate": "2015-04-20T01:22:00.000+10:00"
+        })))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let s = Sprint::start(
+        &client,
+        38,
+        "2015-04-11T15:22:00.000+10:00",
+        "2015-04-20T01:22:00.000+10:00",
+    )
+    .unwrap();
+
+    assert_eq!(s.data.origin_board_id, 5);
+}
+
+#[test]
+fn test_start_between() {
+    let result =
+        fs::read_to_string("tests/assets/agile/sprint.json").expect("Unable to read in JSON file");
+
+    let m = mock("POST", "/rest/agile/1.0/sprint/39")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::Json(json!({
+            "state": "active",
+            "startDate": "2015-04-11T15:22:00.000+10:00",
+            "endDate": "2015-04-20T01:22:00.000+10:00"
+        })))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let start = parse_timestamp("2015-04-11T15:22:00.000+10:00").unwrap();
+    let end = parse_timestamp("2015-04-20T01:22:00.000+10:00").unwrap();
+    let s = Sprint::start_between(&client, 39, &start, &end).unwrap();
+
+    assert_eq!(s.data.origin_board_id, 5);
+    m.assert();
+}
+
+#[test]
+fn test_issues() {
+    let result = fs::read_to_string("tests/assets/agile/sprint_issues.json")
+        .expect("Unable to read in JSON file");
+
+    let _m = mock("GET", "/rest/agile/1.0/sprint/39/issue")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::UrlEncoded("maxResults".into(), "50".into()))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let i = Sprint::issues(&client, 39, None).unwrap();
+
+    assert_eq!(i.data.pagination.total, 1);
+    assert_eq!(i.data.issues[0].key, "TEST-1");
+}
+
+#[test]
+fn test_move_issues_in_chunks() {
+    let issues: Vec<String> = (1..=60).map(|i| format!("TEST-{}", i)).collect();
+
+    let m = mock("POST", "/rest/agile/1.0/sprint/40/issue")
+        .with_status(204)
+        .expect(2)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    Sprint::move_issues(&client, 40, &issues).unwrap();
+
+    m.assert();
+}
+
+#[test]
+fn test_move_to_backlog() {
+    let issues = vec![String::from("TEST-1"), String::from("TEST-2")];
+
+    let m = mock("POST", "/rest/agile/1.0/backlog/issue")
+        .with_status(204)
+        .match_body(Matcher::Json(json!({ "issues": ["TEST-1", "TEST-2"] })))
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    Sprint::move_to_backlog(&client, &issues).unwrap();
+
+    m.assert();
+}
+
+#[test]
+fn test_delete() {
+    let m = mock("DELETE", "/rest/agile/1.0/sprint/41")
+        .with_status(204)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    Sprint::delete(&client, 41).unwrap();
+
+    m.assert();
+}
@@ -0,0 +1,113 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::cache::{Cache, DiskStore};
+use jirac::v2::{Priority, Resolution};
+use jirac::{Client, Credentials};
+use mockito::{mock, Matcher};
+use serde_json::Value;
+use std::time::Duration;
+
+// ============================================================================
+// Tests
+// ============================================================================
+fn client(cache: Cache) -> Client {
+    Client::builder(mockito::server_url(), Credentials::Pat("token".into()))
+        .middleware(cache)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_fresh_entries_are_not_fetched() {
+    let m = mock("GET", "/rest/api/2/priority/3")
+        .with_status(200)
+        .with_body(r#"{"id": "3", "name": "Major"}"#)
+        .expect(1)
+        .create();
+
+    let client = client(Cache::new().reference_data(Duration::from_secs(60)));
+    for _ in 0..3 {
+        assert_eq!(Priority::from_id(&client, "3").unwrap().data.name, "Major");
+    }
+
+    m.assert();
+}
+
+#[test]
+fn test_stale_entries_are_revalidated() {
+    let first = mock("GET", "/rest/api/2/resolution/1")
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"r1\"")
+        .with_body(r#"{"id": "1", "name": "Fixed"}"#)
+        .expect(1)
+        .create();
+    let revalidated = mock("GET", "/rest/api/2/resolution/1")
+        .match_header("if-none-match", "\"r1\"")
+        .with_status(304)
+        .expect(2)
+        .create();
+
+    let client = client(Cache::new().ttl("api/2/resolution", Duration::from_secs(0)));
+    for _ in 0..3 {
+        assert_eq!(
+            Resolution::from_id(&client, "1").unwrap().data.name,
+            "Fixed"
+        );
+    }
+
+    first.assert();
+    revalidated.assert();
+}
+
+#[test]
+fn test_uncached_endpoints() {
+    let m = mock("GET", "/rest/api/2/issue/CACHE-1")
+        .with_status(200)
+        .with_body("{}")
+        .expect(2)
+        .create();
+    let missing = mock("GET", "/rest/api/2/priority/404")
+        .with_status(404)
+        .expect(2)
+        .create();
+
+    let client = client(Cache::new().reference_data(Duration::from_secs(60)));
+    for _ in 0..2 {
+        client.get::<Value>("api/2/issue/CACHE-1").unwrap();
+        assert!(Priority::from_id(&client, "404").is_err());
+    }
+
+    m.assert();
+    missing.assert();
+}
+
+#[test]
+fn test_disk_store_outlives_client() {
+    let dir = std::env::temp_dir().join(format!("jirac-cache-test-{}", std::process::id()));
+    let store = DiskStore::new(&dir);
+    let m = mock("GET", "/rest/api/2/priority/4")
+        .with_status(200)
+        .with_body(r#"{"id": "4", "name": "Minor"}"#)
+        .expect(1)
+        .create();
+
+    for _ in 0..2 {
+        let cache = Cache::with_store(store.clone()).reference_data(Duration::from_secs(60));
+        assert_eq!(
+            Priority::from_id(&client(cache), "4").unwrap().data.name,
+            "Minor"
+        );
+    }
+    m.assert();
+
+    Cache::with_store(store).clear().unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+}
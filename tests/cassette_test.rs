@@ -0,0 +1,152 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::cassette::{Cassette, Mode};
+use jirac::{Client, Credentials, Error};
+use mockito::{mock, Matcher};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+
+// ============================================================================
+// Tests
+// ============================================================================
+fn cassette_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir()
+        .join(format!("jirac-cassettes-{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn client(cassette: Cassette) -> Client {
+    Client::new(
+        mockito::server_url(),
+        Credentials::new_basic("jdoe", "super-secret").unwrap(),
+    )
+    .with_cassette(cassette)
+}
+
+fn record_then_replay(name: &str) {
+    let path = cassette_path(name);
+    let issue = mock("GET", "/rest/api/2/issue/CAS-1")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("set-cookie", "JSESSIONID=abc; Path=/")
+        .with_body(r#"{"key": "CAS-1"}"#)
+        .expect(1)
+        .create();
+    let search = mock("POST", "/rest/api/2/search")
+        .match_body(Matcher::Json(json!({"jql": "project = CAS"})))
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"errorMessages": ["bad jql"], "errors": {}}"#)
+        .expect(1)
+        .create();
+
+    let recorder = client(Cassette::auto(&path).unwrap());
+    assert_eq!(recorder.cassette().unwrap().mode, Mode::Record);
+    recorder.get::<Value>("api/2/issue/CAS-1").unwrap();
+    assert!(recorder
+        .post::<_, Value>("api/2/search", json!({"jql": "project = CAS"}))
+        .is_err());
+
+    let written = fs::read_to_string(&path).unwrap();
+    assert!(!written.contains("super-secret"));
+    assert!(!written.contains("amRvZTpzdXBlci1zZWNyZXQ"));
+    assert!(!written.contains("JSESSIONID=abc"));
+
+    // Replaying sends nothing, the mocks were hit once while recording
+    let player = client(Cassette::auto(&path).unwrap());
+    assert_eq!(player.cassette().unwrap().mode, Mode::Replay);
+    assert_eq!(
+        player.get::<Value>("api/2/issue/CAS-1").unwrap().data["key"],
+        "CAS-1"
+    );
+    match player.post::<_, Value>("api/2/search", json!({"jql": "project = CAS"})) {
+        Err(Error::Fault { errors, .. }) => assert_eq!(errors.error_messages, vec!["bad jql"]),
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+
+    issue.assert();
+    search.assert();
+}
+
+#[test]
+fn test_record_then_replay_json() {
+    record_then_replay("record.json");
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_record_then_replay_yaml() {
+    record_then_replay("record.yaml");
+}
+
+#[test]
+fn test_replay_skips_login() {
+    let path = cassette_path("login.json");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(
+        &path,
+        json!({"interactions": [
+            {
+                "request": {"method": "GET", "uri": "/rest/api/2/myself"},
+                "response": {"status": 401, "body": ""}
+            },
+            {
+                "request": {"method": "GET", "uri": "/rest/api/2/myself"},
+                "response": {
+                    "status": 200,
+                    "headers": {"content-type": "application/json"},
+                    "body": "{\"name\": \"jdoe\"}"
+                }
+            }
+        ]})
+        .to_string(),
+    )
+    .unwrap();
+
+    // Logging in would fail, the host doesn't exist
+    let player = Client::new(
+        "http://jira.invalid",
+        Credentials::new_session("jdoe", "super-secret").unwrap(),
+    )
+    .with_cassette(Cassette::replay(&path).unwrap());
+
+    let me = player.get::<Value>("api/2/myself").unwrap().data;
+    assert_eq!(me["name"], "jdoe");
+}
+
+#[test]
+fn test_replay_unmatched() {
+    let path = cassette_path("unmatched.json");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(
+        &path,
+        json!({"interactions": [{
+            "request": {"method": "GET", "uri": "/rest/api/2/myself"},
+            "response": {"status": 200, "body": "{}"}
+        }]})
+        .to_string(),
+    )
+    .unwrap();
+
+    let player = client(Cassette::replay(&path).unwrap());
+    player.get::<Value>("api/2/myself").unwrap();
+
+    // Each recorded interaction is only replayed once
+    match player.get::<Value>("api/2/myself") {
+        Err(Error::UnmatchedRequest { method, uri }) => {
+            assert_eq!(method, "GET");
+            assert_eq!(uri, "/rest/api/2/myself");
+        }
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+}
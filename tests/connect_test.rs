@@ -0,0 +1,83 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+extern crate reqwest;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::connect::{install_key_from, query_string_hash, Claims, ConnectApp};
+use jirac::Error;
+use jirac::{Client, Credentials};
+use mockito::{mock, Matcher};
+use serde_json::Value;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_requests_carry_jwt() {
+    let m = mock("GET", "/rest/api/2/search")
+        .match_query(Matcher::UrlEncoded("jql".into(), "project = TEST".into()))
+        .match_header(
+            "authorization",
+            Matcher::Regex(r"^JWT [\w-]+\.[\w-]+\.[\w-]+$".to_string()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create();
+
+    let client = Client::new(
+        mockito::server_url(),
+        Credentials::new_connect("my-app", "shared-secret").unwrap(),
+    );
+
+    client
+        .get::<Value>("api/2/search?jql=project%20%3D%20TEST")
+        .unwrap();
+    m.assert();
+}
+
+#[test]
+fn test_token_is_bound_to_request() {
+    let app = ConnectApp::new("my-app", "shared-secret");
+    let base = "https://x.atlassian.net";
+    let token = app
+        .token("GET", "https://x.atlassian.net/rest/api/2/myself", base)
+        .unwrap();
+    let claims = Claims::decode_unverified(&token).unwrap();
+
+    assert_eq!(claims.iss, "my-app");
+    assert_eq!(
+        claims.qsh.unwrap(),
+        query_string_hash("GET", "/rest/api/2/myself", base).unwrap()
+    );
+}
+
+#[test]
+fn test_install_key_from() {
+    let pem = "-----BEGIN PUBLIC KEY-----\nMIIB\n-----END PUBLIC KEY-----\n";
+    let found = mock("GET", "/keys/0a1b-2c3d")
+        .with_status(200)
+        .with_body(pem)
+        .create();
+    let missing = mock("GET", "/keys/gone").with_status(404).create();
+
+    let http = reqwest::Client::new();
+    let keys = format!("{}/keys", mockito::server_url());
+
+    assert_eq!(install_key_from(&http, &keys, "0a1b-2c3d").unwrap(), pem);
+    assert!(matches!(
+        install_key_from(&http, &keys, "gone"),
+        Err(Error::InvalidJwt(_))
+    ));
+    assert!(matches!(
+        install_key_from(&http, &keys, "../admin"),
+        Err(Error::InvalidJwt(_))
+    ));
+    found.assert();
+    missing.assert();
+}
@@ -0,0 +1,61 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::providers::{Env, Netrc, Profiles};
+use jirac::{Client, CredentialProvider, Credentials, Error};
+use mockito::mock;
+use serde_json::Value;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_pat_bearer_auth() {
+    let m = mock("GET", "/rest/api/2/serverInfo")
+        .match_header("authorization", "Bearer personal-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create();
+
+    let client = Client::new(
+        mockito::server_url(),
+        Credentials::new_pat("personal-token").unwrap(),
+    );
+
+    client.get::<Value>("api/2/serverInfo").unwrap();
+    m.assert();
+}
+
+#[test]
+fn test_provider_chain() {
+    let profiles = Profiles::parse(
+        "[local]\nhost = \"http://127.0.0.1:1234\"\nuser = \"jdoe\"\ntoken = \"secret\"",
+    )
+    .unwrap();
+
+    let providers: Vec<Box<dyn CredentialProvider>> = vec![
+        Box::new(Env::with_prefix("JIRAC_CHAIN_UNSET")),
+        Box::new(Netrc::from_path("/nonexistent/.netrc")),
+        Box::new(profiles),
+    ];
+
+    match Credentials::from_provider(&providers, "http://127.0.0.1:1234").unwrap() {
+        Credentials::Basic(user, token) => {
+            assert_eq!(user, "jdoe");
+            assert_eq!(token, "secret");
+        }
+        other => panic!("unexpected credentials {:?}", other),
+    }
+
+    match Credentials::from_provider(&providers, "https://other.example.com") {
+        Err(Error::MissingCredentials(host)) => assert_eq!(host, "https://other.example.com"),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
@@ -0,0 +1,157 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::middleware::{Middleware, Next, Request, Response};
+use jirac::{Client, Credentials, Error, Result};
+use mockito::{mock, Matcher};
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+// ============================================================================
+// Tests
+// ============================================================================
+/// Records the order it sees requests and responses in
+#[derive(Debug)]
+struct Trace {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Middleware for Trace {
+    fn handle(&self, request: Request, next: Next) -> Result<Response> {
+        self.log.lock().unwrap().push(format!("{} >", self.name));
+        let response = next.run(request);
+        self.log.lock().unwrap().push(format!("{} <", self.name));
+
+        response
+    }
+}
+
+#[derive(Debug)]
+struct Header(&'static str, &'static str);
+
+impl Middleware for Header {
+    fn handle(&self, mut request: Request, next: Next) -> Result<Response> {
+        request
+            .headers
+            .insert(self.0, HeaderValue::from_static(self.1));
+
+        next.run(request)
+    }
+}
+
+/// Answers every request itself
+#[derive(Debug)]
+struct Canned;
+
+impl Middleware for Canned {
+    fn handle(&self, request: Request, _: Next) -> Result<Response> {
+        Ok(Response::new(
+            StatusCode::OK,
+            format!(r#"{{"url": "{}"}}"#, request.url),
+        ))
+    }
+}
+
+/// Turns responses into 404s
+#[derive(Debug)]
+struct Missing;
+
+impl Middleware for Missing {
+    fn handle(&self, request: Request, next: Next) -> Result<Response> {
+        let mut response = next.run(request)?;
+        response.status = StatusCode::NOT_FOUND;
+
+        Ok(response)
+    }
+}
+
+#[test]
+fn test_headers_and_order() {
+    let m = mock("GET", "/rest/api/2/myself")
+        .match_header("x-audit", "sync")
+        .match_header("authorization", "Bearer custom")
+        .with_status(200)
+        .with_body("{}")
+        .create();
+
+    let log = Arc::new(Mutex::new(vec![]));
+    let client = Client::builder(mockito::server_url(), Credentials::Pat("token".into()))
+        .middleware(Trace {
+            name: "outer",
+            log: log.clone(),
+        })
+        .middleware(Header("x-audit", "sync"))
+        .middleware(Header("authorization", "Bearer custom"))
+        .build()
+        .unwrap()
+        .with_middleware(Trace {
+            name: "inner",
+            log: log.clone(),
+        });
+
+    client.get::<Value>("api/2/myself").unwrap();
+
+    m.assert();
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["outer >", "inner >", "inner <", "outer <"]
+    );
+}
+
+#[test]
+fn test_short_circuit() {
+    let m = mock("GET", Matcher::Any).expect(0).create();
+
+    let client = Client::builder(mockito::server_url(), Credentials::Pat("token".into()))
+        .middleware(Canned)
+        .build()
+        .unwrap();
+    let data = client.get::<Value>("api/2/short-circuit").unwrap().data;
+
+    assert_eq!(
+        data["url"],
+        format!("{}/rest/api/2/short-circuit", mockito::server_url())
+    );
+    m.assert();
+}
+
+#[test]
+fn test_change_response() {
+    let _m = mock("GET", "/rest/api/2/changed")
+        .with_status(200)
+        .with_body("{}")
+        .create();
+
+    let client = Client::new(mockito::server_url(), Credentials::Pat("token".into()))
+        .with_middleware(Missing);
+
+    match client.get::<Value>("api/2/changed") {
+        Err(Error::NotFound(_)) => {}
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+}
+
+#[test]
+fn test_credentials_still_applied() {
+    let m = mock("GET", "/rest/api/2/credentials")
+        .match_header(AUTHORIZATION.as_str(), "Bearer token")
+        .match_header("x-audit", "sync")
+        .with_status(200)
+        .with_body("{}")
+        .create();
+
+    let client = Client::new(mockito::server_url(), Credentials::Pat("token".into()))
+        .with_middleware(Header("x-audit", "sync"));
+    client.get::<Value>("api/2/credentials").unwrap();
+
+    m.assert();
+}
@@ -0,0 +1,124 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+extern crate openssl;
+extern crate reqwest;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::oauth1::{OAuth1Flow, RequestToken};
+use jirac::v2::ServerInfo;
+use jirac::Client;
+use jirac::Credentials;
+use mockito::{mock, Matcher};
+use openssl::rsa::Rsa;
+
+// ============================================================================
+// Tests
+// ============================================================================
+fn private_key() -> Vec<u8> {
+    Rsa::generate(2048).unwrap().private_key_to_pem().unwrap()
+}
+
+#[test]
+fn test_requests_are_signed() {
+    let m = mock("GET", "/rest/api/2/serverInfo")
+        .match_header(
+            "authorization",
+            Matcher::Regex(
+                r#"^OAuth oauth_consumer_key="jirac", oauth_nonce="[0-9a-f]{32}", oauth_signature="[^"]+", oauth_signature_method="RSA-SHA1", oauth_timestamp="\d+", oauth_token="access", oauth_version="1.0"$"#
+                    .into(),
+            ),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"version": "8.5.4"}"#)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_oauth1("jirac", private_key(), "access", "secret").unwrap();
+    let client = Client::new(url, creds);
+
+    let info = ServerInfo::get(&client).unwrap().data;
+
+    assert_eq!(info.version, "8.5.4");
+    m.assert();
+}
+
+#[test]
+fn test_oauth1_flow() {
+    let _request = mock(
+        "POST",
+        Matcher::Regex("/plugins/servlet/oauth/request-token$".into()),
+    )
+    .match_header(
+        "authorization",
+        Matcher::Regex(r#"oauth_callback="oob""#.into()),
+    )
+    .with_status(200)
+    .with_body("oauth_token=request&oauth_token_secret=request-secret")
+    .create();
+
+    let _access = mock(
+        "POST",
+        Matcher::Regex("/plugins/servlet/oauth/access-token$".into()),
+    )
+    .match_header(
+        "authorization",
+        Matcher::Regex(r#"oauth_token="request".*oauth_verifier="abc123""#.into()),
+    )
+    .with_status(200)
+    .with_body("oauth_token=access&oauth_token_secret=access-secret")
+    .create();
+
+    // The flow goes through the proxy configured on the client
+    let url = "http://jira.invalid";
+    let client = Client::builder(url, Credentials::Pat("token".into()))
+        .proxy(mockito::server_url())
+        .build()
+        .unwrap();
+    let flow = OAuth1Flow::new(url, "jirac", private_key()).unwrap();
+
+    let token = flow.request_token(&client.client).unwrap();
+    assert_eq!(
+        token,
+        RequestToken {
+            token: "request".to_string(),
+            secret: "request-secret".to_string(),
+        }
+    );
+    assert_eq!(
+        flow.authorize_url(&token),
+        format!(
+            "{}/plugins/servlet/oauth/authorize?oauth_token=request",
+            url
+        )
+    );
+
+    match flow.access_token(&client.client, &token, "abc123").unwrap() {
+        Credentials::OAuth1(o) => {
+            assert_eq!(o.token, "access");
+            assert_eq!(o.token_secret, "access-secret");
+        }
+        c => panic!("expected OAuth1 credentials, got {:?}", c),
+    }
+}
+
+#[test]
+fn test_oauth1_problem() {
+    let _m = mock("POST", "/plugins/servlet/oauth/request-token")
+        .with_status(401)
+        .with_body("oauth_problem=consumer_key_unknown")
+        .create();
+
+    let url = &mockito::server_url();
+    let flow = OAuth1Flow::new(url.as_str(), "unknown", private_key()).unwrap();
+
+    match flow.request_token(&reqwest::Client::new()) {
+        Err(jirac::Error::OAuthProblem(p)) => assert_eq!(p, "consumer_key_unknown"),
+        r => panic!("expected an OAuth problem, got {:?}", r.map(|_| ())),
+    }
+}
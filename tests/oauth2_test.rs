@@ -0,0 +1,169 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+extern crate reqwest;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::oauth2::{MemoryStore, OAuth2Config, Pkce, RefreshingToken, Token, TokenStore};
+use jirac::Client;
+use jirac::Credentials;
+use mockito::{mock, Matcher};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ============================================================================
+// Tests
+// ============================================================================
+fn config() -> OAuth2Config {
+    let mut config = OAuth2Config::new("client", "https://example.com/callback", vec![]);
+    config.token_url = format!("{}/oauth/token", mockito::server_url());
+    config
+}
+
+fn token(access_token: &str, expires_in: i64) -> Token {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    Token {
+        access_token: access_token.to_string(),
+        refresh_token: Some("r1".to_string()),
+        expires_at: Some((now + expires_in) as u64),
+        scope: String::new(),
+    }
+}
+
+fn refresh_mock() -> mockito::Mock {
+    mock("POST", "/oauth/token")
+        .match_body(Matcher::PartialJson(json!({
+            "grant_type": "refresh_token",
+            "client_id": "client",
+            "refresh_token": "r1"
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"access_token": "fresh", "refresh_token": "r2", "expires_in": 3600}"#)
+        .create()
+}
+
+#[test]
+fn test_exchange_code_with_pkce() {
+    let pkce = Pkce::new().unwrap();
+
+    let m = mock("POST", "/oauth/token")
+        .match_body(Matcher::Json(json!({
+            "grant_type": "authorization_code",
+            "client_id": "client",
+            "code": "abc",
+            "redirect_uri": "https://example.com/callback",
+            "code_verifier": pkce.verifier
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"access_token": "a", "refresh_token": "r", "expires_in": 3600, "scope": "read:jira-work"}"#)
+        .create();
+
+    let token = config()
+        .exchange_code(&reqwest::Client::new(), "abc", Some(&pkce))
+        .unwrap();
+
+    assert_eq!(token.access_token, "a");
+    assert_eq!(token.refresh_token.unwrap(), "r");
+    assert!(token.expires_at.is_some());
+    m.assert();
+}
+
+#[test]
+fn test_refresh_before_expiry() {
+    let refresh = refresh_mock();
+    let request = mock("GET", "/rest/api/2/serverInfo")
+        .match_header("authorization", "Bearer fresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create();
+
+    let store = Arc::new(MemoryStore::default());
+    let provider = RefreshingToken::with_token(config(), store.clone(), token("old", 10)).unwrap();
+    let client = Client::new(
+        mockito::server_url(),
+        Credentials::new_oauth2(provider).unwrap(),
+    );
+
+    client.get::<Value>("api/2/serverInfo").unwrap();
+
+    // The rotated refresh token has to be kept for next time
+    assert_eq!(store.load().unwrap().unwrap().refresh_token.unwrap(), "r2");
+    refresh.assert();
+    request.assert();
+}
+
+#[test]
+fn test_refresh_through_proxy() {
+    let refresh = mock("POST", Matcher::Regex("/oauth/token$".to_string()))
+        .match_header("proxy-authorization", "Basic cHJveHk6c2VjcmV0")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"access_token": "fresh", "expires_in": 3600}"#)
+        .create();
+    let request = mock("GET", Matcher::Regex("/rest/api/2/proxied$".to_string()))
+        .match_header("authorization", "Bearer fresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create();
+
+    let mut config = config();
+    config.token_url = "http://auth.invalid/oauth/token".to_string();
+    let provider =
+        RefreshingToken::with_token(config, MemoryStore::default(), token("old", 10)).unwrap();
+    let client = Client::builder(
+        "http://jira.invalid",
+        Credentials::new_oauth2(provider).unwrap(),
+    )
+    .proxy(mockito::server_url())
+    .proxy_credentials("proxy", "secret")
+    .build()
+    .unwrap();
+
+    client.get::<Value>("api/2/proxied").unwrap();
+
+    refresh.assert();
+    request.assert();
+}
+
+#[test]
+fn test_refresh_on_unauthorized() {
+    let refresh = refresh_mock();
+    let rejected = mock("GET", "/rest/api/2/myself")
+        .match_header("authorization", "Bearer revoked")
+        .with_status(401)
+        .create();
+    let accepted = mock("GET", "/rest/api/2/myself")
+        .match_header("authorization", "Bearer fresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"accountId": "5b10a2844c20165700ede21g"}"#)
+        .create();
+
+    let provider =
+        RefreshingToken::with_token(config(), MemoryStore::default(), token("revoked", 3600))
+            .unwrap();
+    let client = Client::new(
+        mockito::server_url(),
+        Credentials::new_oauth2(provider).unwrap(),
+    );
+
+    let me = client.get::<Value>("api/2/myself").unwrap().data;
+
+    assert_eq!(me["accountId"], "5b10a2844c20165700ede21g");
+    refresh.assert();
+    rejected.assert();
+    accepted.assert();
+}
@@ -0,0 +1,164 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::session::{Session, SessionCookie};
+use jirac::{Client, Credentials, Error};
+use mockito::{mock, Matcher};
+use serde_json::{json, Value};
+
+// ============================================================================
+// Tests
+// ============================================================================
+fn login_mock(value: &str) -> mockito::Mock {
+    mock("POST", "/rest/auth/1/session")
+        .match_body(Matcher::Json(json!({
+            "username": "jdoe",
+            "password": "secret"
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "session": {"name": "JSESSIONID", "value": value},
+                "loginInfo": {"loginCount": 1, "failedLoginCount": 0}
+            })
+            .to_string(),
+        )
+        .create()
+}
+
+fn cookie(value: &str) -> SessionCookie {
+    SessionCookie {
+        name: "JSESSIONID".to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[test]
+fn test_login_once_and_reuse_cookie() {
+    let login = login_mock("abc").expect(1);
+    let request = mock("GET", "/rest/api/2/myself")
+        .match_header("cookie", "JSESSIONID=abc")
+        .match_header("authorization", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .expect(2)
+        .create();
+
+    let client = Client::new(
+        mockito::server_url(),
+        Credentials::new_session("jdoe", "secret").unwrap(),
+    );
+
+    client.get::<Value>("api/2/myself").unwrap();
+    client.get::<Value>("api/2/myself").unwrap();
+
+    login.assert();
+    request.assert();
+}
+
+#[test]
+fn test_relogin_on_unauthorized() {
+    let expired = mock("GET", "/rest/api/2/serverInfo")
+        .match_header("cookie", "JSESSIONID=expired")
+        .with_status(401)
+        .create();
+    let login = login_mock("fresh");
+    let request = mock("GET", "/rest/api/2/serverInfo")
+        .match_header("cookie", "JSESSIONID=fresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create();
+
+    let session = Session::new("jdoe", "secret").with_cookie(cookie("expired"));
+    let client = Client::new(mockito::server_url(), Credentials::Session(session.clone()));
+
+    client.get::<Value>("api/2/serverInfo").unwrap();
+
+    assert_eq!(session.cookie(), Some(cookie("fresh")));
+    expired.assert();
+    login.assert();
+    request.assert();
+}
+
+#[test]
+fn test_login_captcha() {
+    let login = mock("POST", "/rest/auth/1/session")
+        .match_body(Matcher::PartialJson(json!({"username": "captcha"})))
+        .with_status(403)
+        .with_header("x-seraph-loginreason", "AUTHENTICATION_DENIED")
+        .create();
+
+    let client = Client::new(
+        mockito::server_url(),
+        Credentials::new_session("captcha", "secret").unwrap(),
+    );
+
+    match client.get::<Value>("api/2/field") {
+        Err(Error::Forbidden(_)) => {}
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+    login.assert();
+}
+
+#[test]
+fn test_login_failures_keep_their_status() {
+    let login = mock("POST", "/rest/auth/1/session")
+        .match_body(Matcher::PartialJson(json!({"username": "down"})))
+        .with_status(503)
+        .with_body("maintenance")
+        .create();
+
+    let client = Client::new(
+        mockito::server_url(),
+        Credentials::new_session("down", "secret").unwrap(),
+    );
+
+    match client.get::<Value>("api/2/field") {
+        Err(Error::ServerError(context)) => assert_eq!(context.body, "maintenance"),
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+    login.assert();
+
+    let login = mock("POST", "/rest/auth/1/session")
+        .match_body(Matcher::PartialJson(json!({"username": "wrong"})))
+        .with_status(401)
+        .create();
+
+    let client = Client::new(
+        mockito::server_url(),
+        Credentials::new_session("wrong", "secret").unwrap(),
+    );
+
+    match client.get::<Value>("api/2/field") {
+        Err(Error::Unauthorized(_)) => {}
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+    login.assert();
+}
+
+#[test]
+fn test_logout() {
+    let logout = mock("DELETE", "/rest/auth/1/session")
+        .match_header("cookie", "JSESSIONID=bye")
+        .with_status(204)
+        .create();
+
+    let session = Session::new("jdoe", "secret").with_cookie(cookie("bye"));
+    let client = Client::new(mockito::server_url(), Credentials::Session(session.clone()));
+
+    client.logout().unwrap();
+    // A second logout has nothing to end
+    client.logout().unwrap();
+
+    assert_eq!(session.cookie(), None);
+    logout.assert();
+}
@@ -0,0 +1,249 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::testing::FakeJira;
+use jirac::v2::{Issue, Version};
+use jirac::{Client, Deployment, Error};
+use serde_json::{json, Value};
+
+// ============================================================================
+// Tests
+// ============================================================================
+fn create(client: &Client, summary: &str) -> String {
+    let created = client
+        .post::<_, Value>(
+            "api/2/issue",
+            json!({"fields": {
+                "project": {"key": "TEST"},
+                "issuetype": {"name": "Task"},
+                "summary": summary,
+                "labels": ["fake"]
+            }}),
+        )
+        .unwrap()
+        .data;
+
+    created["key"].as_str().unwrap().to_string()
+}
+
+fn fetch(client: &Client, key: &str) -> Issue {
+    Issue::from_key(client, key, None, None, None, None, None)
+        .unwrap()
+        .data
+}
+
+#[test]
+fn test_create_then_get() {
+    let jira = FakeJira::start();
+    jira.add_project("TEST", "Test project");
+    let client = jira.client();
+
+    assert_eq!(create(&client, "First"), "TEST-1");
+    assert_eq!(create(&client, "Second"), "TEST-2");
+
+    let issue = fetch(&client, "TEST-2");
+    assert_eq!(issue.fields.summary, "Second");
+    assert_eq!(issue.fields.labels, vec!["fake"]);
+    assert_eq!(issue.fields.issue_type.unwrap().name, "Task");
+    assert_eq!(issue.fields.project.unwrap().key, "TEST");
+    assert_eq!(issue.fields.status.unwrap().name, "To Do");
+}
+
+#[test]
+fn test_deleted_keys_not_reused() {
+    let jira = FakeJira::start();
+    jira.add_project("TEST", "Test project");
+    let client = jira.client();
+
+    create(&client, "First");
+    let newest = create(&client, "Second");
+    client
+        .delete::<()>(&format!("api/2/issue/{}", newest))
+        .unwrap();
+
+    assert_eq!(create(&client, "Third"), "TEST-3");
+}
+
+#[test]
+fn test_search_non_ascii() {
+    let jira = FakeJira::start();
+    jira.add_project("TEST", "Test project");
+    let client = jira.client();
+
+    create(&client, "Trip to İstanbul");
+    create(&client, "Trip to Ankara");
+
+    let found = client
+        .post::<_, Value>(
+            "api/2/search",
+            json!({"jql": "summary ~ \"İstanbul\" and project = TEST"}),
+        )
+        .unwrap()
+        .data;
+
+    assert_eq!(found["total"], 1);
+    assert_eq!(found["issues"][0]["key"], "TEST-1");
+}
+
+#[test]
+fn test_edit_issue() {
+    let jira = FakeJira::start();
+    jira.add_project("TEST", "Test project");
+    let client = jira.client();
+    let key = create(&client, "Before");
+
+    client
+        .put::<_, Value>(
+            &format!("api/2/issue/{}", key),
+            json!({
+                "fields": {"summary": "After"},
+                "update": {"labels": [{"add": "edited"}, {"remove": "fake"}]}
+            }),
+        )
+        .unwrap();
+
+    let issue = fetch(&client, &key);
+    assert_eq!(issue.fields.summary, "After");
+    assert_eq!(issue.fields.labels, vec!["edited"]);
+}
+
+#[test]
+fn test_transition() {
+    let jira = FakeJira::start();
+    jira.add_project("TEST", "Test project");
+    let client = jira.client();
+    let key = create(&client, "Workflow");
+
+    let transitions = client
+        .get::<Value>(&format!("api/2/issue/{}/transitions", key))
+        .unwrap()
+        .data;
+    let done = transitions["transitions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["to"]["name"] == "Done")
+        .unwrap()["id"]
+        .clone();
+
+    client
+        .post::<_, Value>(
+            &format!("api/2/issue/{}/transitions", key),
+            json!({"transition": {"id": done}}),
+        )
+        .unwrap();
+
+    let issue = fetch(&client, &key);
+    assert_eq!(issue.fields.status.unwrap().name, "Done");
+    assert_eq!(issue.fields.resolution.unwrap().name, "Done");
+    assert!(issue.fields.resolution_date.is_some());
+
+    // Done is not reachable from Done
+    match client.post::<_, Value>(
+        &format!("api/2/issue/{}/transitions", key),
+        json!({"transition": {"id": done}}),
+    ) {
+        Err(Error::Fault { errors, .. }) => assert_eq!(
+            errors.error_messages,
+            vec!["Transition id '31' is not valid for this issue."]
+        ),
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+}
+
+#[test]
+fn test_search_pagination() {
+    let jira = FakeJira::start();
+    jira.add_project("TEST", "Test project");
+    jira.add_project("OTHER", "Other project");
+    let client = jira.client();
+
+    for i in 0..5 {
+        create(&client, &format!("Issue {}", i));
+    }
+
+    let page = client
+        .get::<Value>(
+            "api/2/search?jql=project%20%3D%20TEST%20ORDER%20BY%20key&startAt=2&maxResults=2",
+        )
+        .unwrap()
+        .data;
+    let keys: Vec<&str> = page["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["key"].as_str().unwrap())
+        .collect();
+
+    assert_eq!(page["total"], 5);
+    assert_eq!(page["startAt"], 2);
+    assert_eq!(keys, vec!["TEST-3", "TEST-4"]);
+
+    let empty = client
+        .post::<_, Value>("api/2/search", json!({"jql": "project = OTHER"}))
+        .unwrap()
+        .data;
+    assert_eq!(empty["total"], 0);
+}
+
+#[test]
+fn test_errors() {
+    let jira = FakeJira::start();
+    jira.add_project("TEST", "Test project");
+    let client = jira.client();
+
+    match client.post::<_, Value>(
+        "api/2/issue",
+        json!({"fields": {"project": {"key": "NOPE"}, "issuetype": {"name": "Task"}}}),
+    ) {
+        Err(Error::Fault { code, errors, .. }) => {
+            assert_eq!(code.as_u16(), 400);
+            assert_eq!(errors.errors["project"], "valid project is required");
+            assert_eq!(
+                errors.errors["summary"],
+                "You must specify a summary of the issue."
+            );
+        }
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+
+    match client.get::<Value>("api/2/issue/TEST-99") {
+        Err(Error::NotFound(_)) => {}
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+
+    match client.get::<Value>("api/2/search?jql=reporter%20%3D%20me") {
+        Err(Error::Fault { errors, .. }) => assert_eq!(errors.error_messages.len(), 1),
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+}
+
+#[test]
+fn test_versions_and_server_info() {
+    let jira = FakeJira::start();
+    let project = jira.add_project("TEST", "Test project");
+    let mut client = jira.client();
+
+    let mut version = Version::new();
+    version.name = "1.0".to_string();
+    version.project_id = project["id"].as_str().unwrap().parse().unwrap();
+
+    let created = version.create(&client).unwrap().data;
+    assert_eq!(created.name, "1.0");
+    assert_eq!(
+        Version::from_id(&client, created.id.as_str())
+            .unwrap()
+            .data
+            .name,
+        "1.0"
+    );
+
+    let capabilities = client.detect_capabilities().unwrap();
+    assert_eq!(capabilities.deployment, Deployment::Server);
+    assert_eq!(jira.requests().len(), 3);
+}
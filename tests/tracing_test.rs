@@ -0,0 +1,180 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::session::{Session, SessionCookie};
+use jirac::{Client, Credentials, Logging};
+use mockito::{mock, Matcher};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+// ============================================================================
+// Tests
+// ============================================================================
+type Fields = BTreeMap<String, String>;
+
+/// Keeps the fields of every span and event it sees
+#[derive(Clone, Default)]
+struct Capture {
+    spans: Arc<Mutex<Vec<Fields>>>,
+    events: Arc<Mutex<Vec<Fields>>>,
+}
+
+struct Visitor<'a>(&'a mut Fields);
+
+impl<'a> Visit for Visitor<'a> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &Attributes) -> Id {
+        let mut fields = Fields::new();
+        fields.insert("name".to_string(), attrs.metadata().name().to_string());
+        attrs.record(&mut Visitor(&mut fields));
+
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(fields);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, id: &Id, values: &Record) {
+        let mut spans = self.spans.lock().unwrap();
+        values.record(&mut Visitor(&mut spans[id.into_u64() as usize - 1]));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event) {
+        let mut fields = Fields::new();
+        event.record(&mut Visitor(&mut fields));
+        self.events.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn test_span_fields() {
+    let comment_path = Matcher::Regex(r"^/rest/api/2/issue/TRACE-7/comment/10010\?".to_string());
+    let _expired = mock("GET", comment_path.clone())
+        .match_header("cookie", "JSESSIONID=expired")
+        .with_status(401)
+        .create();
+    let _login = mock("POST", "/rest/auth/1/session")
+        .with_status(200)
+        .with_body(r#"{"session": {"name": "JSESSIONID", "value": "fresh"}}"#)
+        .create();
+    let _comment = mock("GET", comment_path.clone())
+        .match_header("cookie", "JSESSIONID=fresh")
+        .with_status(200)
+        .with_body(r#"{"id": "10010"}"#)
+        .create();
+
+    let session = Session::new("jdoe", "secret").with_cookie(SessionCookie {
+        name: "JSESSIONID".to_string(),
+        value: "expired".to_string(),
+    });
+    let client = Client::new(mockito::server_url(), Credentials::Session(session));
+
+    let capture = Capture::default();
+    tracing::subscriber::with_default(capture.clone(), || {
+        client
+            .get::<Value>("api/2/issue/TRACE-7/comment/10010?expand=renderedBody")
+            .unwrap();
+    });
+
+    let spans = capture.spans.lock().unwrap();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0]["name"], "jira.request");
+    assert_eq!(spans[0]["method"], "GET");
+    assert_eq!(spans[0]["endpoint"], "api/2/issue/{key}/comment/{id}");
+    assert_eq!(spans[0]["status"], "200");
+    assert_eq!(spans[0]["retries"], "1");
+    assert_eq!(spans[0]["response_size"], "15");
+    assert!(spans[0].contains_key("latency_ms"));
+}
+
+#[test]
+fn test_redacted_logging() {
+    let _m = mock("POST", "/rest/api/2/trace/redacted")
+        .match_body(Matcher::Any)
+        .with_status(200)
+        .with_body(r#"{"token": "from-server", "ok": true}"#)
+        .create();
+
+    let client = Client::builder(
+        mockito::server_url(),
+        Credentials::Basic("jdoe".into(), "secret".into()),
+    )
+    .logging(Logging::new().log_bodies(true).redact_field("pin"))
+    .build()
+    .unwrap();
+
+    let capture = Capture::default();
+    tracing::subscriber::with_default(capture.clone(), || {
+        client
+            .post::<_, Value>(
+                "api/2/trace/redacted",
+                json!({"password": "hunter2", "pin": "1234", "summary": "kept"}),
+            )
+            .unwrap();
+    });
+
+    let events = capture.events.lock().unwrap();
+    let sent = events
+        .iter()
+        .find(|e| e["message"] == "sending request")
+        .unwrap();
+    let received = events
+        .iter()
+        .find(|e| e["message"] == "received response")
+        .unwrap();
+
+    assert!(sent["body"].contains("kept"));
+    assert!(!sent["body"].contains("hunter2"));
+    assert!(!sent["body"].contains("1234"));
+    assert!(!received["body"].contains("from-server"));
+    assert_eq!(received["status"], "200");
+
+    // Basic credentials are added after logging, a header set by hand is
+    // redacted
+    let mut client = client;
+    client.add_header("authorization", "Bearer by-hand".to_string());
+    let capture = Capture::default();
+    tracing::subscriber::with_default(capture.clone(), || {
+        client
+            .post::<_, Value>("api/2/trace/redacted", json!({}))
+            .unwrap();
+    });
+
+    let events = capture.events.lock().unwrap();
+    assert!(events
+        .iter()
+        .all(|e| !format!("{:?}", e).contains("by-hand")));
+    assert!(events
+        .iter()
+        .all(|e| !format!("{:?}", e).contains("amRvZTpzZWNyZXQ")));
+}
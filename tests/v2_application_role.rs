@@ -148,9 +148,15 @@ fn test_put_with_key_unauthorized() {
     let client = Client::new(url, creds);
 
     match a.update(&client, None) {
-        Err(Error::Unauthorized) => assert!(true),
-        _ => assert!(false),
-    };
+        Err(Error::Unauthorized(context)) => {
+            assert_eq!(context.method, "PUT");
+            assert_eq!(context.status.as_u16(), 401);
+            assert!(context
+                .url
+                .ends_with("/rest/api/2/applicationrole/jira-software"));
+        }
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
 }
 
 #[test]
@@ -170,7 +176,7 @@ fn test_put_with_key_precondition_failed() {
     let client = Client::new(url, creds);
 
     match a.update(&client, None) {
-        Err(Error::PreconditionFailed) => assert!(true),
+        Err(Error::PreconditionFailed(_)) => assert!(true),
         _ => assert!(false),
     };
 }
@@ -192,7 +198,93 @@ fn test_put_with_key_forbidden() {
     let client = Client::new(url, creds);
 
     match a.update(&client, None) {
-        Err(Error::Forbidden) => assert!(true),
+        Err(Error::Forbidden(_)) => assert!(true),
         _ => assert!(false),
     };
 }
+
+#[test]
+fn test_put_with_key_server_error() {
+    let result = fs::read_to_string("tests/assets/v2/application_role.json")
+        .expect("Unable to read in JSON file");
+
+    let a: ApplicationRole = serde_json::from_str(&result).unwrap();
+
+    let _m = mock("PUT", "/rest/api/2/applicationrole/jira-software")
+        .with_status(502)
+        .with_header("content-type", "text/html")
+        .with_body(format!("<html>{}</html>", "Bad Gateway ".repeat(200)))
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    match a.update(&client, None) {
+        Err(Error::ServerError(context)) => {
+            assert_eq!(context.status.as_u16(), 502);
+            assert_eq!(context.content_type(), Some("text/html"));
+            assert!(context.body.starts_with("<html>Bad Gateway"));
+            assert_eq!(context.body.len(), jirac::BODY_SNIPPET_LEN);
+            assert!(context.errors.is_none());
+        }
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+}
+
+#[test]
+fn test_get_unexpected_content_type() {
+    let _m = mock("GET", "/rest/api/2/applicationrole/html")
+        .with_status(200)
+        .with_header("content-type", "text/html")
+        .with_body("<html>Please log in to the proxy</html>")
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    match ApplicationRole::from_key(&client, "html") {
+        Err(e @ Error::UnexpectedContentType(_)) => {
+            assert_eq!(e.status().map(|s| s.as_u16()), Some(200));
+            assert!(e.to_string().contains("text/html"));
+            assert!(e.to_string().contains("Please log in"));
+        }
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+}
+
+#[test]
+fn test_put_with_key_fault() {
+    let result = fs::read_to_string("tests/assets/v2/application_role.json")
+        .expect("Unable to read in JSON file");
+
+    let a: ApplicationRole = serde_json::from_str(&result).unwrap();
+
+    let _m = mock("PUT", "/rest/api/2/applicationrole/jira-software")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"errorMessages": ["Role is locked"]}"#)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    match a.update(&client, None) {
+        Err(Error::Fault {
+            code,
+            errors,
+            context,
+        }) => {
+            assert_eq!(code.as_u16(), 400);
+            assert_eq!(errors.error_messages, vec!["Role is locked"]);
+            assert!(errors.errors.is_empty());
+            assert_eq!(
+                context.errors.unwrap().error_messages,
+                errors.error_messages
+            );
+        }
+        other => panic!("unexpected result {:?}", other.map(|r| r.data)),
+    }
+}
@@ -0,0 +1,36 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::v2::{JiraDuration, TimeTrackingConfiguration};
+use jirac::Client;
+use jirac::Credentials;
+use mockito::mock;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_fetch_configuration() {
+    let _m = mock("GET", "/rest/api/2/configuration/timetracking/options")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"workingHoursPerDay": 6.0, "workingDaysPerWeek": 4.0,
+                "timeFormat": "pretty", "defaultUnit": "minute"}"#,
+        )
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let c = TimeTrackingConfiguration::fetch(&client).unwrap().data;
+
+    assert_eq!(c.seconds_per_week(), 4 * 6 * 3600);
+    assert_eq!(JiraDuration::from_seconds(108000).format(&c), "1w 1d");
+}
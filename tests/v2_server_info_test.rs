@@ -0,0 +1,99 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::v2::{ServerInfo, User};
+use jirac::Client;
+use jirac::Credentials;
+use jirac::{Capabilities, Deployment, Error, Feature};
+use mockito::{mock, Matcher};
+use std::fs;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_get_server_info() {
+    let result = fs::read_to_string("tests/assets/v2/server_info.json")
+        .expect("Unable to read in JSON file");
+
+    let _m = mock("GET", "/rest/api/2/serverInfo")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let info = ServerInfo::get(&client).unwrap().data;
+
+    assert_eq!(info.version_numbers, vec![8, 5, 4]);
+}
+
+#[test]
+fn test_detect_capabilities() {
+    let _m = mock("GET", "/rest/api/2/serverInfo")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"version": "1001.0.0-SNAPSHOT", "versionNumbers": [1001, 0, 0], "deploymentType": "Cloud"}"#)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let mut client = Client::new(url, creds);
+
+    let capabilities = client.detect_capabilities().unwrap();
+
+    assert_eq!(capabilities.deployment, Deployment::Cloud);
+    assert_eq!(client.capabilities().map(|c| c.deployment), Some(Deployment::Cloud));
+    assert!(client.is_cloud());
+}
+
+#[test]
+fn test_unsupported_fails_before_sending() {
+    let m = mock("GET", "/rest/api/2/user").expect(0).create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds)
+        .with_capabilities(Capabilities::new(Deployment::Cloud, vec![1001, 0, 0]));
+
+    match User::from_username(&client, "fred", vec![]) {
+        Err(Error::Unsupported {
+            feature: Feature::Usernames,
+            deployment: Deployment::Cloud,
+        }) => {}
+        Err(e) => panic!("expected Unsupported, got {}", e),
+        Ok(_) => panic!("expected Unsupported"),
+    }
+
+    m.assert();
+}
+
+#[test]
+fn test_search_picks_parameter_for_deployment() {
+    let _m = mock("GET", "/rest/api/2/user/search")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::UrlEncoded("query".into(), "mia+dev@example.com".into()))
+        .with_body("[]")
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds)
+        .with_capabilities(Capabilities::new(Deployment::Cloud, vec![1001, 0, 0]));
+
+    let users = User::search(&client, "mia+dev@example.com", None, None)
+        .unwrap()
+        .data;
+
+    assert!(users.is_empty());
+}
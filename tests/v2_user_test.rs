@@ -6,7 +6,7 @@ extern crate jirac;
 // ============================================================================
 // Use
 // ============================================================================
-use jirac::v2::{User, UserExpand};
+use jirac::v2::{User, UserExpand, UserMigration};
 use jirac::Client;
 use jirac::Credentials;
 use jirac::Resp;
@@ -43,3 +43,137 @@ fn test_get_user() {
 
     assert_eq!(user.name, "fred");
 }
+
+#[test]
+fn test_get_user_by_account_id() {
+    let _m = mock("GET", "/rest/api/2/user")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::UrlEncoded(
+            "accountId".into(),
+            "5b10a2844c20165700ede21g".into(),
+        ))
+        .with_body(r#"{"accountId": "5b10a2844c20165700ede21g", "accountType": "atlassian"}"#)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let user = User::from_account_id(&client, "5b10a2844c20165700ede21g", vec![]).unwrap();
+
+    assert_eq!(user.data.account_type, "atlassian");
+}
+
+#[test]
+fn test_get_users_in_bulk() {
+    let result =
+        fs::read_to_string("tests/assets/v2/user_bulk.json").expect("Unable to read in JSON file");
+
+    let _m = mock("GET", "/rest/api/2/user/bulk")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::Regex(
+            "accountId=5b10a2844c20165700ede21g&accountId=5b10ac8d82e05b22cc7d4ef5&".into(),
+        ))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let ids = vec!["5b10a2844c20165700ede21g", "5b10ac8d82e05b22cc7d4ef5"];
+    let users = User::from_account_ids(&client, ids, None).unwrap().data;
+
+    assert_eq!(users.users.len(), 2);
+    assert_eq!(users.users[1].account_type, "app");
+}
+
+#[test]
+fn test_migrate_usernames() {
+    let _m = mock("GET", "/rest/api/2/user/bulk/migration")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::Exact("username=fred&username=mia".into()))
+        .with_body(
+            r#"[{"username": "fred", "accountId": "5b10ac8d82e05b22cc7d4ef5"},
+                {"username": "mia", "accountId": "5b10a2844c20165700ede21g"}]"#,
+        )
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let migrations = UserMigration::from_usernames(&client, vec!["fred", "mia"])
+        .unwrap()
+        .data;
+    let map = UserMigration::to_map(&migrations);
+
+    assert_eq!(map.get("mia").unwrap(), "5b10a2844c20165700ede21g");
+}
+
+#[test]
+fn test_migrate_email_usernames() {
+    let m = mock("GET", "/rest/api/2/user/bulk/migration")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("username".into(), "a+b@example.com".into()),
+            Matcher::UrlEncoded("username".into(), "c&d #1".into()),
+        ]))
+        .with_body(r#"[{"username": "a+b@example.com", "accountId": "5b10ac8d82e05b22cc7d4ef6"}]"#)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let migrations = UserMigration::from_usernames(&client, vec!["a+b@example.com", "c&d #1"])
+        .unwrap()
+        .data;
+
+    assert_eq!(migrations[0].account_id, "5b10ac8d82e05b22cc7d4ef6");
+    m.assert();
+}
+
+#[test]
+fn test_search_by_email_query() {
+    let m = mock("GET", Matcher::Regex("^/rest/api/2/user/search".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::UrlEncoded(
+            "query".into(),
+            "a+b@example.com".into(),
+        ))
+        .with_body("[]")
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    User::search_by_query(&client, "a+b@example.com", None).unwrap();
+    m.assert();
+}
+
+#[test]
+fn test_search_encodes_username() {
+    let m = mock("GET", Matcher::Regex("^/rest/api/2/user/search".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::UrlEncoded(
+            "username".into(),
+            "j+doe&co".into(),
+        ))
+        .with_body("[]")
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    User::search(&client, "j+doe&co", None, None).unwrap();
+    m.assert();
+}
@@ -0,0 +1,159 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::v3::{BulkChangelogRequest, Changelog, ContextReference, ExpressionContext};
+use jirac::v3::{ExpressionResult, PermissionCheck, PermissionCheckRequest, Project};
+use jirac::v3::{ProjectPermissionsCheck, User};
+use jirac::Client;
+use jirac::Credentials;
+use mockito::{mock, Matcher};
+use serde_json::json;
+use std::fs;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_get_user_by_account_id() {
+    let result =
+        fs::read_to_string("tests/assets/v3/user.json").expect("Unable to read in JSON file");
+
+    let _m = mock("GET", "/rest/api/3/user")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::UrlEncoded(
+            "accountId".into(),
+            "5b10a2844c20165700ede21g".into(),
+        ))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let user = User::from_account_id(&client, "5b10a2844c20165700ede21g", vec![]).unwrap();
+
+    assert_eq!(user.data.display_name, "Mia Krystof");
+}
+
+#[test]
+fn test_search_projects() {
+    let result = fs::read_to_string("tests/assets/v3/project_search.json")
+        .expect("Unable to read in JSON file");
+
+    let _m = mock("GET", "/rest/api/3/project/search")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("query".into(), "ex".into()),
+            Matcher::UrlEncoded("startAt".into(), "0".into()),
+        ]))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let page = Project::search(&client, Some("ex"), None, None, None)
+        .unwrap()
+        .data;
+
+    assert_eq!(page.values.len(), 2);
+    assert!(page.next().is_none());
+}
+
+#[test]
+fn test_bulk_fetch_changelogs() {
+    let result = fs::read_to_string("tests/assets/v3/changelog_bulk.json")
+        .expect("Unable to read in JSON file");
+
+    let m = mock("POST", "/rest/api/3/changelog/bulkfetch")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::Json(json!({
+            "issueIdsOrKeys": ["EX-1"],
+            "fieldIds": ["status"]
+        })))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let mut request = BulkChangelogRequest::new(vec!["EX-1"]);
+    request.field_ids = vec!["status".to_string()];
+
+    let bulk = Changelog::bulk_fetch(&client, &request).unwrap().data;
+
+    assert_eq!(bulk.issue_changelogs[0].change_histories.len(), 1);
+    m.assert();
+}
+
+#[test]
+fn test_evaluate_expression() {
+    let m = mock("POST", "/rest/api/3/expression/eval")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::Json(json!({
+            "expression": "issue.summary",
+            "context": {"issue": {"key": "EX-1"}}
+        })))
+        .with_body(r#"{"value": "Order entry fails", "meta": {"complexity": {}}}"#)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let context = ExpressionContext {
+        issue: Some(ContextReference::key("EX-1")),
+        ..Default::default()
+    };
+    let result = ExpressionResult::evaluate(&client, "issue.summary", context).unwrap();
+
+    assert_eq!(result.data.value, "Order entry fails");
+    m.assert();
+}
+
+#[test]
+fn test_check_permissions() {
+    let result = fs::read_to_string("tests/assets/v3/permissions_check.json")
+        .expect("Unable to read in JSON file");
+
+    let m = mock("POST", "/rest/api/3/permissions/check")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::Json(json!({
+            "globalPermissions": ["ADMINISTER"],
+            "projectPermissions": [{"permissions": ["EDIT_ISSUES"], "projects": [10001]}]
+        })))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let request = PermissionCheckRequest {
+        global_permissions: vec!["ADMINISTER".to_string()],
+        project_permissions: vec![ProjectPermissionsCheck {
+            permissions: vec!["EDIT_ISSUES".to_string()],
+            projects: vec![10001],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let check = PermissionCheck::check(&client, &request).unwrap().data;
+
+    assert!(check.has_project_permission("EDIT_ISSUES", 10001));
+    m.assert();
+}
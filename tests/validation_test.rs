@@ -0,0 +1,79 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::{Client, Credentials, ValidationKind};
+use mockito::mock;
+use serde_json::{json, Value};
+use std::fs;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_validation_errors() {
+    let create = mock("POST", "/rest/api/2/issue")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "errorMessages": [],
+                "errors": {
+                    "customfield_10402": "Field 'customfield_10402' cannot be set. It is not on the appropriate screen, or unknown.",
+                    "summary": "You must specify a summary of the issue."
+                }
+            })
+            .to_string(),
+        )
+        .create();
+    let fields = mock("GET", "/rest/api/2/field")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(fs::read_to_string("tests/assets/v2/field.json").unwrap())
+        .expect(1)
+        .create();
+
+    let client = Client::new(mockito::server_url(), Credentials::Pat("token".into()));
+    let err = client
+        .post::<_, Value>("api/2/issue", json!({"fields": {"customfield_10402": 3}}))
+        .map(|r| r.data)
+        .unwrap_err();
+
+    let problems = err.validation_errors(&client).unwrap();
+    assert_eq!(problems.len(), 2);
+
+    assert_eq!(problems[0].field_id, "customfield_10402");
+    assert_eq!(problems[0].field_name.as_deref(), Some("Story Points"));
+    assert_eq!(problems[0].kind, ValidationKind::NotOnScreen);
+
+    assert_eq!(problems[1].field(), "Summary");
+    assert_eq!(problems[1].kind, ValidationKind::Required);
+    assert_eq!(problems[1].to_string(), "\"Summary\" is required");
+
+    create.assert();
+    fields.assert();
+}
+
+#[test]
+fn test_no_validation_errors() {
+    let m = mock("GET", "/rest/api/2/issue/VAL-1")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"errorMessages": ["Issue does not exist"], "errors": {}}"#)
+        .create();
+
+    let client = Client::new(mockito::server_url(), Credentials::Pat("token".into()));
+    let err = client
+        .get::<Value>("api/2/issue/VAL-1")
+        .map(|r| r.data)
+        .unwrap_err();
+
+    // Field metadata is only fetched when there are field errors
+    assert!(err.validation_errors(&client).unwrap().is_empty());
+    m.assert();
+}
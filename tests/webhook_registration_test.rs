@@ -0,0 +1,181 @@
+// ============================================================================
+// External Crates
+// ============================================================================
+extern crate jirac;
+extern crate mockito;
+
+// ============================================================================
+// Use
+// ============================================================================
+use jirac::webhook::{DynamicWebhook, Webhook, WebhookDetails, DYNAMIC_WEBHOOK_LIFETIME};
+use jirac::Client;
+use jirac::Credentials;
+use mockito::{mock, Matcher};
+use serde_json::json;
+use std::fs;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+// ============================================================================
+// Tests
+// ============================================================================
+#[test]
+fn test_create_server_webhook() {
+    let result = fs::read_to_string("tests/assets/webhook/webhook.json")
+        .expect("Unable to read in JSON file");
+
+    let m = mock("POST", "/rest/webhooks/1.0/webhook")
+        .with_status(201)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::PartialJson(json!({
+            "name": "my first webhook via rest",
+            "events": ["jira:issue_created", "jira:issue_updated"],
+            "filters": {
+                "issue-related-events-section": "Project = JRA AND resolution = Fixed"
+            }
+        })))
+        .with_body(result)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_basic("test", "test").unwrap();
+    let client = Client::new(url, creds);
+
+    let mut w = Webhook::new(
+        "my first webhook via rest",
+        "http://www.example.com/webhooks",
+        vec![
+            "jira:issue_created".to_string(),
+            "jira:issue_updated".to_string(),
+        ],
+    );
+    w.set_jql("Project = JRA AND resolution = Fixed");
+
+    let created = w.create(&client).unwrap();
+
+    assert_eq!(created.data.id(), Some(1));
+    m.assert();
+}
+
+#[test]
+fn test_register_dynamic_webhooks() {
+    let _m = mock("POST", "/rest/api/2/webhook")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::Json(json!({
+            "url": "https://example.com/hook",
+            "webhooks": [
+                { "events": ["jira:issue_created"], "jqlFilter": "project = PRJ" },
+                { "events": ["jira:issue_created"], "jqlFilter": "project = ???" }
+            ]
+        })))
+        .with_body(
+            r#"{"webhookRegistrationResult": [
+                {"createdWebhookId": 1000},
+                {"errors": ["The clause project is unsupported"]}
+            ]}"#,
+        )
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_oauth("token").unwrap();
+    let client = Client::new(url, creds);
+
+    let details = vec![
+        WebhookDetails {
+            events: vec!["jira:issue_created".to_string()],
+            jql_filter: "project = PRJ".to_string(),
+        },
+        WebhookDetails {
+            events: vec!["jira:issue_created".to_string()],
+            jql_filter: "project = ???".to_string(),
+        },
+    ];
+
+    let r = DynamicWebhook::register(&client, "https://example.com/hook", details).unwrap();
+
+    assert_eq!(r[0].created_webhook_id, Some(1000));
+    assert_eq!(r[1].errors.len(), 1);
+}
+
+#[test]
+fn test_refresh_expiring() {
+    let result = fs::read_to_string("tests/assets/webhook/dynamic_webhooks.json")
+        .expect("Unable to read in JSON file");
+
+    let list = mock("GET", "/rest/api/2/webhook")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::Any)
+        .with_body(result)
+        .create();
+
+    let refresh = mock("PUT", "/rest/api/2/webhook/refresh")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_body(Matcher::Json(json!({ "webhookIds": [10000, 10001] })))
+        .with_body(r#"{"expirationDate": 1592000000000}"#)
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_oauth("token").unwrap();
+    let client = Client::new(url, creds);
+
+    let ids = DynamicWebhook::refresh_expiring(&client, DYNAMIC_WEBHOOK_LIFETIME).unwrap();
+
+    assert_eq!(ids, vec![10000, 10001]);
+    list.assert();
+    refresh.assert();
+}
+
+#[test]
+fn test_refresher_reports_errors() {
+    // Nothing is mocked under this path so every refresh fails
+    let url = format!("{}/refresher", mockito::server_url());
+    let creds = Credentials::new_oauth("token").unwrap();
+    let client = Client::new(url, creds);
+    let (tx, rx) = channel();
+
+    let refresher = DynamicWebhook::spawn_refresher_with(
+        &client,
+        Duration::from_secs(60),
+        DYNAMIC_WEBHOOK_LIFETIME,
+        move |e| tx.send(e.to_string()).unwrap(),
+    );
+
+    assert!(rx.recv_timeout(Duration::from_secs(10)).is_ok());
+    refresher.stop();
+}
+
+#[test]
+fn test_failed() {
+    let _m = mock("GET", "/rest/api/2/webhook/failed")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("maxResults".into(), "10".into()),
+            Matcher::UrlEncoded("after".into(), "1573118132000".into()),
+        ]))
+        .with_body(
+            r#"{
+                "values": [{
+                    "id": "1",
+                    "body": "{\"data\":\"webhook data\"}",
+                    "url": "https://example.com",
+                    "failureTime": 1573118132000
+                }],
+                "maxResults": 10,
+                "next": "https://your-domain.atlassian.net/rest/api/2/webhook/failed?failedAfter=1573118132000&maxResults=10"
+            }"#,
+        )
+        .create();
+
+    let url = &mockito::server_url();
+    let creds = Credentials::new_oauth("token").unwrap();
+    let client = Client::new(url, creds);
+
+    let f = DynamicWebhook::failed(&client, 10, Some(1573118132000)).unwrap();
+
+    assert_eq!(f.data.values[0].failure_time, 1573118132000);
+    assert!(f.data.next.is_some());
+}